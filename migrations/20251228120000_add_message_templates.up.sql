@@ -0,0 +1,5 @@
+CREATE TABLE message_templates (
+    name TEXT PRIMARY KEY,
+    body TEXT NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,6 @@
+DELETE FROM message_templates WHERE locale <> 'en';
+ALTER TABLE message_templates DROP CONSTRAINT message_templates_pkey;
+ALTER TABLE message_templates DROP COLUMN locale;
+ALTER TABLE message_templates ADD PRIMARY KEY (name);
+
+ALTER TABLE notification_settings DROP COLUMN locale;
@@ -0,0 +1,5 @@
+ALTER TABLE notification_settings ADD COLUMN locale TEXT NOT NULL DEFAULT 'en';
+
+ALTER TABLE message_templates ADD COLUMN locale TEXT NOT NULL DEFAULT 'en';
+ALTER TABLE message_templates DROP CONSTRAINT message_templates_pkey;
+ALTER TABLE message_templates ADD PRIMARY KEY (name, locale);
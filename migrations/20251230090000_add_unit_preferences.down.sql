@@ -0,0 +1,4 @@
+ALTER TABLE notification_settings
+    DROP COLUMN temperature_unit,
+    DROP COLUMN pressure_unit,
+    DROP COLUMN volume_unit;
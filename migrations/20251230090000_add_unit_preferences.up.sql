@@ -0,0 +1,4 @@
+ALTER TABLE notification_settings
+    ADD COLUMN temperature_unit TEXT NOT NULL DEFAULT 'celsius',
+    ADD COLUMN pressure_unit TEXT NOT NULL DEFAULT 'hpa',
+    ADD COLUMN volume_unit TEXT NOT NULL DEFAULT 'liters';
@@ -0,0 +1,11 @@
+CREATE TABLE scheduled_outages (
+    id SERIAL PRIMARY KEY,
+    source TEXT NOT NULL,
+    starts_at TIMESTAMP NOT NULL,
+    ends_at TIMESTAMP NOT NULL,
+    warned_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (source, starts_at, ends_at)
+);
+
+CREATE INDEX idx_scheduled_outages_starts_at ON scheduled_outages(starts_at);
@@ -0,0 +1 @@
+CREATE INDEX idx_sensor_data_created_at ON sensor_data(created_at);
@@ -0,0 +1,6 @@
+ALTER TABLE power_outages
+    DROP COLUMN cause;
+
+ALTER TABLE sensor_data
+    DROP COLUMN uptime_secs,
+    DROP COLUMN boot_count;
@@ -0,0 +1,6 @@
+ALTER TABLE sensor_data
+    ADD COLUMN uptime_secs BIGINT,
+    ADD COLUMN boot_count BIGINT;
+
+ALTER TABLE power_outages
+    ADD COLUMN cause TEXT NOT NULL DEFAULT 'unknown';
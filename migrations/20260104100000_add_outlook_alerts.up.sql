@@ -0,0 +1,2 @@
+ALTER TABLE notification_settings
+    ADD COLUMN outlook_alerts BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,6 @@
+CREATE TABLE power_subscribers (
+    telegram_user_id BIGINT PRIMARY KEY,
+    username TEXT,
+    locale TEXT NOT NULL DEFAULT 'en',
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
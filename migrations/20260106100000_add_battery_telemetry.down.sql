@@ -0,0 +1,6 @@
+ALTER TABLE power_outages
+    DROP COLUMN battery_alert_percent;
+
+ALTER TABLE sensor_data
+    DROP COLUMN supply_voltage,
+    DROP COLUMN battery_percent;
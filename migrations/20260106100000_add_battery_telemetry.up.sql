@@ -0,0 +1,6 @@
+ALTER TABLE sensor_data
+    ADD COLUMN supply_voltage FLOAT,
+    ADD COLUMN battery_percent FLOAT;
+
+ALTER TABLE power_outages
+    ADD COLUMN battery_alert_percent FLOAT;
@@ -0,0 +1,5 @@
+CREATE TABLE heartbeats (
+    source TEXT PRIMARY KEY,
+    last_seen_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    resumed_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
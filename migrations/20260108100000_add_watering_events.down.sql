@@ -0,0 +1 @@
+DROP TABLE IF EXISTS watering_events;
@@ -0,0 +1,7 @@
+CREATE TABLE watering_events (
+    id SERIAL PRIMARY KEY,
+    duration_secs INTEGER NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_watering_events_created_at ON watering_events (created_at);
@@ -0,0 +1,13 @@
+CREATE TABLE bot_dialogues (
+    chat_id BIGINT PRIMARY KEY,
+    state TEXT NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+-- Private chats share the user's id, so existing users keep their access
+INSERT INTO bot_dialogues (chat_id, state)
+SELECT telegram_user_id, '"Subscriber"' FROM power_subscribers;
+
+INSERT INTO bot_dialogues (chat_id, state)
+SELECT telegram_user_id, '"Authorized"' FROM authorized_users
+ON CONFLICT (chat_id) DO UPDATE SET state = excluded.state;
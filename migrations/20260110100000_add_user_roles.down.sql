@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS bot_settings;
+ALTER TABLE authorized_users DROP COLUMN IF EXISTS role;
@@ -0,0 +1,12 @@
+-- Everyone authorized so far could run the pump; the earliest user administers
+ALTER TABLE authorized_users ADD COLUMN role TEXT NOT NULL DEFAULT 'operator';
+ALTER TABLE authorized_users ALTER COLUMN role SET DEFAULT 'viewer';
+
+UPDATE authorized_users SET role = 'admin'
+WHERE id = (SELECT MIN(id) FROM authorized_users);
+
+CREATE TABLE bot_settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
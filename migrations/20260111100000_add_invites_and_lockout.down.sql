@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS auth_attempts;
+DROP TABLE IF EXISTS invites;
+ALTER TABLE authorized_users DROP COLUMN IF EXISTS expires_at;
@@ -0,0 +1,20 @@
+-- Guests invited with limited access lose it at this time
+ALTER TABLE authorized_users ADD COLUMN expires_at TIMESTAMP;
+
+CREATE TABLE invites (
+    token TEXT PRIMARY KEY,
+    role TEXT NOT NULL,
+    max_uses INTEGER NOT NULL,
+    uses INTEGER NOT NULL DEFAULT 0,
+    access_hours INTEGER,
+    expires_at TIMESTAMP NOT NULL,
+    created_by BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE auth_attempts (
+    telegram_user_id BIGINT PRIMARY KEY,
+    failures INTEGER NOT NULL DEFAULT 0,
+    locked_until TIMESTAMP,
+    last_attempt_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
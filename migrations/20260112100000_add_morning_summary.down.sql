@@ -0,0 +1,5 @@
+ALTER TABLE notification_settings
+    DROP COLUMN IF EXISTS timezone,
+    DROP COLUMN IF EXISTS morning_summary_sent_on,
+    DROP COLUMN IF EXISTS morning_summary_hour,
+    DROP COLUMN IF EXISTS morning_summary;
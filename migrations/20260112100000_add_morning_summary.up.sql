@@ -0,0 +1,6 @@
+ALTER TABLE notification_settings
+    ADD COLUMN morning_summary BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN morning_summary_hour SMALLINT NOT NULL DEFAULT 8,
+    -- Local date of the last summary, so each day gets one
+    ADD COLUMN morning_summary_sent_on DATE,
+    ADD COLUMN timezone TEXT NOT NULL DEFAULT 'Europe/Kyiv';
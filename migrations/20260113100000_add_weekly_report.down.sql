@@ -0,0 +1,5 @@
+ALTER TABLE notification_settings
+    DROP COLUMN IF EXISTS weekly_report_sent_on,
+    DROP COLUMN IF EXISTS weekly_report;
+
+DROP TABLE IF EXISTS alert_incidents;
@@ -0,0 +1,12 @@
+-- One row each time an alert condition starts, for the weekly report
+CREATE TABLE alert_incidents (
+    id SERIAL PRIMARY KEY,
+    alert_kind TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_alert_incidents_created_at ON alert_incidents (created_at);
+
+ALTER TABLE notification_settings
+    ADD COLUMN weekly_report BOOLEAN NOT NULL DEFAULT true,
+    ADD COLUMN weekly_report_sent_on DATE;
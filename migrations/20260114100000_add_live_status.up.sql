@@ -0,0 +1,9 @@
+-- Pinned status messages kept up to date with the latest reading, one per chat
+CREATE TABLE live_status_messages (
+    chat_id BIGINT PRIMARY KEY,
+    message_id INTEGER NOT NULL,
+    telegram_user_id BIGINT NOT NULL,
+    reading_id INTEGER,
+    offline BOOLEAN NOT NULL DEFAULT false,
+    edited_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,8 @@
+-- Group chats linked by an admin; their members can read sensors and get alerts there
+CREATE TABLE authorized_chats (
+    chat_id BIGINT PRIMARY KEY,
+    title TEXT,
+    locale TEXT NOT NULL DEFAULT 'en',
+    authorized_by BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
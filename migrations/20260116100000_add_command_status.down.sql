@@ -0,0 +1,6 @@
+DELETE FROM device_commands WHERE status <> 'queued';
+DROP INDEX IF EXISTS idx_device_commands_status;
+ALTER TABLE device_commands
+    DROP COLUMN status,
+    DROP COLUMN queued_by,
+    DROP COLUMN updated_at;
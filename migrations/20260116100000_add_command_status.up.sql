@@ -0,0 +1,7 @@
+-- Keep picked-up and cancelled commands around so the bot can show the queue's history
+ALTER TABLE device_commands
+    ADD COLUMN status TEXT NOT NULL DEFAULT 'queued',
+    ADD COLUMN queued_by BIGINT,
+    ADD COLUMN updated_at TIMESTAMPTZ;
+
+CREATE INDEX idx_device_commands_status ON device_commands (status, created_at);
@@ -0,0 +1,12 @@
+-- Personal access tokens for scripts and shortcuts; only a SHA-256 of each token is kept
+CREATE TABLE api_tokens (
+    id SERIAL PRIMARY KEY,
+    telegram_user_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    scopes TEXT[] NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    last_used_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (telegram_user_id, name)
+);
@@ -0,0 +1 @@
+ALTER TABLE power_outages DROP COLUMN on_battery;
@@ -0,0 +1,10 @@
+-- Outages seen on the UPS battery have no gap in readings, so the gap backfill must keep them.
+-- Existing ones started with a reading below the mains voltage (config::battery::MAINS_MIN_VOLTAGE)
+ALTER TABLE power_outages ADD COLUMN on_battery BOOLEAN NOT NULL DEFAULT false;
+
+UPDATE power_outages po SET on_battery = true
+WHERE po.battery_alert_percent IS NOT NULL
+   OR EXISTS (
+       SELECT 1 FROM sensor_data sd
+       WHERE sd.created_at = po.started_at AND sd.supply_voltage < 11.0
+   );
@@ -2,27 +2,30 @@ use std::sync::Arc;
 
 use teloxide::{prelude::*, types::ChatId};
 use time::OffsetDateTime;
-use time_tz::{timezones::db::europe::KYIV, OffsetDateTimeExt};
+use time_tz::OffsetDateTimeExt;
 
 use crate::config::ALERT_COOLDOWN_SECS;
-use crate::db::{AlertKind, Db};
+use crate::db::{AlertKind, Db, Locale, NotificationSettings};
+use crate::services::user_timezone;
+use crate::templates::{Localized, Templates};
 
 #[derive(Clone)]
 pub struct Alerter {
     bot: Arc<Bot>,
     db: Db,
+    templates: Arc<Templates>,
 }
 
 impl Alerter {
-    pub fn new(bot: Arc<Bot>, db: Db) -> Self {
-        Self { bot, db }
+    pub fn new(bot: Arc<Bot>, db: Db, templates: Arc<Templates>) -> Self {
+        Self { bot, db, templates }
     }
 
     pub async fn check_and_alert(
         &self,
         kind: AlertKind,
         triggered: bool,
-        message: &str,
+        message: impl Fn(&Localized<'_>) -> String,
     ) -> anyhow::Result<()> {
         let state = self.db.get_alert_state(kind).await?;
         let was_active = state.as_ref().map(|s| s.active).unwrap_or(false);
@@ -47,6 +50,10 @@ impl Alerter {
             .set_alert_state(kind, triggered, should_send)
             .await?;
 
+        if triggered && !was_active {
+            self.db.record_alert_incident(kind).await?;
+        }
+
         if should_send {
             self.broadcast_alert(kind, message).await?;
         }
@@ -54,68 +61,106 @@ impl Alerter {
         Ok(())
     }
 
-    /// Broadcast alert respecting user preferences
-    async fn broadcast_alert(&self, kind: AlertKind, message: &str) -> anyhow::Result<()> {
+    /// Broadcast alert respecting user preferences, rendered in each user's locale
+    async fn broadcast_alert(
+        &self,
+        kind: AlertKind,
+        message: impl Fn(&Localized<'_>) -> String,
+    ) -> anyhow::Result<()> {
         let user_ids = self.db.get_users_for_alert(kind).await?;
 
         for user_id in user_ids {
-            if self.is_quiet_hours(user_id).await {
+            let settings = self
+                .db
+                .get_notification_settings(user_id)
+                .await
+                .unwrap_or_default();
+            if is_quiet_hours(&settings) {
                 continue;
             }
-            if let Err(e) = self.bot.send_message(ChatId(user_id), message).await {
+            let t = self.templates.for_user(&settings);
+            if let Err(e) = self.send(&t, user_id, &message(&t)).await {
                 eprintln!("Failed to send alert to {}: {}", user_id, e);
             }
         }
+        self.broadcast_to_chats(&message).await?;
 
         Ok(())
     }
 
     /// Broadcast power alert respecting user preferences
-    pub async fn broadcast_power_alert(&self, message: &str) -> anyhow::Result<()> {
+    pub async fn broadcast_power_alert(
+        &self,
+        message: impl Fn(&Localized<'_>) -> String,
+    ) -> anyhow::Result<()> {
         let user_ids = self.db.get_users_for_power_alert().await?;
-
-        for user_id in user_ids {
-            if self.is_quiet_hours(user_id).await {
-                continue;
-            }
-            if let Err(e) = self.bot.send_message(ChatId(user_id), message).await {
-                eprintln!("Failed to send power alert to {}: {}", user_id, e);
+        self.broadcast_to(user_ids, &message).await;
+        self.broadcast_to_chats(&message).await?;
+
+        for subscriber in self.db.get_power_subscribers().await? {
+            let t = self.templates.locale(subscriber.locale);
+            if let Err(e) = self
+                .send(&t, subscriber.telegram_user_id, &message(&t))
+                .await
+            {
+                eprintln!(
+                    "Failed to send power alert to subscriber {}: {}",
+                    subscriber.telegram_user_id, e
+                );
             }
         }
 
         Ok(())
     }
 
-    /// Check if it's quiet hours for a user
-    async fn is_quiet_hours(&self, user_id: i64) -> bool {
-        let settings = match self.db.get_notification_settings(user_id).await {
-            Ok(s) => s,
-            Err(_) => return false,
-        };
+    /// Broadcast an outage heads-up to users who opted in
+    pub async fn broadcast_outlook_alert(
+        &self,
+        message: impl Fn(&Localized<'_>) -> String,
+    ) -> anyhow::Result<()> {
+        let user_ids = self.db.get_users_for_outlook_alert().await?;
+        self.broadcast_to(user_ids, message).await;
+        Ok(())
+    }
 
-        if !settings.quiet_hours_enabled {
-            return false;
+    async fn broadcast_to(&self, user_ids: Vec<i64>, message: impl Fn(&Localized<'_>) -> String) {
+        for user_id in user_ids {
+            let settings = self
+                .db
+                .get_notification_settings(user_id)
+                .await
+                .unwrap_or_default();
+            if is_quiet_hours(&settings) {
+                continue;
+            }
+            let t = self.templates.for_user(&settings);
+            if let Err(e) = self.send(&t, user_id, &message(&t)).await {
+                eprintln!("Failed to send power alert to {}: {}", user_id, e);
+            }
         }
+    }
 
-        let now = OffsetDateTime::now_utc().to_timezone(KYIV);
-        let hour = now.hour() as i16;
-
-        let start = settings.quiet_hours_start;
-        let end = settings.quiet_hours_end;
-
-        if start > end {
-            hour >= start || hour < end
-        } else {
-            hour >= start && hour < end
+    /// Alerts every linked group chat in its language; quiet hours are personal, so not applied
+    async fn broadcast_to_chats(
+        &self,
+        message: impl Fn(&Localized<'_>) -> String,
+    ) -> anyhow::Result<()> {
+        for chat in self.db.get_authorized_chats().await? {
+            let t = self.templates.locale(chat.locale);
+            if let Err(e) = self.send(&t, chat.chat_id, &message(&t)).await {
+                eprintln!("Failed to send alert to chat {}: {}", chat.chat_id, e);
+            }
         }
+        Ok(())
     }
 
     #[allow(dead_code)]
     pub async fn broadcast(&self, message: &str) -> anyhow::Result<()> {
         let user_ids = self.db.get_authorized_user_ids().await?;
+        let t = self.templates.locale(Locale::default());
 
         for user_id in user_ids {
-            if let Err(e) = self.bot.send_message(ChatId(user_id), message).await {
+            if let Err(e) = self.send(&t, user_id, message).await {
                 eprintln!("Failed to send alert to {}: {}", user_id, e);
             }
         }
@@ -125,7 +170,37 @@ impl Alerter {
 
     #[allow(dead_code)]
     pub async fn send_to(&self, user_id: i64, message: &str) -> anyhow::Result<()> {
-        self.bot.send_message(ChatId(user_id), message).await?;
+        let t = self.templates.locale(Locale::default());
+        self.send(&t, user_id, message).await?;
         Ok(())
     }
+
+    async fn send(
+        &self,
+        t: &Localized<'_>,
+        user_id: i64,
+        message: &str,
+    ) -> Result<Message, teloxide::RequestError> {
+        t.send_message(&self.bot, ChatId(user_id), message.to_string())
+            .await
+    }
+}
+
+/// Check if it's quiet hours for a user, in their time zone
+fn is_quiet_hours(settings: &NotificationSettings) -> bool {
+    if !settings.quiet_hours_enabled {
+        return false;
+    }
+
+    let now = OffsetDateTime::now_utc().to_timezone(user_timezone(&settings.timezone));
+    let hour = now.hour() as i16;
+
+    let start = settings.quiet_hours_start;
+    let end = settings.quiet_hours_end;
+
+    if start > end {
+        hour >= start || hour < end
+    } else {
+        hour >= start && hour < end
+    }
 }
@@ -0,0 +1,71 @@
+use axum::{
+    extract::FromRequestParts,
+    http::{header, request::Parts, HeaderMap, StatusCode},
+};
+
+use crate::db::{ApiScope, TokenGrant};
+use crate::AppState;
+
+/// Who is calling `/api`: the device with the shared key, or a script with a
+/// personal access token sent as `Authorization: Bearer <token>`
+pub enum ApiClient {
+    Device,
+    Token(TokenGrant),
+}
+
+impl ApiClient {
+    /// The device key can do anything; tokens only what their scopes allow
+    pub fn allows(&self, scope: ApiScope) -> bool {
+        match self {
+            ApiClient::Device => true,
+            ApiClient::Token(grant) => grant.allows(scope),
+        }
+    }
+
+    /// Only the device posts readings and takes queued commands, whatever a
+    /// token's scopes
+    pub fn is_device(&self) -> bool {
+        matches!(self, ApiClient::Device)
+    }
+
+    /// Telegram user behind a token
+    pub fn user_id(&self) -> Option<i64> {
+        match self {
+            ApiClient::Device => None,
+            ApiClient::Token(grant) => Some(grant.telegram_user_id),
+        }
+    }
+}
+
+impl FromRequestParts<AppState> for ApiClient {
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, StatusCode> {
+        if check_api_key(&parts.headers, &state.api_key) {
+            return Ok(ApiClient::Device);
+        }
+
+        let token = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .ok_or(StatusCode::UNAUTHORIZED)?;
+        match state.db.authenticate_api_token(token.trim()).await {
+            Ok(Some(grant)) => Ok(ApiClient::Token(grant)),
+            Ok(None) => Err(StatusCode::UNAUTHORIZED),
+            Err(e) => {
+                eprintln!("Failed to check API token: {:?}", e);
+                Err(StatusCode::INTERNAL_SERVER_ERROR)
+            }
+        }
+    }
+}
+
+fn check_api_key(headers: &HeaderMap, expected: &str) -> bool {
+    headers
+        .get("X-Api-Key")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v == expected)
+        .unwrap_or(false)
+}
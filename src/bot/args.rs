@@ -0,0 +1,165 @@
+use time::{Date, Duration, Month};
+
+use crate::db::ApiScope;
+use crate::services::ChartMetric;
+
+/// Parses durations like `45`, `45s`, `1m 20s`, `1хв 20с` or `1:20` into seconds
+pub fn parse_duration(text: &str) -> Option<i32> {
+    let text = text.trim().to_lowercase();
+
+    if let Some((mins, secs)) = text.split_once(':') {
+        let mins: i32 = mins.trim().parse().ok()?;
+        let secs: i32 = secs.trim().parse().ok()?;
+        return (secs < 60).then(|| mins.checked_mul(60)?.checked_add(secs))?;
+    }
+
+    let mut rest = text.as_str();
+    let mut total: Option<i32> = None;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let value: i32 = rest[..digits].parse().ok()?;
+        rest = rest[digits..].trim_start();
+
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let multiplier = match &rest[..unit_len] {
+            // A bare number is only allowed on its own
+            "" if total.is_none() && rest.is_empty() => 1,
+            "s" | "sec" | "secs" | "second" | "seconds" | "с" | "сек" => 1,
+            "m" | "min" | "mins" | "minute" | "minutes" | "хв" => 60,
+            _ => return None,
+        };
+        rest = rest[unit_len..].trim_start();
+
+        total = Some(
+            total
+                .unwrap_or(0)
+                .checked_add(value.checked_mul(multiplier)?)?,
+        );
+    }
+
+    total
+}
+
+/// Parses how far back to look, like `24h`, `7d` or `2w`, up to a year
+pub fn parse_span(text: &str) -> Option<Duration> {
+    let text = text.trim().to_lowercase();
+    let unit_start = text.find(|c: char| !c.is_ascii_digit())?;
+    let value: i64 = text[..unit_start].parse().ok()?;
+    let unit_secs: i64 = match text[unit_start..].trim() {
+        "h" | "год" => 3600,
+        "d" | "д" => 24 * 3600,
+        "w" | "т" => 7 * 24 * 3600,
+        _ => return None,
+    };
+    let span = Duration::seconds(value.checked_mul(unit_secs)?);
+    (value > 0 && span <= Duration::days(366)).then_some(span)
+}
+
+/// Parses a month like `2025-12` into its first day and the first day of the next
+pub fn parse_month(text: &str) -> Option<(Date, Date)> {
+    let (year, month) = text.trim().split_once('-')?;
+    let month = Month::try_from(month.parse::<u8>().ok()?).ok()?;
+    let first = Date::from_calendar_date(year.parse().ok()?, month, 1).ok()?;
+    let next = first.checked_add(Duration::days(31))?.replace_day(1).ok()?;
+    Some((first, next))
+}
+
+/// Metric by its full name or a short alias like `soil` or `temp`
+pub fn parse_metric(text: &str) -> Option<ChartMetric> {
+    let text = text.trim().to_lowercase();
+    ChartMetric::from_name(&text).or(match text.as_str() {
+        "temp" => Some(ChartMetric::Temperature),
+        "soil" => Some(ChartMetric::SoilMoisture),
+        "water" | "tank" => Some(ChartMetric::WaterLevel),
+        _ => None,
+    })
+}
+
+/// Comma-separated token scopes like `read,water`
+pub fn parse_scopes(text: &str) -> Option<Vec<ApiScope>> {
+    let mut scopes = Vec::new();
+    for name in text.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        let scope = ApiScope::from_name(&name.to_lowercase())?;
+        if !scopes.contains(&scope) {
+            scopes.push(scope);
+        }
+    }
+    (!scopes.is_empty()).then_some(scopes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("45"), Some(45));
+        assert_eq!(parse_duration("45s"), Some(45));
+        assert_eq!(parse_duration("1m 20s"), Some(80));
+        assert_eq!(parse_duration("1хв 20с"), Some(80));
+        assert_eq!(parse_duration("1:20"), Some(80));
+        assert_eq!(parse_duration("1:60"), None);
+        assert_eq!(parse_duration("1m 20"), None);
+        assert_eq!(parse_duration("5h"), None);
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("99999999m"), None);
+        assert_eq!(parse_duration("99999999:00"), None);
+    }
+
+    #[test]
+    fn spans() {
+        assert_eq!(parse_span("24h"), Some(Duration::hours(24)));
+        assert_eq!(parse_span("7д"), Some(Duration::days(7)));
+        assert_eq!(parse_span("2w"), Some(Duration::weeks(2)));
+        assert_eq!(parse_span("366d"), Some(Duration::days(366)));
+        assert_eq!(parse_span("367d"), None);
+        assert_eq!(parse_span("0h"), None);
+        assert_eq!(parse_span("d"), None);
+        assert_eq!(parse_span("5y"), None);
+        assert_eq!(parse_span("9999999999999999d"), None);
+        assert_eq!(parse_span("99999999999999999999h"), None);
+    }
+
+    #[test]
+    fn months() {
+        let (first, next) = parse_month("2025-12").unwrap();
+        assert_eq!(
+            first,
+            Date::from_calendar_date(2025, Month::December, 1).unwrap()
+        );
+        assert_eq!(
+            next,
+            Date::from_calendar_date(2026, Month::January, 1).unwrap()
+        );
+
+        let (first, next) = parse_month("2024-02").unwrap();
+        assert_eq!(
+            first,
+            Date::from_calendar_date(2024, Month::February, 1).unwrap()
+        );
+        assert_eq!(
+            next,
+            Date::from_calendar_date(2024, Month::March, 1).unwrap()
+        );
+
+        assert_eq!(parse_month("9999-12"), None);
+        assert_eq!(parse_month("2025-13"), None);
+        assert_eq!(parse_month("2025"), None);
+        assert_eq!(parse_month("-1-"), None);
+    }
+
+    #[test]
+    fn scopes() {
+        assert_eq!(
+            parse_scopes("read, Water,read"),
+            Some(vec![ApiScope::Read, ApiScope::Water])
+        );
+        assert_eq!(parse_scopes("admin"), Some(vec![ApiScope::Admin]));
+        assert_eq!(parse_scopes("read,root"), None);
+        assert_eq!(parse_scopes(" , "), None);
+    }
+}
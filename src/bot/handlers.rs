@@ -1,8 +1,30 @@
-use teloxide::{dispatching::dialogue::InMemStorage, prelude::*, utils::command::BotCommands};
+use std::sync::Arc;
 
-use super::keyboard::{main_keyboard, settings_keyboard, water_duration_keyboard};
+use serde::{Deserialize, Serialize};
+use teloxide::{
+    prelude::*,
+    types::{
+        Chat, ChatAction, InlineKeyboardMarkup, InlineQuery, InlineQueryResult,
+        InlineQueryResultArticle, InputFile, KeyboardRemove, MessageId, User,
+    },
+    utils::command::BotCommands,
+};
+use time::{Duration, OffsetDateTime, PrimitiveDateTime, Time};
+use time_tz::timezones::db::europe::KYIV;
+
+use super::args::{parse_duration, parse_metric, parse_month, parse_scopes, parse_span};
+use super::keyboard::{
+    chart_metric_keyboard, chart_period_keyboard, live_status_keyboard, main_keyboard,
+    morning_hour_keyboard, power_keyboard, queue_keyboard, settings_keyboard, stats_keyboard,
+    status_keyboard, tokens_keyboard, water_confirm_keyboard, water_duration_keyboard, MenuButton,
+};
 use super::responses;
-use crate::db::Db;
+use super::storage::DbStorage;
+use crate::config::{api_tokens, auth, commands, queue, water};
+use crate::db::{ApiScope, AuthorizedUser, Db, Locale, NotificationSettings, Role};
+use crate::live_status::LiveReading;
+use crate::services::{current_battery, format_kyiv, sensor_csv, ChartMetric, PowerPeriod};
+use crate::templates::{Localized, TemplateId, Templates, Value};
 
 #[derive(BotCommands, Clone)]
 #[command(rename_rule = "lowercase", description = "Available commands:")]
@@ -11,23 +33,118 @@ pub enum Command {
     Start,
     #[command(description = "Show this help message")]
     Help,
+    #[command(description = "Current readings")]
+    Status,
+    #[command(description = "Water the garden, e.g. /water 12")]
+    Water(String),
+    #[command(description = "Stats for a period, e.g. /stats 7d")]
+    Stats(String),
+    #[command(description = "Chart of a metric, e.g. /history soil 24h")]
+    History(String),
+    #[command(description = "Power outages, e.g. /outages 30d")]
+    Outages(String),
+    #[command(description = "Readings of a month as CSV, e.g. /export csv 2025-12")]
+    Export(String),
+    #[command(description = "Queued and recent device commands")]
+    Queue,
     #[command(description = "Notification settings")]
     Settings,
+    #[command(description = "Weekly garden report")]
+    Weekly,
+    #[command(description = "Time zone for the morning summary and quiet hours")]
+    Timezone(String),
+    #[command(description = "API tokens for scripts and shortcuts")]
+    Tokens,
+    #[command(description = "Create an API token, e.g. /newtoken shortcut read,water 90d")]
+    NewToken(String),
+}
+
+/// Commands open to anyone, without the bot secret
+#[derive(BotCommands, Clone)]
+#[command(rename_rule = "lowercase", description = "Public commands:")]
+pub enum PublicCommand {
+    #[command(description = "Get power alerts")]
+    Subscribe,
+    #[command(description = "Stop power alerts")]
+    Unsubscribe,
+    #[command(description = "Current power state")]
+    Power,
+    /// Opened from an invite deep link with its token
+    #[command(hide)]
+    Start(String),
+}
+
+/// Commands for admins only
+#[derive(BotCommands, Clone)]
+#[command(rename_rule = "lowercase", description = "Admin commands:")]
+pub enum AdminCommand {
+    #[command(description = "List users")]
+    Users,
+    #[command(description = "Remove a user's access")]
+    Revoke(String),
+    #[command(description = "Change a user's role")]
+    Promote(String),
+    #[command(description = "Message all users")]
+    Broadcast(String),
+    #[command(description = "Create an invite link")]
+    Invite(String),
+    #[command(description = "Replace the bot secret")]
+    RotateSecret,
+}
+
+/// Commands an admin runs in a group chat to link it
+#[derive(BotCommands, Clone)]
+#[command(rename_rule = "lowercase", description = "Group commands:")]
+pub enum GroupCommand {
+    #[command(description = "Let this group use the bot and get alerts")]
+    LinkGroup,
+    #[command(description = "Stop this group using the bot")]
+    UnlinkGroup,
 }
 
-#[derive(Clone, Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub enum State {
     #[default]
     Unauthorized,
+    /// Receives power alerts only
+    Subscriber,
     Authorized,
+    /// Authorized and typing a custom watering duration
+    EnterWaterDuration,
 }
 
-pub type BotDialogue = Dialogue<State, InMemStorage<State>>;
+pub type BotDialogue = Dialogue<State, DbStorage>;
 
 #[derive(Clone)]
 pub struct BotState {
     pub db: Db,
     pub bot_secret: String,
+    pub templates: Arc<Templates>,
+}
+
+/// The user's settings, or defaults in their Telegram language if they have none yet
+async fn user_settings(db: &Db, user: Option<&User>) -> NotificationSettings {
+    let Some(user) = user else {
+        return NotificationSettings::default();
+    };
+
+    let user_id = user.id.0 as i64;
+    match db.find_notification_settings(user_id).await {
+        Ok(Some(settings)) => settings,
+        _ => NotificationSettings {
+            telegram_user_id: user_id,
+            locale: Locale::from_language_code(user.language_code.as_deref()),
+            ..Default::default()
+        },
+    }
+}
+
+/// The secret an admin rotated to, otherwise `BOT_SECRET`
+async fn bot_secret(state: &BotState) -> String {
+    match state.db.get_bot_secret().await {
+        Ok(Some(secret)) => secret,
+        _ => state.bot_secret.clone(),
+    }
 }
 
 pub async fn handle_unauthorized(
@@ -42,38 +159,308 @@ pub async fn handle_unauthorized(
 
     let user_id = user.id.0 as i64;
     let username = user.username.as_deref();
+    let settings = user_settings(&state.db, Some(user)).await;
+    let locale = settings.locale;
+    let t = state.templates.for_user(&settings);
 
     if state.db.is_user_authorized(user_id).await.unwrap_or(false) {
         let _ = dialogue.update(State::Authorized).await;
-        bot.send_message(msg.chat.id, "Welcome back!")
-            .reply_markup(main_keyboard())
+        t.send_message(&bot, msg.chat.id, t.text(TemplateId::WelcomeBack))
+            .reply_markup(main_keyboard(&t))
             .await?;
         return Ok(());
     }
 
+    // The tables are the source of truth; the stored dialogue may lag behind them
+    if state.db.is_power_subscriber(user_id).await.unwrap_or(false) {
+        let _ = dialogue.update(State::Subscriber).await;
+        return handle_subscriber(bot, msg, dialogue, state).await;
+    }
+
     let Some(text) = msg.text() else {
-        bot.send_message(msg.chat.id, "Please enter the secret word to continue.")
+        t.send_message(&bot, msg.chat.id, t.text(TemplateId::EnterSecret))
             .await?;
         return Ok(());
     };
 
-    if text.trim() == state.bot_secret {
-        if let Err(e) = state.db.authorize_user(user_id, username).await {
-            eprintln!("Failed to authorize user: {}", e);
-            bot.send_message(msg.chat.id, "Authorization failed. Please try again.")
-                .await?;
-        } else {
-            let _ = state.db.ensure_notification_settings(user_id).await;
-            let _ = dialogue.update(State::Authorized).await;
-            bot.send_message(msg.chat.id, "You are now authorized!")
-                .reply_markup(main_keyboard())
+    if is_locked_out(&bot, &msg, &state, &t, user_id).await? {
+        return Ok(());
+    }
+
+    if text.trim() == bot_secret(&state).await {
+        authorize(&bot, &msg, &dialogue, &state, user_id, username, locale).await?;
+    } else {
+        reject_attempt(&bot, &msg, &state, user, TemplateId::IncorrectSecret).await?;
+    }
+
+    Ok(())
+}
+
+async fn authorize(
+    bot: &Bot,
+    msg: &Message,
+    dialogue: &BotDialogue,
+    state: &BotState,
+    user_id: i64,
+    username: Option<&str>,
+    locale: Locale,
+) -> ResponseResult<()> {
+    let t = state.templates.locale(locale);
+
+    if let Err(e) = state.db.authorize_user(user_id, username).await {
+        eprintln!("Failed to authorize user: {}", e);
+        t.send_message(bot, msg.chat.id, t.text(TemplateId::AuthFailed))
+            .await?;
+        return Ok(());
+    }
+
+    welcome(bot, msg, dialogue, state, user_id, locale).await
+}
+
+/// Authorizes with the role of an invite link
+async fn join_by_invite(
+    bot: &Bot,
+    msg: &Message,
+    dialogue: &BotDialogue,
+    state: &BotState,
+    user: &User,
+    locale: Locale,
+    token: &str,
+) -> ResponseResult<()> {
+    let t = state.templates.locale(locale);
+    let user_id = user.id.0 as i64;
+
+    if is_locked_out(bot, msg, state, &t, user_id).await? {
+        return Ok(());
+    }
+
+    let username = user.username.as_deref();
+    match state.db.redeem_invite(token, user_id, username).await {
+        Ok(Some(_)) => welcome(bot, msg, dialogue, state, user_id, locale).await,
+        Ok(None) => reject_attempt(bot, msg, state, user, TemplateId::InviteInvalid).await,
+        Err(e) => {
+            eprintln!("Failed to redeem invite: {}", e);
+            t.send_message(bot, msg.chat.id, t.text(TemplateId::AuthFailed))
                 .await?;
+            Ok(())
         }
-    } else {
-        bot.send_message(msg.chat.id, "Incorrect. Please enter the secret word.")
+    }
+}
+
+/// Sets up a newly authorized user and shows them the main menu
+async fn welcome(
+    bot: &Bot,
+    msg: &Message,
+    dialogue: &BotDialogue,
+    state: &BotState,
+    user_id: i64,
+    locale: Locale,
+) -> ResponseResult<()> {
+    let t = state.templates.locale(locale);
+
+    let _ = state.db.clear_failed_auth(user_id).await;
+    let _ = state.db.ensure_notification_settings(user_id, locale).await;
+    let _ = state.db.remove_power_subscriber(user_id).await;
+    let _ = dialogue.update(State::Authorized).await;
+    t.send_message(bot, msg.chat.id, t.text(TemplateId::Authorized))
+        .reply_markup(main_keyboard(&t))
+        .await?;
+    Ok(())
+}
+
+/// Tells a user who is locked out after wrong attempts when they may try again
+async fn is_locked_out(
+    bot: &Bot,
+    msg: &Message,
+    state: &BotState,
+    t: &Localized<'_>,
+    user_id: i64,
+) -> ResponseResult<bool> {
+    let Some(locked_until) = state.db.get_auth_lockout(user_id).await.ok().flatten() else {
+        return Ok(false);
+    };
+
+    let minutes = (locked_until.assume_utc() - OffsetDateTime::now_utc()).whole_minutes() + 1;
+    let text = t.render(
+        TemplateId::AuthLocked,
+        &[("minutes", Value::Integer(minutes))],
+    );
+    t.send_message(bot, msg.chat.id, text).await?;
+    Ok(true)
+}
+
+/// Counts a wrong secret or invite. Too many in a row lock the user out and alert the admins
+async fn reject_attempt(
+    bot: &Bot,
+    msg: &Message,
+    state: &BotState,
+    user: &User,
+    reply: TemplateId,
+) -> ResponseResult<()> {
+    let settings = user_settings(&state.db, Some(user)).await;
+    let t = state.templates.for_user(&settings);
+    let user_id = user.id.0 as i64;
+
+    let failures = match state
+        .db
+        .record_failed_auth(user_id, auth::FAILURE_WINDOW_MINS)
+        .await
+    {
+        Ok(failures) => failures,
+        Err(e) => {
+            eprintln!("Failed to record authorization attempt: {}", e);
+            0
+        }
+    };
+
+    if failures < auth::MAX_FAILURES {
+        t.send_message(bot, msg.chat.id, t.text(reply)).await?;
+        return Ok(());
+    }
+
+    if let Err(e) = state.db.lock_auth(user_id, auth::LOCKOUT_MINS).await {
+        eprintln!("Failed to lock out user {}: {}", user_id, e);
+    }
+    let text = t.render(
+        TemplateId::AuthLocked,
+        &[("minutes", auth::LOCKOUT_MINS.into())],
+    );
+    t.send_message(bot, msg.chat.id, text).await?;
+
+    let admin_ids = state
+        .db
+        .get_user_ids_with_role(Role::Admin)
+        .await
+        .unwrap_or_default();
+    let name = user_name(user_id, user.username.as_deref());
+    send_to_users(bot, state, admin_ids, |t| {
+        t.render(
+            TemplateId::AuthSuspicious,
+            &[
+                ("user", name.as_str().into()),
+                ("attempts", failures.into()),
+                ("minutes", auth::LOCKOUT_MINS.into()),
+            ],
+        )
+    })
+    .await;
+    Ok(())
+}
+
+/// Subscribers can only check the power or upgrade with the bot secret
+pub async fn handle_subscriber(
+    bot: Bot,
+    msg: Message,
+    dialogue: BotDialogue,
+    state: BotState,
+) -> ResponseResult<()> {
+    let Some(user) = msg.from.as_ref() else {
+        return Ok(());
+    };
+
+    let settings = user_settings(&state.db, Some(user)).await;
+    let t = state.templates.for_user(&settings);
+    let user_id = user.id.0 as i64;
+
+    if msg.text().is_none() {
+        t.send_message(&bot, msg.chat.id, t.text(TemplateId::SubscriberHelp))
+            .await?;
+        return Ok(());
+    }
+
+    // Any other text may be a guess at the secret, so it counts as an attempt
+    if is_locked_out(&bot, &msg, &state, &t, user_id).await? {
+        return Ok(());
+    }
+
+    if msg.text().map(str::trim) == Some(bot_secret(&state).await.as_str()) {
+        let username = user.username.as_deref();
+        return authorize(
+            &bot,
+            &msg,
+            &dialogue,
+            &state,
+            user_id,
+            username,
+            settings.locale,
+        )
+        .await;
+    }
+
+    reject_attempt(&bot, &msg, &state, user, TemplateId::SubscriberHelp).await
+}
+
+pub async fn handle_public_command(
+    bot: Bot,
+    msg: Message,
+    cmd: PublicCommand,
+    dialogue: BotDialogue,
+    state: BotState,
+) -> ResponseResult<()> {
+    let Some(user) = msg.from.as_ref() else {
+        return Ok(());
+    };
+
+    let user_id = user.id.0 as i64;
+    let settings = user_settings(&state.db, Some(user)).await;
+    let t = state.templates.for_user(&settings);
+
+    if state.db.is_user_authorized(user_id).await.unwrap_or(false) {
+        let _ = dialogue.update(State::Authorized).await;
+        t.send_message(&bot, msg.chat.id, t.text(TemplateId::WelcomeBack))
+            .reply_markup(main_keyboard(&t))
             .await?;
+        return Ok(());
     }
 
+    let text = match cmd {
+        PublicCommand::Subscribe => {
+            let username = user.username.as_deref();
+            match state
+                .db
+                .add_power_subscriber(user_id, username, settings.locale)
+                .await
+            {
+                Ok(()) => {
+                    let _ = dialogue.update(State::Subscriber).await;
+                    t.text(TemplateId::Subscribed)
+                }
+                Err(e) => {
+                    eprintln!("Failed to add power subscriber: {}", e);
+                    t.text(TemplateId::UpdateFailed)
+                }
+            }
+        }
+        PublicCommand::Unsubscribe => {
+            if let Err(e) = state.db.remove_power_subscriber(user_id).await {
+                eprintln!("Failed to remove power subscriber: {}", e);
+            }
+            let _ = dialogue.update(State::Unauthorized).await;
+            t.text(TemplateId::Unsubscribed)
+        }
+        PublicCommand::Power => responses::build_power_current(&state.db, &t, KYIV).await,
+        PublicCommand::Start(token) if !token.trim().is_empty() => {
+            return join_by_invite(
+                &bot,
+                &msg,
+                &dialogue,
+                &state,
+                user,
+                settings.locale,
+                token.trim(),
+            )
+            .await;
+        }
+        PublicCommand::Start(_) => {
+            if state.db.is_power_subscriber(user_id).await.unwrap_or(false) {
+                t.text(TemplateId::SubscriberHelp)
+            } else {
+                t.text(TemplateId::EnterSecret)
+            }
+        }
+    };
+
+    t.send_message(&bot, msg.chat.id, text).await?;
     Ok(())
 }
 
@@ -83,162 +470,1453 @@ pub async fn handle_command(
     cmd: Command,
     state: BotState,
 ) -> ResponseResult<()> {
+    let settings = user_settings(&state.db, msg.from.as_ref()).await;
+    let locale = settings.locale;
+    let t = state.templates.for_user(&settings);
+
     match cmd {
         Command::Start => {
-            bot.send_message(msg.chat.id, "Main menu:")
-                .reply_markup(main_keyboard())
+            t.send_message(&bot, msg.chat.id, t.text(TemplateId::MainMenu))
+                .reply_markup(main_keyboard(&t))
                 .await?;
         }
         Command::Help => {
-            bot.send_message(msg.chat.id, Command::descriptions().to_string())
+            let user_id = msg.from.as_ref().map(|u| u.id.0 as i64).unwrap_or(0);
+            let mut help = t.text(TemplateId::Help);
+            if state.db.get_user_role(user_id).await.ok().flatten() == Some(Role::Admin) {
+                help.push_str(&t.text(TemplateId::HelpAdmin));
+            }
+            t.send_message(&bot, msg.chat.id, help).await?;
+        }
+        Command::Status => {
+            let status = responses::build_status(&state.db, &t).await;
+            t.send_message(&bot, msg.chat.id, status)
+                .reply_markup(status_keyboard(&t))
+                .await?;
+        }
+        Command::Water(args) => water(&bot, &msg, &state, &t, &args).await?,
+        Command::Stats(args) => {
+            let text = match args.trim() {
+                "" => responses::build_stats(&state.db, &t).await,
+                span => match parse_span(span) {
+                    Some(span) => responses::build_period_stats(&state.db, &t, span).await,
+                    None => t.text(TemplateId::StatsUsage),
+                },
+            };
+            t.send_message(&bot, msg.chat.id, text).await?;
+        }
+        Command::History(args) => history(&bot, &msg, &state, &t, &args).await?,
+        Command::Outages(args) => {
+            let span = match args.trim() {
+                "" => Some(Duration::days(commands::OUTAGES_DAYS)),
+                span => parse_span(span),
+            };
+            let text = match span {
+                Some(span) => responses::build_outages(&state.db, &t, span).await,
+                None => t.text(TemplateId::OutagesUsage),
+            };
+            t.send_message(&bot, msg.chat.id, text).await?;
+        }
+        Command::Export(args) => export(&bot, &msg, &state, &t, &args).await?,
+        Command::Queue => {
+            let user_id = msg.from.as_ref().map(|u| u.id.0 as i64).unwrap_or(0);
+            let role = state.db.get_user_role(user_id).await.ok().flatten();
+            let (text, keyboard) = queue_view(&state.db, &t, role >= Some(Role::Operator)).await;
+            t.send_message(&bot, msg.chat.id, text)
+                .reply_markup(keyboard)
                 .await?;
         }
         Command::Settings => {
             let user_id = msg.from.as_ref().map(|u| u.id.0 as i64).unwrap_or(0);
-            let _ = state.db.ensure_notification_settings(user_id).await;
+            let _ = state.db.ensure_notification_settings(user_id, locale).await;
             let settings = state
                 .db
                 .get_notification_settings(user_id)
                 .await
                 .unwrap_or_default();
 
-            bot.send_message(msg.chat.id, "⚙️ Notification Settings")
-                .reply_markup(settings_keyboard(&settings))
+            t.send_message(&bot, msg.chat.id, t.text(TemplateId::SettingsTitle))
+                .reply_markup(settings_keyboard(&t, &settings))
+                .await?;
+        }
+        Command::Weekly => {
+            let report = responses::build_weekly_report(&state.db, &t).await;
+            t.send_message(&bot, msg.chat.id, report).await?;
+        }
+        Command::Timezone(name) => {
+            let user_id = msg.from.as_ref().map(|u| u.id.0 as i64).unwrap_or(0);
+            let name = name.trim();
+            let text = if name.is_empty() {
+                t.render(
+                    TemplateId::TimezoneUsage,
+                    &[("timezone", settings.timezone.as_str().into())],
+                )
+            } else {
+                let _ = state.db.ensure_notification_settings(user_id, locale).await;
+                match state.db.set_timezone(user_id, name).await {
+                    Ok(Some(timezone)) => {
+                        t.render(TemplateId::TimezoneSet, &[("timezone", timezone.into())])
+                    }
+                    Ok(None) => t.render(TemplateId::TimezoneUnknown, &[("timezone", name.into())]),
+                    Err(e) => {
+                        eprintln!("Failed to set time zone: {}", e);
+                        t.text(TemplateId::UpdateFailed)
+                    }
+                }
+            };
+            t.send_message(&bot, msg.chat.id, text).await?;
+        }
+        Command::Tokens => {
+            let user_id = msg.from.as_ref().map(|u| u.id.0 as i64).unwrap_or(0);
+            let (text, keyboard) = tokens_view(&state.db, &t, user_id).await;
+            t.send_message(&bot, msg.chat.id, text)
+                .reply_markup(keyboard)
                 .await?;
         }
+        Command::NewToken(args) => {
+            let user_id = msg.from.as_ref().map(|u| u.id.0 as i64).unwrap_or(0);
+            let text = new_token(&state, &t, user_id, &args).await;
+            t.send_message(&bot, msg.chat.id, text).await?;
+        }
     }
     Ok(())
 }
 
-pub async fn handle_message(bot: Bot, msg: Message, state: BotState) -> ResponseResult<()> {
-    let Some(text) = msg.text() else {
-        return Ok(());
+/// `/newtoken <name> <scopes> [expiry]` creates a token with scopes the user's role allows
+async fn new_token(state: &BotState, t: &Localized<'_>, user_id: i64, args: &str) -> String {
+    let usage = || {
+        t.render(
+            TemplateId::TokenUsage,
+            &[
+                ("max_name", Value::Integer(api_tokens::MAX_NAME_LEN as i64)),
+                ("days", api_tokens::DEFAULT_VALID_DAYS.into()),
+            ],
+        )
+    };
+    let mut args = args.split_whitespace();
+    let name = args
+        .next()
+        .filter(|name| name.chars().count() <= api_tokens::MAX_NAME_LEN);
+    let scopes = args.next().and_then(parse_scopes);
+    let valid_days = match args.next() {
+        // Rounded up to whole days, so `12h` still lasts a day
+        Some(span) => parse_span(span).map(|span| ((span.whole_hours() + 23) / 24) as i32),
+        None => Some(api_tokens::DEFAULT_VALID_DAYS),
+    };
+    let (Some(name), Some(scopes), Some(valid_days), None) =
+        (name, scopes, valid_days, args.next())
+    else {
+        return usage();
     };
 
-    let user_id = msg.from.as_ref().map(|u| u.id.0 as i64).unwrap_or(0);
-
-    let response = match text {
-        "📊 Status" => responses::build_status(&state.db).await,
-        "🌤 Weather" => responses::build_weather(&state.db).await,
-        "🌱 Garden" => responses::build_garden(&state.db).await,
-        "📈 Stats" => responses::build_stats(&state.db).await,
-        "⚡ Power" => responses::build_power_history(&state.db).await,
-        "⚙️ Settings" => {
-            let _ = state.db.ensure_notification_settings(user_id).await;
-            let settings = state.db.get_notification_settings(user_id).await.ok();
-            let settings = settings.unwrap_or_default();
+    let role = state.db.get_user_role(user_id).await.ok().flatten();
+    if let Some(scope) = scopes.iter().find(|s| role < Some(s.min_role())) {
+        return t.render(
+            TemplateId::TokenScopeNotAllowed,
+            &[("scope", scope.name().into())],
+        );
+    }
 
-            bot.send_message(msg.chat.id, "⚙️ Notification Settings")
-                .reply_markup(settings_keyboard(&settings))
-                .await?;
-            return Ok(());
-        }
-        "💧 Water" => {
-            bot.send_message(msg.chat.id, "💧 Select watering duration:")
-                .reply_markup(water_duration_keyboard())
-                .await?;
-            return Ok(());
+    match state
+        .db
+        .create_api_token(user_id, name, &scopes, valid_days)
+        .await
+    {
+        Ok(Some(token)) => t.render(
+            TemplateId::TokenCreated,
+            &[
+                ("name", name.into()),
+                ("scopes", scope_names(&scopes).into()),
+                ("expires", format_kyiv(token.expires_at).into()),
+                ("token", token.token.into()),
+            ],
+        ),
+        Ok(None) => t.render(TemplateId::TokenNameTaken, &[("name", name.into())]),
+        Err(e) => {
+            eprintln!("Failed to create API token: {}", e);
+            t.text(TemplateId::UpdateFailed)
         }
-        _ => {
-            bot.send_message(msg.chat.id, "Use the menu below:")
-                .reply_markup(main_keyboard())
-                .await?;
-            return Ok(());
+    }
+}
+
+/// The user's tokens with a revoke button each
+async fn tokens_view(db: &Db, t: &Localized<'_>, user_id: i64) -> (String, InlineKeyboardMarkup) {
+    let tokens = db.get_api_tokens(user_id).await.unwrap_or_else(|e| {
+        eprintln!("Failed to load API tokens: {}", e);
+        Vec::new()
+    });
+    if tokens.is_empty() {
+        return (
+            t.text(TemplateId::TokensNone),
+            InlineKeyboardMarkup::default(),
+        );
+    }
+
+    let items: String = tokens
+        .iter()
+        .map(|token| {
+            let used = token
+                .last_used_at
+                .map(|time| {
+                    t.render(
+                        TemplateId::TokenLastUsed,
+                        &[("time", format_kyiv(time).into())],
+                    )
+                })
+                .unwrap_or_default();
+            t.render(
+                TemplateId::TokenItem,
+                &[
+                    ("name", token.name.as_str().into()),
+                    ("scopes", scope_names(&token.scopes).into()),
+                    ("expires", format_kyiv(token.expires_at).into()),
+                    ("used", Value::Fragment(used)),
+                ],
+            )
+        })
+        .collect();
+    let text = t.render(
+        TemplateId::TokensTitle,
+        &[("items", Value::Fragment(items))],
+    );
+    (text, tokens_keyboard(t, &tokens))
+}
+
+fn scope_names(scopes: &[ApiScope]) -> String {
+    scopes
+        .iter()
+        .map(|s| s.name())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// `/water <duration>` queues watering right away
+async fn water(
+    bot: &Bot,
+    msg: &Message,
+    state: &BotState,
+    t: &Localized<'_>,
+    args: &str,
+) -> ResponseResult<()> {
+    let user_id = msg.from.as_ref().map(|u| u.id.0 as i64).unwrap_or(0);
+    let role = state.db.get_user_role(user_id).await.ok().flatten();
+    let seconds = parse_duration(args)
+        .filter(|secs| (water::PUMP_MIN_SECS..=water::PUMP_MAX_SECS).contains(secs));
+
+    let text = if role < Some(Role::Operator) {
+        t.text(TemplateId::NotPermitted)
+    } else if let Some(seconds) = seconds {
+        match state.db.add_pump_command(seconds, Some(user_id)).await {
+            Ok(_) => t.render(TemplateId::WaterQueued, &[("seconds", seconds.into())]),
+            Err(e) => {
+                eprintln!("Failed to queue watering: {}", e);
+                t.text(TemplateId::WaterQueueFailed)
+            }
         }
+    } else {
+        t.render(
+            TemplateId::WaterUsage,
+            &[
+                ("min", water::PUMP_MIN_SECS.into()),
+                ("max", water::PUMP_MAX_SECS.into()),
+            ],
+        )
     };
-
-    bot.send_message(msg.chat.id, response).await?;
+    t.send_message(bot, msg.chat.id, text).await?;
     Ok(())
 }
 
-pub async fn handle_callback(bot: Bot, q: CallbackQuery, state: BotState) -> ResponseResult<()> {
-    let Some(data) = q.data.as_ref() else {
-        return Ok(());
+/// `/history <metric> [period]` sends a chart, of the last day by default
+async fn history(
+    bot: &Bot,
+    msg: &Message,
+    state: &BotState,
+    t: &Localized<'_>,
+    args: &str,
+) -> ResponseResult<()> {
+    let mut words = args.split_whitespace();
+    let metric = words.next().and_then(parse_metric);
+    let span = match words.next() {
+        Some(span) => parse_span(span),
+        None => Some(Duration::hours(commands::HISTORY_HOURS)),
     };
-
-    let user_id = q.from.id.0 as i64;
-    if !state.db.is_user_authorized(user_id).await.unwrap_or(false) {
-        bot.answer_callback_query(q.id.clone())
-            .text("Not authorized")
+    let (Some(metric), Some(span), None) = (metric, span, words.next()) else {
+        t.send_message(bot, msg.chat.id, t.text(TemplateId::HistoryUsage))
             .await?;
         return Ok(());
-    }
-
-    let Some(ref msg) = q.message else {
-        return Ok(());
     };
 
-    if data.starts_with("toggle_") {
-        handle_toggle(&bot, &q, &state, user_id, data, msg).await?;
-        return Ok(());
-    }
-
-    if data.starts_with("water_") {
-        if let Some(duration_str) = data.strip_prefix("water_") {
-            if let Ok(duration) = duration_str.parse::<i32>() {
-                match state.db.add_pump_command(duration).await {
-                    Ok(_) => {
-                        bot.answer_callback_query(q.id.clone())
-                            .text(format!("💧 Watering for {} seconds queued!", duration))
-                            .await?;
-                        bot.delete_message(msg.chat().id, msg.id()).await?;
-                    }
-                    Err(_) => {
-                        bot.answer_callback_query(q.id.clone())
-                            .text("Failed to queue command")
-                            .await?;
-                    }
-                }
-            }
-        }
+    bot.send_chat_action(msg.chat.id, ChatAction::UploadPhoto)
+        .await?;
+    let period = responses::span_label(t, span);
+    let Some(png) = responses::build_chart(&state.db, t, metric, span, period).await else {
+        t.send_message(bot, msg.chat.id, t.text(TemplateId::ChartNoData))
+            .await?;
         return Ok(());
-    }
-
-    if data == "back" {
-        bot.answer_callback_query(q.id.clone()).await?;
-        bot.delete_message(msg.chat().id, msg.id()).await?;
-    }
+    };
 
+    bot.send_photo(msg.chat.id, InputFile::memory(png).file_name("chart.png"))
+        .await?;
     Ok(())
 }
 
-async fn handle_toggle(
+/// `/export csv <month>` sends the month's readings as a file
+async fn export(
     bot: &Bot,
-    q: &CallbackQuery,
+    msg: &Message,
     state: &BotState,
-    user_id: i64,
-    data: &str,
-    msg: &teloxide::types::MaybeInaccessibleMessage,
+    t: &Localized<'_>,
+    args: &str,
 ) -> ResponseResult<()> {
-    let result = match data {
-        "toggle_soil" => state.db.toggle_soil_alerts(user_id).await,
-        "toggle_temp" => state.db.toggle_temperature_alerts(user_id).await,
-        "toggle_power" => state.db.toggle_power_alerts(user_id).await,
-        "toggle_quiet" => state.db.toggle_quiet_hours(user_id).await,
-        _ => return Ok(()),
+    let month = match args.split_whitespace().collect::<Vec<_>>()[..] {
+        [format, month] if format.eq_ignore_ascii_case("csv") => parse_month(month),
+        _ => None,
+    };
+    let Some((first, next)) = month else {
+        t.send_message(bot, msg.chat.id, t.text(TemplateId::ExportUsage))
+            .await?;
+        return Ok(());
     };
 
-    let notification = match result {
-        Ok(enabled) => {
-            let name = match data {
-                "toggle_soil" => "Soil alerts",
-                "toggle_temp" => "Temperature alerts",
-                "toggle_power" => "Power alerts",
-                "toggle_quiet" => "Quiet hours",
-                _ => "Setting",
-            };
-            format!("{} {}", name, if enabled { "enabled" } else { "disabled" })
+    let from = PrimitiveDateTime::new(first, Time::MIDNIGHT);
+    let to = PrimitiveDateTime::new(next, Time::MIDNIGHT);
+    let records = match state.db.get_sensor_records_between(from, to).await {
+        Ok(records) => records,
+        Err(e) => {
+            eprintln!("Failed to export readings: {}", e);
+            Vec::new()
         }
-        Err(_) => "Failed to update".to_string(),
     };
 
-    bot.answer_callback_query(q.id.clone()).text(&notification).await?;
-
-    if let Ok(settings) = state.db.get_notification_settings(user_id).await {
-        bot.edit_message_text(msg.chat().id, msg.id(), "⚙️ Notification Settings")
-            .reply_markup(settings_keyboard(&settings))
-            .await?;
+    let month = format!("{}-{:02}", first.year(), first.month() as u8);
+    if records.is_empty() {
+        let text = t.render(TemplateId::ExportNoData, &[("month", month.into())]);
+        t.send_message(bot, msg.chat.id, text).await?;
+        return Ok(());
     }
 
+    bot.send_chat_action(msg.chat.id, ChatAction::UploadDocument)
+        .await?;
+    let csv = sensor_csv(&records).into_bytes();
+    bot.send_document(
+        msg.chat.id,
+        InputFile::memory(csv).file_name(format!("readings-{}.csv", month)),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Commands in a linked group run like in a private chat, with the sender's
+/// own role for watering; ones about personal settings point to a private chat
+pub async fn handle_group_command(
+    bot: Bot,
+    msg: Message,
+    cmd: Command,
+    state: BotState,
+) -> ResponseResult<()> {
+    let settings = user_settings(&state.db, msg.from.as_ref()).await;
+    let t = state.templates.for_user(&settings);
+
+    if !state
+        .db
+        .is_chat_authorized(msg.chat.id.0)
+        .await
+        .unwrap_or(false)
+    {
+        t.send_message(&bot, msg.chat.id, t.text(TemplateId::GroupNotLinked))
+            .await?;
+        return Ok(());
+    }
+
+    match cmd {
+        Command::Start
+        | Command::Settings
+        | Command::Timezone(_)
+        | Command::Tokens
+        | Command::NewToken(_) => {
+            t.send_message(&bot, msg.chat.id, t.text(TemplateId::GroupPrivateOnly))
+                .await?;
+            Ok(())
+        }
+        cmd => handle_command(bot, msg, cmd, state).await,
+    }
+}
+
+/// Links or unlinks the group chat the command is sent in, for admins only
+pub async fn handle_group_link_command(
+    bot: Bot,
+    msg: Message,
+    cmd: GroupCommand,
+    state: BotState,
+) -> ResponseResult<()> {
+    let user_id = msg.from.as_ref().map(|u| u.id.0 as i64).unwrap_or(0);
+    let settings = user_settings(&state.db, msg.from.as_ref()).await;
+    let t = state.templates.for_user(&settings);
+
+    if state.db.get_user_role(user_id).await.ok().flatten() != Some(Role::Admin) {
+        t.send_message(&bot, msg.chat.id, t.text(TemplateId::NotPermitted))
+            .await?;
+        return Ok(());
+    }
+
+    let chat_id = msg.chat.id.0;
+    let result = match cmd {
+        GroupCommand::LinkGroup => state
+            .db
+            .authorize_chat(chat_id, msg.chat.title(), settings.locale, user_id)
+            .await
+            .map(|_| TemplateId::GroupLinked),
+        GroupCommand::UnlinkGroup => state.db.unauthorize_chat(chat_id).await.map(|linked| {
+            if linked {
+                TemplateId::GroupUnlinked
+            } else {
+                TemplateId::GroupNotLinked
+            }
+        }),
+    };
+    let text = match result {
+        Ok(id) => t.text(id),
+        Err(e) => {
+            eprintln!("Failed to update group chat {}: {}", chat_id, e);
+            t.text(TemplateId::UpdateFailed)
+        }
+    };
+
+    t.send_message(&bot, msg.chat.id, text).await?;
+    Ok(())
+}
+
+/// `@bot status` in any chat offers the current reading card to authorized users
+pub async fn handle_inline_query(bot: Bot, q: InlineQuery, state: BotState) -> ResponseResult<()> {
+    let user_id = q.from.id.0 as i64;
+    let query = q.query.trim().to_lowercase();
+    let authorized = state
+        .db
+        .get_user_role(user_id)
+        .await
+        .ok()
+        .flatten()
+        .is_some();
+
+    let mut results = Vec::new();
+    if authorized && "status".starts_with(query.as_str()) {
+        let settings = user_settings(&state.db, Some(&q.from)).await;
+        let t = state.templates.for_user(&settings);
+        if let Some(data) = state.db.get_latest_sensor_data().await.ok().flatten() {
+            let battery = current_battery(&state.db, &data).await.ok().flatten();
+            let card = responses::format_status(&t, &data, battery.as_ref());
+            let description = t.plain(
+                TemplateId::InlineStatusDescription,
+                &[
+                    ("temperature", Value::Temperature(data.temperature)),
+                    ("soil_moisture", data.soil_moisture.into()),
+                    ("water_level", data.water_level.into()),
+                ],
+            );
+            results.push(InlineQueryResult::Article(
+                InlineQueryResultArticle::new(
+                    "status",
+                    t.label(TemplateId::InlineStatusTitle),
+                    t.message_content(card),
+                )
+                .description(description),
+            ));
+        }
+    }
+
+    // Results depend on who asks and on fresh readings, so nothing is cached
+    bot.answer_inline_query(q.id, results)
+        .cache_time(0)
+        .is_personal(true)
+        .await?;
+    Ok(())
+}
+
+pub async fn handle_admin_command(
+    bot: Bot,
+    msg: Message,
+    cmd: AdminCommand,
+    state: BotState,
+) -> ResponseResult<()> {
+    let user_id = msg.from.as_ref().map(|u| u.id.0 as i64).unwrap_or(0);
+    let settings = user_settings(&state.db, msg.from.as_ref()).await;
+    let t = state.templates.for_user(&settings);
+
+    if state.db.get_user_role(user_id).await.ok().flatten() != Some(Role::Admin) {
+        t.send_message(&bot, msg.chat.id, t.text(TemplateId::NotPermitted))
+            .await?;
+        return Ok(());
+    }
+
+    let text = match cmd {
+        AdminCommand::Users => {
+            let users = state.db.get_authorized_users().await.unwrap_or_default();
+            let items: String = users
+                .iter()
+                .map(|user| {
+                    let until = user
+                        .expires_at
+                        .map(|at| {
+                            t.render(TemplateId::UserUntil, &[("time", format_kyiv(at).into())])
+                        })
+                        .unwrap_or_default();
+                    t.render(
+                        TemplateId::UserItem,
+                        &[
+                            ("user", authorized_user_name(user).into()),
+                            ("role", Value::Fragment(t.text(role_label(user.role)))),
+                            ("until", Value::Fragment(until)),
+                        ],
+                    )
+                })
+                .collect();
+            t.render(TemplateId::UsersTitle, &[("items", Value::Fragment(items))])
+        }
+        AdminCommand::Revoke(target) => revoke(&bot, &state, &t, user_id, target.trim()).await?,
+        AdminCommand::Promote(args) => {
+            let mut args = args.split_whitespace();
+            match (args.next(), args.next().and_then(Role::from_name)) {
+                (Some(target), Some(role)) => change_role(&state, &t, user_id, target, role).await,
+                _ => t.text(TemplateId::PromoteUsage),
+            }
+        }
+        AdminCommand::Broadcast(text) => {
+            if text.trim().is_empty() {
+                t.text(TemplateId::BroadcastUsage)
+            } else {
+                let count = broadcast(&bot, &state, text.trim()).await;
+                t.render(
+                    TemplateId::BroadcastSent,
+                    &[("count", Value::Integer(count))],
+                )
+            }
+        }
+        AdminCommand::Invite(args) => invite(&bot, &state, &t, user_id, &args).await?,
+        AdminCommand::RotateSecret => match state.db.rotate_bot_secret().await {
+            Ok(secret) => t.render(TemplateId::SecretRotated, &[("secret", secret.into())]),
+            Err(e) => {
+                eprintln!("Failed to rotate bot secret: {}", e);
+                t.text(TemplateId::UpdateFailed)
+            }
+        },
+    };
+
+    t.send_message(&bot, msg.chat.id, text).await?;
+    Ok(())
+}
+
+async fn revoke(
+    bot: &Bot,
+    state: &BotState,
+    t: &Localized<'_>,
+    admin_id: i64,
+    target: &str,
+) -> ResponseResult<String> {
+    if target.is_empty() {
+        return Ok(t.text(TemplateId::RevokeUsage));
+    }
+    let Some(user) = state.db.find_authorized_user(target).await.ok().flatten() else {
+        return Ok(t.render(TemplateId::UserNotFound, &[("user", target.into())]));
+    };
+    if user.telegram_user_id == admin_id {
+        return Ok(t.text(TemplateId::CannotChangeOwnAccess));
+    }
+
+    // Settings go with the user, so read their language first
+    let settings = state
+        .db
+        .get_notification_settings(user.telegram_user_id)
+        .await
+        .unwrap_or_default();
+    if let Err(e) = state.db.revoke_user(user.telegram_user_id).await {
+        eprintln!("Failed to revoke user: {}", e);
+        return Ok(t.text(TemplateId::UpdateFailed));
+    }
+
+    let user_t = state.templates.for_user(&settings);
+    let chat_id = ChatId(user.telegram_user_id);
+    if let Err(e) = user_t
+        .send_message(bot, chat_id, user_t.text(TemplateId::AccessRevoked))
+        .reply_markup(KeyboardRemove::new())
+        .await
+    {
+        eprintln!(
+            "Failed to notify revoked user {}: {}",
+            user.telegram_user_id, e
+        );
+    }
+
+    Ok(t.render(
+        TemplateId::UserRevoked,
+        &[("user", authorized_user_name(&user).into())],
+    ))
+}
+
+async fn change_role(
+    state: &BotState,
+    t: &Localized<'_>,
+    admin_id: i64,
+    target: &str,
+    role: Role,
+) -> String {
+    let Some(user) = state.db.find_authorized_user(target).await.ok().flatten() else {
+        return t.render(TemplateId::UserNotFound, &[("user", target.into())]);
+    };
+    if user.telegram_user_id == admin_id {
+        return t.text(TemplateId::CannotChangeOwnAccess);
+    }
+
+    match state.db.set_user_role(user.telegram_user_id, role).await {
+        Ok(()) => t.render(
+            TemplateId::UserRoleChanged,
+            &[
+                ("user", authorized_user_name(&user).into()),
+                ("role", Value::Fragment(t.text(role_label(role)))),
+            ],
+        ),
+        Err(e) => {
+            eprintln!("Failed to change user role: {}", e);
+            t.text(TemplateId::UpdateFailed)
+        }
+    }
+}
+
+/// `/invite <role> [uses] [valid hours] [access hours]`; with access hours the invited
+/// user is a guest
+async fn invite(
+    bot: &Bot,
+    state: &BotState,
+    t: &Localized<'_>,
+    admin_id: i64,
+    args: &str,
+) -> ResponseResult<String> {
+    let mut args = args.split_whitespace();
+    let role = args.next().and_then(Role::from_name);
+    let numbers: Option<Vec<i32>> = args
+        .map(|arg| arg.parse().ok().filter(|n| *n > 0))
+        .collect();
+    let (Some(role), Some(numbers)) = (role, numbers) else {
+        return Ok(t.text(TemplateId::InviteUsage));
+    };
+    if numbers.len() > 3 {
+        return Ok(t.text(TemplateId::InviteUsage));
+    }
+
+    let uses = numbers.first().copied().unwrap_or(1);
+    let valid_hours = numbers.get(1).copied().unwrap_or(auth::INVITE_VALID_HOURS);
+    let access_hours = numbers.get(2).copied();
+
+    let me = bot.get_me().await?;
+    let invite = match state
+        .db
+        .create_invite(role, admin_id, uses, valid_hours, access_hours)
+        .await
+    {
+        Ok(invite) => invite,
+        Err(e) => {
+            eprintln!("Failed to create invite: {}", e);
+            return Ok(t.text(TemplateId::UpdateFailed));
+        }
+    };
+
+    let access = access_hours
+        .map(|hours| {
+            let duration = t.duration(hours.saturating_mul(60));
+            t.render(
+                TemplateId::GuestAccess,
+                &[("duration", Value::Fragment(duration))],
+            )
+        })
+        .unwrap_or_default();
+    let link = format!("https://t.me/{}?start={}", me.username(), invite.token);
+
+    Ok(t.render(
+        TemplateId::InviteCreated,
+        &[
+            ("role", Value::Fragment(t.text(role_label(role)))),
+            ("uses", uses.into()),
+            ("expires", format_kyiv(invite.expires_at).into()),
+            ("access", Value::Fragment(access)),
+            ("link", link.into()),
+        ],
+    ))
+}
+
+/// Sends the message to every authorized user in their language, returning how many got it
+async fn broadcast(bot: &Bot, state: &BotState, text: &str) -> i64 {
+    let user_ids = state.db.get_authorized_user_ids().await.unwrap_or_default();
+    send_to_users(bot, state, user_ids, |t| {
+        t.render(TemplateId::BroadcastMessage, &[("text", text.into())])
+    })
+    .await
+}
+
+/// Sends each user the message rendered in their language, returning how many got it
+async fn send_to_users(
+    bot: &Bot,
+    state: &BotState,
+    user_ids: Vec<i64>,
+    render: impl Fn(&Localized<'_>) -> String,
+) -> i64 {
+    let mut sent = 0;
+
+    for user_id in user_ids {
+        let settings = state
+            .db
+            .get_notification_settings(user_id)
+            .await
+            .unwrap_or_default();
+        let t = state.templates.for_user(&settings);
+        match t.send_message(bot, ChatId(user_id), render(&t)).await {
+            Ok(_) => sent += 1,
+            Err(e) => eprintln!("Failed to send message to {}: {}", user_id, e),
+        }
+    }
+
+    sent
+}
+
+fn user_name(user_id: i64, username: Option<&str>) -> String {
+    match username {
+        Some(username) => format!("@{} ({})", username, user_id),
+        None => user_id.to_string(),
+    }
+}
+
+fn authorized_user_name(user: &AuthorizedUser) -> String {
+    user_name(user.telegram_user_id, user.username.as_deref())
+}
+
+fn role_label(role: Role) -> TemplateId {
+    match role {
+        Role::Viewer => TemplateId::RoleViewer,
+        Role::Operator => TemplateId::RoleOperator,
+        Role::Admin => TemplateId::RoleAdmin,
+    }
+}
+
+pub async fn handle_message(
+    bot: Bot,
+    msg: Message,
+    dialogue: BotDialogue,
+    state: BotState,
+) -> ResponseResult<()> {
+    let Some(text) = msg.text() else {
+        return Ok(());
+    };
+
+    let user_id = msg.from.as_ref().map(|u| u.id.0 as i64).unwrap_or(0);
+    let settings = user_settings(&state.db, msg.from.as_ref()).await;
+    let locale = settings.locale;
+    let t = state.templates.for_user(&settings);
+
+    // Access may have been revoked since the dialogue was stored
+    let Some(role) = state.db.get_user_role(user_id).await.ok().flatten() else {
+        let _ = dialogue.update(State::Unauthorized).await;
+        t.send_message(&bot, msg.chat.id, t.text(TemplateId::EnterSecret))
+            .await?;
+        return Ok(());
+    };
+
+    let Some(button) = MenuButton::parse(&state.templates, text) else {
+        t.send_message(&bot, msg.chat.id, t.text(TemplateId::UseMenu))
+            .reply_markup(main_keyboard(&t))
+            .await?;
+        return Ok(());
+    };
+
+    let response = match button {
+        MenuButton::Status => {
+            let status = responses::build_status(&state.db, &t).await;
+            t.send_message(&bot, msg.chat.id, status)
+                .reply_markup(status_keyboard(&t))
+                .await?;
+            return Ok(());
+        }
+        MenuButton::Weather => responses::build_weather(&state.db, &t).await,
+        MenuButton::Garden => responses::build_garden(&state.db, &t).await,
+        MenuButton::Stats => {
+            let stats = responses::build_stats(&state.db, &t).await;
+            t.send_message(&bot, msg.chat.id, stats)
+                .reply_markup(stats_keyboard(&t))
+                .await?;
+            return Ok(());
+        }
+        MenuButton::Power => {
+            let history = responses::build_power_history(&state.db, &t).await;
+            t.send_message(&bot, msg.chat.id, history)
+                .reply_markup(power_keyboard(&t))
+                .await?;
+            return Ok(());
+        }
+        MenuButton::Outlook => responses::build_outlook(&state.db, &t).await,
+        MenuButton::Settings => {
+            let _ = state.db.ensure_notification_settings(user_id, locale).await;
+            let settings = state.db.get_notification_settings(user_id).await.ok();
+            let settings = settings.unwrap_or_default();
+
+            t.send_message(&bot, msg.chat.id, t.text(TemplateId::SettingsTitle))
+                .reply_markup(settings_keyboard(&t, &settings))
+                .await?;
+            return Ok(());
+        }
+        MenuButton::Charts => {
+            t.send_message(&bot, msg.chat.id, t.text(TemplateId::ChartSelectMetric))
+                .reply_markup(chart_metric_keyboard(&t))
+                .await?;
+            return Ok(());
+        }
+        MenuButton::Queue => {
+            let (text, keyboard) = queue_view(&state.db, &t, role >= Role::Operator).await;
+            t.send_message(&bot, msg.chat.id, text)
+                .reply_markup(keyboard)
+                .await?;
+            return Ok(());
+        }
+        MenuButton::Water if role < Role::Operator => t.text(TemplateId::NotPermitted),
+        MenuButton::Water => {
+            t.send_message(&bot, msg.chat.id, t.text(TemplateId::WaterSelect))
+                .reply_markup(water_duration_keyboard(&t))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    t.send_message(&bot, msg.chat.id, response).await?;
+    Ok(())
+}
+
+/// Reads a custom watering duration and asks to confirm it
+pub async fn handle_water_duration(
+    bot: Bot,
+    msg: Message,
+    dialogue: BotDialogue,
+    state: BotState,
+) -> ResponseResult<()> {
+    let settings = user_settings(&state.db, msg.from.as_ref()).await;
+    let t = state.templates.for_user(&settings);
+    let text = msg.text().unwrap_or_default();
+
+    // The role may have changed since the input started
+    let user_id = msg.from.as_ref().map(|u| u.id.0 as i64).unwrap_or(0);
+    let role = state.db.get_user_role(user_id).await.ok().flatten();
+    if role < Some(Role::Operator) {
+        let _ = dialogue.update(State::Authorized).await;
+        return handle_message(bot, msg, dialogue, state).await;
+    }
+
+    // Commands and menu buttons leave the input, buttons still doing their job
+    if text.starts_with('/') {
+        let _ = dialogue.update(State::Authorized).await;
+        t.send_message(&bot, msg.chat.id, t.text(TemplateId::InputCancelled))
+            .reply_markup(main_keyboard(&t))
+            .await?;
+        return Ok(());
+    }
+    if MenuButton::parse(&state.templates, text).is_some() {
+        let _ = dialogue.update(State::Authorized).await;
+        return handle_message(bot, msg, dialogue, state).await;
+    }
+
+    let Some(seconds) = parse_duration(text) else {
+        t.send_message(&bot, msg.chat.id, t.text(TemplateId::WaterDurationInvalid))
+            .await?;
+        return Ok(());
+    };
+    if !(water::PUMP_MIN_SECS..=water::PUMP_MAX_SECS).contains(&seconds) {
+        let text = t.render(
+            TemplateId::WaterDurationOutOfRange,
+            &[
+                ("min", water::PUMP_MIN_SECS.into()),
+                ("max", water::PUMP_MAX_SECS.into()),
+            ],
+        );
+        t.send_message(&bot, msg.chat.id, text).await?;
+        return Ok(());
+    }
+
+    let _ = dialogue.update(State::Authorized).await;
+    let text = t.render(TemplateId::WaterConfirm, &[("seconds", seconds.into())]);
+    t.send_message(&bot, msg.chat.id, text)
+        .reply_markup(water_confirm_keyboard(&t, seconds))
+        .await?;
+    Ok(())
+}
+
+pub async fn handle_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    dialogue: BotDialogue,
+    state: BotState,
+) -> ResponseResult<()> {
+    let Some(data) = q.data.as_ref() else {
+        return Ok(());
+    };
+
+    let user_id = q.from.id.0 as i64;
+    let settings = user_settings(&state.db, Some(&q.from)).await;
+    let t = state.templates.for_user(&settings);
+
+    let Some(ref msg) = q.message else {
+        return Ok(());
+    };
+
+    let Some(role) = callback_role(&state.db, user_id, msg.chat()).await else {
+        bot.answer_callback_query(q.id.clone())
+            .text(t.label(TemplateId::NotAuthorized))
+            .await?;
+        return Ok(());
+    };
+
+    if data.starts_with("toggle_") {
+        handle_toggle(&bot, &q, &state, user_id, data, msg).await?;
+        return Ok(());
+    }
+
+    if let Some(choice) = data.strip_prefix("morning_") {
+        handle_morning_hour(&bot, &q, &state, user_id, choice, msg).await?;
+        return Ok(());
+    }
+
+    if data == "live_on" {
+        start_live_status(&bot, &q, &state, &t, user_id, msg).await?;
+        return Ok(());
+    }
+
+    if data == "live_off" {
+        bot.answer_callback_query(q.id.clone())
+            .text(t.label(TemplateId::LiveStatusStopped))
+            .await?;
+        let _ = state.db.remove_live_status(msg.chat().id.0).await;
+        let _ = bot
+            .unpin_chat_message(msg.chat().id)
+            .message_id(msg.id())
+            .await;
+        bot.edit_message_reply_markup(msg.chat().id, msg.id())
+            .await?;
+        return Ok(());
+    }
+
+    if data == "queue" {
+        bot.answer_callback_query(q.id.clone()).await?;
+        let (text, keyboard) = queue_view(&state.db, &t, role >= Role::Operator).await;
+        let _ = t
+            .edit_message_text(&bot, msg.chat().id, msg.id(), text)
+            .reply_markup(keyboard)
+            .await;
+        return Ok(());
+    }
+
+    if let Some(id) = data.strip_prefix("cancel_") {
+        let Ok(id) = id.parse::<i32>() else {
+            return Ok(());
+        };
+        let answer = if role < Role::Operator {
+            t.label(TemplateId::NotPermitted)
+        } else {
+            match state.db.cancel_command(id).await {
+                Ok(true) => t.plain(TemplateId::CommandCancelled, &[("id", id.into())]),
+                Ok(false) => t.label(TemplateId::CommandCancelTooLate),
+                Err(e) => {
+                    eprintln!("Failed to cancel command {}: {}", id, e);
+                    t.label(TemplateId::UpdateFailed)
+                }
+            }
+        };
+        bot.answer_callback_query(q.id.clone()).text(answer).await?;
+        let (text, keyboard) = queue_view(&state.db, &t, role >= Role::Operator).await;
+        let _ = t
+            .edit_message_text(&bot, msg.chat().id, msg.id(), text)
+            .reply_markup(keyboard)
+            .await;
+        return Ok(());
+    }
+
+    if let Some(id) = data.strip_prefix("revoke_token_") {
+        let Ok(id) = id.parse::<i32>() else {
+            return Ok(());
+        };
+        let answer = match state.db.revoke_api_token(user_id, id).await {
+            Ok(true) => t.label(TemplateId::TokenRevoked),
+            Ok(false) => t.label(TemplateId::TokenAlreadyRevoked),
+            Err(e) => {
+                eprintln!("Failed to revoke API token {}: {}", id, e);
+                t.label(TemplateId::UpdateFailed)
+            }
+        };
+        bot.answer_callback_query(q.id.clone()).text(answer).await?;
+        let (text, keyboard) = tokens_view(&state.db, &t, user_id).await;
+        let _ = t
+            .edit_message_text(&bot, msg.chat().id, msg.id(), text)
+            .reply_markup(keyboard)
+            .await;
+        return Ok(());
+    }
+
+    if data == "weekly" {
+        bot.answer_callback_query(q.id.clone()).await?;
+        let report = responses::build_weekly_report(&state.db, &t).await;
+        t.send_message(&bot, msg.chat().id, report).await?;
+        return Ok(());
+    }
+
+    if data == "settings" {
+        bot.answer_callback_query(q.id.clone()).await?;
+        let settings = state
+            .db
+            .get_notification_settings(user_id)
+            .await
+            .unwrap_or_default();
+        let t = state.templates.for_user(&settings);
+        t.edit_message_text(
+            &bot,
+            msg.chat().id,
+            msg.id(),
+            t.text(TemplateId::SettingsTitle),
+        )
+        .reply_markup(settings_keyboard(&t, &settings))
+        .await?;
+        return Ok(());
+    }
+
+    if data.starts_with("unit_") {
+        handle_unit_toggle(&bot, &q, &state, settings, data, msg).await?;
+        return Ok(());
+    }
+
+    if let Some(view) = data.strip_prefix("power_") {
+        let text = match view {
+            "history" => responses::build_power_history(&state.db, &t).await,
+            _ => match PowerPeriod::from_name(view) {
+                Some(period) => responses::build_power_stats(&state.db, &t, period).await,
+                None => return Ok(()),
+            },
+        };
+        bot.answer_callback_query(q.id.clone()).await?;
+        t.edit_message_text(&bot, msg.chat().id, msg.id(), text)
+            .reply_markup(power_keyboard(&t))
+            .await?;
+        return Ok(());
+    }
+
+    if data == "charts" {
+        bot.answer_callback_query(q.id.clone()).await?;
+        t.edit_message_text(
+            &bot,
+            msg.chat().id,
+            msg.id(),
+            t.text(TemplateId::ChartSelectMetric),
+        )
+        .reply_markup(chart_metric_keyboard(&t))
+        .await?;
+        return Ok(());
+    }
+
+    if let Some(choice) = data.strip_prefix("chart_") {
+        handle_chart(&bot, &q, &state, &t, choice, msg).await?;
+        return Ok(());
+    }
+
+    if data.starts_with("water_") && role < Role::Operator {
+        bot.answer_callback_query(q.id.clone())
+            .text(t.label(TemplateId::NotPermitted))
+            .await?;
+        return Ok(());
+    }
+
+    if data == "water_custom" {
+        bot.answer_callback_query(q.id.clone()).await?;
+        let _ = dialogue.update(State::EnterWaterDuration).await;
+        let text = t.render(
+            TemplateId::WaterCustomPrompt,
+            &[
+                ("min", water::PUMP_MIN_SECS.into()),
+                ("max", water::PUMP_MAX_SECS.into()),
+            ],
+        );
+        t.edit_message_text(&bot, msg.chat().id, msg.id(), text)
+            .await?;
+        return Ok(());
+    }
+
+    if data.starts_with("water_") {
+        if let Some(duration_str) = data.strip_prefix("water_") {
+            let duration = duration_str
+                .parse::<i32>()
+                .ok()
+                .filter(|secs| (water::PUMP_MIN_SECS..=water::PUMP_MAX_SECS).contains(secs));
+            if let Some(duration) = duration {
+                match state.db.add_pump_command(duration, Some(user_id)).await {
+                    Ok(_) => {
+                        bot.answer_callback_query(q.id.clone())
+                            .text(t.plain(TemplateId::WaterQueued, &[("seconds", duration.into())]))
+                            .await?;
+                        bot.delete_message(msg.chat().id, msg.id()).await?;
+                    }
+                    Err(_) => {
+                        bot.answer_callback_query(q.id.clone())
+                            .text(t.label(TemplateId::WaterQueueFailed))
+                            .await?;
+                    }
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    if data == "back" {
+        bot.answer_callback_query(q.id.clone()).await?;
+        bot.delete_message(msg.chat().id, msg.id()).await?;
+    }
+
+    Ok(())
+}
+
+/// Queued and recent commands, with cancel buttons for those allowed to water
+async fn queue_view(
+    db: &Db,
+    t: &Localized<'_>,
+    can_cancel: bool,
+) -> (String, InlineKeyboardMarkup) {
+    let commands = db
+        .get_command_queue(queue::RECENT_COMMANDS)
+        .await
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to load the command queue: {}", e);
+            Vec::new()
+        });
+    let text = responses::format_queue(t, &commands);
+    (text, queue_keyboard(t, &commands, can_cancel))
+}
+
+/// The user's own role, or viewer for any member of a linked group
+async fn callback_role(db: &Db, user_id: i64, chat: &Chat) -> Option<Role> {
+    let role = db.get_user_role(user_id).await.ok().flatten();
+    if role.is_none() && !chat.is_private() && db.is_chat_authorized(chat.id.0).await.ok()? {
+        return Some(Role::Viewer);
+    }
+    role
+}
+
+/// Sends the status as a new message, pins it and keeps it up to date,
+/// replacing the chat's previous live status
+async fn start_live_status(
+    bot: &Bot,
+    q: &CallbackQuery,
+    state: &BotState,
+    t: &Localized<'_>,
+    user_id: i64,
+    msg: &teloxide::types::MaybeInaccessibleMessage,
+) -> ResponseResult<()> {
+    let chat_id = msg.chat().id;
+    let reading = match LiveReading::latest(&state.db).await {
+        Ok(Some(reading)) => reading,
+        Ok(None) => {
+            bot.answer_callback_query(q.id.clone())
+                .text(t.label(TemplateId::NoSensorData))
+                .await?;
+            return Ok(());
+        }
+        Err(e) => {
+            eprintln!("Failed to load the latest reading: {}", e);
+            bot.answer_callback_query(q.id.clone())
+                .text(t.label(TemplateId::UpdateFailed))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    bot.answer_callback_query(q.id.clone()).await?;
+    let sent = t
+        .send_message(bot, chat_id, reading.format(t))
+        .reply_markup(live_status_keyboard(t))
+        .await?;
+
+    if let Ok(Some(previous)) = state.db.remove_live_status(chat_id.0).await {
+        let previous = MessageId(previous);
+        let _ = bot.unpin_chat_message(chat_id).message_id(previous).await;
+        let _ = bot.edit_message_reply_markup(chat_id, previous).await;
+    }
+    if let Err(e) = bot
+        .pin_chat_message(chat_id, sent.id)
+        .disable_notification(true)
+        .await
+    {
+        eprintln!("Failed to pin live status in {}: {}", chat_id, e);
+    }
+    if let Err(e) = state
+        .db
+        .add_live_status(chat_id.0, sent.id.0, user_id, reading.id, reading.offline)
+        .await
+    {
+        eprintln!("Failed to save live status: {}", e);
+    }
+    Ok(())
+}
+
+/// `chart_<metric>` picks the metric, `chart_<metric>_<period>` sends the chart
+async fn handle_chart(
+    bot: &Bot,
+    q: &CallbackQuery,
+    state: &BotState,
+    t: &Localized<'_>,
+    choice: &str,
+    msg: &teloxide::types::MaybeInaccessibleMessage,
+) -> ResponseResult<()> {
+    if let Some(metric) = ChartMetric::from_name(choice) {
+        bot.answer_callback_query(q.id.clone()).await?;
+        let text = t.render(
+            TemplateId::ChartSelectPeriod,
+            &[("metric", t.label(metric.label()).into())],
+        );
+        t.edit_message_text(bot, msg.chat().id, msg.id(), text)
+            .reply_markup(chart_period_keyboard(t, metric))
+            .await?;
+        return Ok(());
+    }
+
+    let Some((metric, period)) = choice.rsplit_once('_').and_then(|(metric, period)| {
+        Some((
+            ChartMetric::from_name(metric)?,
+            PowerPeriod::from_name(period)?,
+        ))
+    }) else {
+        return Ok(());
+    };
+
+    bot.send_chat_action(msg.chat().id, ChatAction::UploadPhoto)
+        .await?;
+    let span = Duration::days(period.days());
+    let label = t.label(period.label());
+    let Some(png) = responses::build_chart(&state.db, t, metric, span, label).await else {
+        bot.answer_callback_query(q.id.clone())
+            .text(t.label(TemplateId::ChartNoData))
+            .await?;
+        return Ok(());
+    };
+
+    bot.answer_callback_query(q.id.clone()).await?;
+    bot.send_photo(msg.chat().id, InputFile::memory(png).file_name("chart.png"))
+        .await?;
+    Ok(())
+}
+
+async fn handle_toggle(
+    bot: &Bot,
+    q: &CallbackQuery,
+    state: &BotState,
+    user_id: i64,
+    data: &str,
+    msg: &teloxide::types::MaybeInaccessibleMessage,
+) -> ResponseResult<()> {
+    if data == "toggle_language" {
+        return handle_language_toggle(bot, q, state, user_id, msg).await;
+    }
+
+    let result = match data {
+        "toggle_soil" => state.db.toggle_soil_alerts(user_id).await,
+        "toggle_temp" => state.db.toggle_temperature_alerts(user_id).await,
+        "toggle_power" => state.db.toggle_power_alerts(user_id).await,
+        "toggle_outlook" => state.db.toggle_outlook_alerts(user_id).await,
+        "toggle_quiet" => state.db.toggle_quiet_hours(user_id).await,
+        "toggle_morning" => state.db.toggle_morning_summary(user_id).await,
+        "toggle_weekly" => state.db.toggle_weekly_report(user_id).await,
+        _ => return Ok(()),
+    };
+
+    let Ok(settings) = state.db.get_notification_settings(user_id).await else {
+        bot.answer_callback_query(q.id.clone()).await?;
+        return Ok(());
+    };
+    let t = state.templates.for_user(&settings);
+
+    let notification = match result {
+        Ok(enabled) => {
+            let name = match data {
+                "toggle_soil" => TemplateId::SoilAlerts,
+                "toggle_temp" => TemplateId::TemperatureAlerts,
+                "toggle_power" => TemplateId::PowerAlerts,
+                "toggle_outlook" => TemplateId::OutlookAlerts,
+                "toggle_morning" => TemplateId::MorningSummaries,
+                "toggle_weekly" => TemplateId::WeeklyReports,
+                _ => TemplateId::QuietHours,
+            };
+            let id = if enabled {
+                TemplateId::SettingEnabled
+            } else {
+                TemplateId::SettingDisabled
+            };
+            t.plain(id, &[("name", t.label(name).into())])
+        }
+        Err(_) => t.label(TemplateId::UpdateFailed),
+    };
+
+    bot.answer_callback_query(q.id.clone())
+        .text(&notification)
+        .await?;
+
+    t.edit_message_text(
+        bot,
+        msg.chat().id,
+        msg.id(),
+        t.text(TemplateId::SettingsTitle),
+    )
+    .reply_markup(settings_keyboard(&t, &settings))
+    .await?;
+
+    Ok(())
+}
+
+/// `morning_time` opens the hour picker, `morning_<hour>` sets the hour
+async fn handle_morning_hour(
+    bot: &Bot,
+    q: &CallbackQuery,
+    state: &BotState,
+    user_id: i64,
+    choice: &str,
+    msg: &teloxide::types::MaybeInaccessibleMessage,
+) -> ResponseResult<()> {
+    if choice == "time" {
+        bot.answer_callback_query(q.id.clone()).await?;
+        let settings = state
+            .db
+            .get_notification_settings(user_id)
+            .await
+            .unwrap_or_default();
+        let t = state.templates.for_user(&settings);
+        let text = t.render(
+            TemplateId::MorningHourSelect,
+            &[("timezone", settings.timezone.as_str().into())],
+        );
+        t.edit_message_text(bot, msg.chat().id, msg.id(), text)
+            .reply_markup(morning_hour_keyboard(&t))
+            .await?;
+        return Ok(());
+    }
+
+    let Some(hour) = choice.parse::<i16>().ok().filter(|h| (0..24).contains(h)) else {
+        bot.answer_callback_query(q.id.clone()).await?;
+        return Ok(());
+    };
+    let result = state.db.set_morning_summary_hour(user_id, hour).await;
+
+    let settings = state
+        .db
+        .get_notification_settings(user_id)
+        .await
+        .unwrap_or_default();
+    let t = state.templates.for_user(&settings);
+
+    let notification = match result {
+        Ok(()) => t.plain(
+            TemplateId::SettingEnabled,
+            &[("name", t.label(TemplateId::MorningSummaries).into())],
+        ),
+        Err(_) => t.label(TemplateId::UpdateFailed),
+    };
+    bot.answer_callback_query(q.id.clone())
+        .text(&notification)
+        .await?;
+
+    t.edit_message_text(
+        bot,
+        msg.chat().id,
+        msg.id(),
+        t.text(TemplateId::SettingsTitle),
+    )
+    .reply_markup(settings_keyboard(&t, &settings))
+    .await?;
+    Ok(())
+}
+
+async fn handle_language_toggle(
+    bot: &Bot,
+    q: &CallbackQuery,
+    state: &BotState,
+    user_id: i64,
+    msg: &teloxide::types::MaybeInaccessibleMessage,
+) -> ResponseResult<()> {
+    let Ok(mut settings) = state.db.get_notification_settings(user_id).await else {
+        bot.answer_callback_query(q.id.clone()).await?;
+        return Ok(());
+    };
+
+    if state
+        .db
+        .set_locale(user_id, settings.locale.next())
+        .await
+        .is_err()
+    {
+        let t = state.templates.for_user(&settings);
+        bot.answer_callback_query(q.id.clone())
+            .text(t.label(TemplateId::UpdateFailed))
+            .await?;
+        return Ok(());
+    }
+
+    settings.locale = settings.locale.next();
+    let t = state.templates.for_user(&settings);
+    bot.answer_callback_query(q.id.clone()).await?;
+    t.edit_message_text(
+        bot,
+        msg.chat().id,
+        msg.id(),
+        t.text(TemplateId::SettingsTitle),
+    )
+    .reply_markup(settings_keyboard(&t, &settings))
+    .await?;
+
+    // The reply keyboard only changes when a new message carries it
+    t.send_message(bot, msg.chat().id, t.text(TemplateId::LanguageChanged))
+        .reply_markup(main_keyboard(&t))
+        .await?;
+
+    Ok(())
+}
+
+async fn handle_unit_toggle(
+    bot: &Bot,
+    q: &CallbackQuery,
+    state: &BotState,
+    mut settings: NotificationSettings,
+    data: &str,
+    msg: &teloxide::types::MaybeInaccessibleMessage,
+) -> ResponseResult<()> {
+    let mut units = settings.units();
+    match data {
+        "unit_temperature" => units.temperature = units.temperature.next(),
+        "unit_pressure" => units.pressure = units.pressure.next(),
+        "unit_volume" => units.volume = units.volume.next(),
+        _ => return Ok(()),
+    }
+
+    let t = state.templates.for_user(&settings);
+    if state
+        .db
+        .set_units(settings.telegram_user_id, units)
+        .await
+        .is_err()
+    {
+        bot.answer_callback_query(q.id.clone())
+            .text(t.label(TemplateId::UpdateFailed))
+            .await?;
+        return Ok(());
+    }
+
+    settings.temperature_unit = units.temperature;
+    settings.pressure_unit = units.pressure;
+    settings.volume_unit = units.volume;
+    let t = state.templates.for_user(&settings);
+
+    bot.answer_callback_query(q.id.clone()).await?;
+    t.edit_message_text(
+        bot,
+        msg.chat().id,
+        msg.id(),
+        t.text(TemplateId::SettingsTitle),
+    )
+    .reply_markup(settings_keyboard(&t, &settings))
+    .await?;
+
     Ok(())
 }
@@ -2,66 +2,362 @@ use teloxide::types::{
     InlineKeyboardButton, InlineKeyboardMarkup, KeyboardButton, KeyboardMarkup,
 };
 
-use crate::db::NotificationSettings;
+use crate::db::{ApiToken, CommandStatus, DeviceCommand, Locale, NotificationSettings};
+use crate::services::{ChartMetric, PowerPeriod};
+use crate::templates::{Localized, TemplateId, Templates};
+
+/// Reply keyboard buttons, matched by their label in any locale
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuButton {
+    Status,
+    Weather,
+    Garden,
+    Stats,
+    Power,
+    Water,
+    Outlook,
+    Charts,
+    Queue,
+    Settings,
+}
+
+impl MenuButton {
+    const ALL: &'static [MenuButton] = &[
+        MenuButton::Status,
+        MenuButton::Weather,
+        MenuButton::Garden,
+        MenuButton::Stats,
+        MenuButton::Power,
+        MenuButton::Water,
+        MenuButton::Outlook,
+        MenuButton::Charts,
+        MenuButton::Queue,
+        MenuButton::Settings,
+    ];
+
+    fn label(self) -> TemplateId {
+        match self {
+            MenuButton::Status => TemplateId::ButtonStatus,
+            MenuButton::Weather => TemplateId::ButtonWeather,
+            MenuButton::Garden => TemplateId::ButtonGarden,
+            MenuButton::Stats => TemplateId::ButtonStats,
+            MenuButton::Power => TemplateId::ButtonPower,
+            MenuButton::Water => TemplateId::ButtonWater,
+            MenuButton::Outlook => TemplateId::ButtonOutlook,
+            MenuButton::Charts => TemplateId::ButtonCharts,
+            MenuButton::Queue => TemplateId::ButtonQueue,
+            MenuButton::Settings => TemplateId::ButtonSettings,
+        }
+    }
+
+    pub fn parse(templates: &Templates, text: &str) -> Option<Self> {
+        Locale::ALL.iter().find_map(|&locale| {
+            let t = templates.locale(locale);
+            Self::ALL
+                .iter()
+                .copied()
+                .find(|button| t.label(button.label()) == text)
+        })
+    }
+}
+
+pub fn main_keyboard(t: &Localized<'_>) -> KeyboardMarkup {
+    let button = |b: MenuButton| KeyboardButton::new(t.label(b.label()));
 
-pub fn main_keyboard() -> KeyboardMarkup {
     KeyboardMarkup::new(vec![
-        vec![
-            KeyboardButton::new("📊 Status"),
-            KeyboardButton::new("🌤 Weather"),
-        ],
-        vec![
-            KeyboardButton::new("🌱 Garden"),
-            KeyboardButton::new("📈 Stats"),
-        ],
-        vec![
-            KeyboardButton::new("⚡ Power"),
-            KeyboardButton::new("💧 Water"),
-        ],
-        vec![KeyboardButton::new("⚙️ Settings")],
+        vec![button(MenuButton::Status), button(MenuButton::Weather)],
+        vec![button(MenuButton::Garden), button(MenuButton::Stats)],
+        vec![button(MenuButton::Power), button(MenuButton::Water)],
+        vec![button(MenuButton::Outlook), button(MenuButton::Charts)],
+        vec![button(MenuButton::Queue), button(MenuButton::Settings)],
     ])
     .resize_keyboard()
     .persistent()
 }
 
-pub fn water_duration_keyboard() -> InlineKeyboardMarkup {
+pub fn water_duration_keyboard(t: &Localized<'_>) -> InlineKeyboardMarkup {
+    let button = |secs: i32| {
+        InlineKeyboardButton::callback(
+            t.plain(TemplateId::ButtonWaterSeconds, &[("seconds", secs.into())]),
+            format!("water_{}", secs),
+        )
+    };
+
     InlineKeyboardMarkup::new(vec![
+        vec![button(5), button(10), button(15)],
         vec![
-            InlineKeyboardButton::callback("5 sec", "water_5"),
-            InlineKeyboardButton::callback("10 sec", "water_10"),
-            InlineKeyboardButton::callback("15 sec", "water_15"),
-        ],
-        vec![
-            InlineKeyboardButton::callback("20 sec", "water_20"),
-            InlineKeyboardButton::callback("30 sec", "water_30"),
+            button(20),
+            button(30),
+            InlineKeyboardButton::callback(
+                t.label(TemplateId::ButtonWaterCustom),
+                "water_custom",
+            ),
         ],
-        vec![InlineKeyboardButton::callback("« Cancel", "back")],
+        vec![InlineKeyboardButton::callback(
+            t.label(TemplateId::ButtonCancel),
+            "back",
+        )],
     ])
 }
 
-pub fn settings_keyboard(settings: &NotificationSettings) -> InlineKeyboardMarkup {
-    let soil_icon = if settings.soil_moisture_alerts { "✅" } else { "❌" };
-    let temp_icon = if settings.temperature_alerts { "✅" } else { "❌" };
-    let power_icon = if settings.power_alerts { "✅" } else { "❌" };
-    let quiet_icon = if settings.quiet_hours_enabled { "✅" } else { "❌" };
+/// Cancel buttons for queued commands, then a refresh button
+pub fn queue_keyboard(
+    t: &Localized<'_>,
+    commands: &[DeviceCommand],
+    can_cancel: bool,
+) -> InlineKeyboardMarkup {
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = commands
+        .iter()
+        .filter(|c| can_cancel && c.status == CommandStatus::Queued)
+        .map(|c| {
+            vec![InlineKeyboardButton::callback(
+                t.plain(
+                    TemplateId::ButtonCancelCommand,
+                    &[("id", c.id.into()), ("seconds", c.duration_secs.into())],
+                ),
+                format!("cancel_{}", c.id),
+            )]
+        })
+        .collect();
+    rows.push(vec![InlineKeyboardButton::callback(
+        t.label(TemplateId::ButtonRefresh),
+        "queue",
+    )]);
+    InlineKeyboardMarkup::new(rows)
+}
+
+/// A revoke button per token
+pub fn tokens_keyboard(t: &Localized<'_>, tokens: &[ApiToken]) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(tokens.iter().map(|token| {
+        vec![InlineKeyboardButton::callback(
+            t.plain(
+                TemplateId::ButtonRevokeToken,
+                &[("name", token.name.as_str().into())],
+            ),
+            format!("revoke_token_{}", token.id),
+        )]
+    }))
+}
+
+pub fn water_confirm_keyboard(t: &Localized<'_>, seconds: i32) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![vec![
+        InlineKeyboardButton::callback(
+            t.label(TemplateId::ButtonConfirm),
+            format!("water_{}", seconds),
+        ),
+        InlineKeyboardButton::callback(t.label(TemplateId::ButtonCancel), "back"),
+    ]])
+}
+
+pub fn stats_keyboard(t: &Localized<'_>) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+        t.label(TemplateId::ButtonWeeklyReport),
+        "weekly",
+    )]])
+}
+
+pub fn status_keyboard(t: &Localized<'_>) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+        t.label(TemplateId::ButtonPinLiveStatus),
+        "live_on",
+    )]])
+}
+
+/// Under the pinned status the server keeps editing
+pub fn live_status_keyboard(t: &Localized<'_>) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+        t.label(TemplateId::ButtonStopLiveStatus),
+        "live_off",
+    )]])
+}
+
+/// Hours from 04:00 to 11:00 for the morning summary, callbacks `morning_<hour>`
+pub fn morning_hour_keyboard(t: &Localized<'_>) -> InlineKeyboardMarkup {
+    let hours: Vec<i16> = (4..12).collect();
+    let mut rows: Vec<Vec<_>> = hours
+        .chunks(4)
+        .map(|row| {
+            row.iter()
+                .map(|hour| {
+                    InlineKeyboardButton::callback(
+                        t.plain(
+                            TemplateId::ButtonHour,
+                            &[("hour", format!("{:02}", hour).into())],
+                        ),
+                        format!("morning_{}", hour),
+                    )
+                })
+                .collect()
+        })
+        .collect();
+
+    rows.push(vec![InlineKeyboardButton::callback(
+        t.label(TemplateId::ButtonBack),
+        "settings",
+    )]);
+    InlineKeyboardMarkup::new(rows)
+}
+
+pub fn power_keyboard(t: &Localized<'_>) -> InlineKeyboardMarkup {
+    let periods = PowerPeriod::ALL
+        .iter()
+        .map(|period| {
+            InlineKeyboardButton::callback(
+                t.label(period.button()),
+                format!("power_{}", period.name()),
+            )
+        })
+        .collect();
 
     InlineKeyboardMarkup::new(vec![
+        periods,
         vec![InlineKeyboardButton::callback(
-            format!("{} Soil moisture", soil_icon),
+            t.label(TemplateId::ButtonPowerHistory),
+            "power_history",
+        )],
+    ])
+}
+
+pub fn chart_metric_keyboard(t: &Localized<'_>) -> InlineKeyboardMarkup {
+    let rows: Vec<Vec<_>> = ChartMetric::ALL
+        .chunks(2)
+        .map(|metrics| {
+            metrics
+                .iter()
+                .map(|metric| {
+                    InlineKeyboardButton::callback(
+                        t.label(metric.label()),
+                        format!("chart_{}", metric.name()),
+                    )
+                })
+                .collect()
+        })
+        .collect();
+
+    InlineKeyboardMarkup::new(rows)
+}
+
+pub fn chart_period_keyboard(t: &Localized<'_>, metric: ChartMetric) -> InlineKeyboardMarkup {
+    let periods = PowerPeriod::ALL
+        .iter()
+        .map(|period| {
+            InlineKeyboardButton::callback(
+                t.label(period.button()),
+                format!("chart_{}_{}", metric.name(), period.name()),
+            )
+        })
+        .collect();
+
+    InlineKeyboardMarkup::new(vec![
+        periods,
+        vec![InlineKeyboardButton::callback(
+            t.label(TemplateId::ButtonBack),
+            "charts",
+        )],
+    ])
+}
+
+pub fn settings_keyboard(
+    t: &Localized<'_>,
+    settings: &NotificationSettings,
+) -> InlineKeyboardMarkup {
+    let icon = |enabled: bool| if enabled { "✅" } else { "❌" };
+    let units = settings.units();
+
+    InlineKeyboardMarkup::new(vec![
+        vec![InlineKeyboardButton::callback(
+            t.plain(
+                TemplateId::SettingSoil,
+                &[("icon", icon(settings.soil_moisture_alerts).into())],
+            ),
             "toggle_soil",
         )],
         vec![InlineKeyboardButton::callback(
-            format!("{} Temperature", temp_icon),
+            t.plain(
+                TemplateId::SettingTemperature,
+                &[("icon", icon(settings.temperature_alerts).into())],
+            ),
             "toggle_temp",
         )],
         vec![InlineKeyboardButton::callback(
-            format!("{} Power outage", power_icon),
+            t.plain(
+                TemplateId::SettingPower,
+                &[("icon", icon(settings.power_alerts).into())],
+            ),
             "toggle_power",
         )],
         vec![InlineKeyboardButton::callback(
-            format!("{} Quiet hours (23-07)", quiet_icon),
+            t.plain(
+                TemplateId::SettingOutlook,
+                &[("icon", icon(settings.outlook_alerts).into())],
+            ),
+            "toggle_outlook",
+        )],
+        vec![
+            InlineKeyboardButton::callback(
+                t.plain(
+                    TemplateId::SettingMorningSummary,
+                    &[("icon", icon(settings.morning_summary).into())],
+                ),
+                "toggle_morning",
+            ),
+            InlineKeyboardButton::callback(
+                t.plain(
+                    TemplateId::SettingMorningHour,
+                    &[(
+                        "hour",
+                        format!("{:02}", settings.morning_summary_hour).into(),
+                    )],
+                ),
+                "morning_time",
+            ),
+        ],
+        vec![InlineKeyboardButton::callback(
+            t.plain(
+                TemplateId::SettingWeeklyReport,
+                &[("icon", icon(settings.weekly_report).into())],
+            ),
+            "toggle_weekly",
+        )],
+        vec![InlineKeyboardButton::callback(
+            t.plain(
+                TemplateId::SettingQuietHours,
+                &[
+                    ("icon", icon(settings.quiet_hours_enabled).into()),
+                    ("start", format!("{:02}", settings.quiet_hours_start).into()),
+                    ("end", format!("{:02}", settings.quiet_hours_end).into()),
+                ],
+            ),
             "toggle_quiet",
         )],
-        vec![InlineKeyboardButton::callback("« Back", "back")],
+        vec![InlineKeyboardButton::callback(
+            t.plain(
+                TemplateId::SettingTemperatureUnit,
+                &[("unit", t.unit_symbol(units.temperature.template()).into())],
+            ),
+            "unit_temperature",
+        )],
+        vec![InlineKeyboardButton::callback(
+            t.plain(
+                TemplateId::SettingPressureUnit,
+                &[("unit", t.unit_symbol(units.pressure.template()).into())],
+            ),
+            "unit_pressure",
+        )],
+        vec![InlineKeyboardButton::callback(
+            t.plain(
+                TemplateId::SettingVolumeUnit,
+                &[("unit", t.unit_symbol(units.volume.template()).into())],
+            ),
+            "unit_volume",
+        )],
+        vec![InlineKeyboardButton::callback(
+            t.label(TemplateId::SettingLanguage),
+            "toggle_language",
+        )],
+        vec![InlineKeyboardButton::callback(
+            t.label(TemplateId::ButtonBack),
+            "back",
+        )],
     ])
 }
@@ -1,26 +1,70 @@
+mod args;
 mod handlers;
 mod keyboard;
 mod responses;
+mod storage;
 
 use std::sync::Arc;
 
 use teloxide::{
-    dispatching::{dialogue::InMemStorage, UpdateFilterExt},
+    dispatching::UpdateFilterExt,
     dptree,
     prelude::*,
+    types::{MenuButton, WebAppInfo},
     update_listeners::webhooks::{self, Options},
+    utils::command::BotCommands,
 };
 
-pub use handlers::{BotState, Command, State};
+pub use handlers::{AdminCommand, BotState, Command, GroupCommand, PublicCommand, State};
+pub use keyboard::live_status_keyboard;
+pub use responses::{build_chart, build_morning_summary, build_weekly_report, format_status};
+use storage::DbStorage;
+
+use crate::config::mini_app;
+use crate::db::Locale;
+use crate::templates::TemplateId;
 
 pub async fn init_bot(
     bot: Arc<Bot>,
     webhook_secret: String,
     bot_state: BotState,
 ) -> anyhow::Result<axum::Router> {
+    // Group chats only take commands and keep no dialogue, so chatter is never
+    // mistaken for a secret
+    let group_handler = Update::filter_message()
+        .filter(|msg: Message| !msg.chat.is_private())
+        .branch(
+            dptree::entry()
+                .filter_command::<GroupCommand>()
+                .endpoint(handlers::handle_group_link_command),
+        )
+        .branch(
+            dptree::entry()
+                .filter_command::<Command>()
+                .endpoint(handlers::handle_group_command),
+        )
+        .branch(dptree::endpoint(|| async { respond(()) }));
+
     let message_handler = Update::filter_message()
-        .enter_dialogue::<Message, InMemStorage<State>, State>()
-        .branch(dptree::case![State::Unauthorized].endpoint(handlers::handle_unauthorized))
+        .enter_dialogue::<Message, DbStorage, State>()
+        .branch(
+            dptree::case![State::Unauthorized]
+                .branch(
+                    dptree::entry()
+                        .filter_command::<PublicCommand>()
+                        .endpoint(handlers::handle_public_command),
+                )
+                .branch(dptree::endpoint(handlers::handle_unauthorized)),
+        )
+        .branch(
+            dptree::case![State::Subscriber]
+                .branch(
+                    dptree::entry()
+                        .filter_command::<PublicCommand>()
+                        .endpoint(handlers::handle_public_command),
+                )
+                .branch(dptree::endpoint(handlers::handle_subscriber)),
+        )
         .branch(
             dptree::case![State::Authorized]
                 .branch(
@@ -28,14 +72,26 @@ pub async fn init_bot(
                         .filter_command::<Command>()
                         .endpoint(handlers::handle_command),
                 )
+                .branch(
+                    dptree::entry()
+                        .filter_command::<AdminCommand>()
+                        .endpoint(handlers::handle_admin_command),
+                )
                 .branch(dptree::endpoint(handlers::handle_message)),
-        );
+        )
+        .branch(dptree::case![State::EnterWaterDuration].endpoint(handlers::handle_water_duration));
+
+    let callback_handler = Update::filter_callback_query()
+        .enter_dialogue::<CallbackQuery, DbStorage, State>()
+        .endpoint(handlers::handle_callback);
 
-    let callback_handler = Update::filter_callback_query().endpoint(handlers::handle_callback);
+    let inline_handler = Update::filter_inline_query().endpoint(handlers::handle_inline_query);
 
     let handler = dptree::entry()
+        .branch(group_handler)
         .branch(message_handler)
-        .branch(callback_handler);
+        .branch(callback_handler)
+        .branch(inline_handler);
 
     let (listener, _stop_flag, router) = webhooks::axum_to_router(
         (*bot).clone(),
@@ -47,10 +103,30 @@ pub async fn init_bot(
     )
     .await?;
 
+    // Lists the commands in Telegram's menu
+    if let Err(e) = bot.set_my_commands(Command::bot_commands()).await {
+        eprintln!("Failed to register bot commands: {}", e);
+    }
+
+    // Opens the Mini App dashboard from the button next to the message field
+    let dashboard = MenuButton::WebApp {
+        text: bot_state
+            .templates
+            .locale(Locale::default())
+            .label(TemplateId::ButtonDashboard),
+        web_app: WebAppInfo {
+            url: mini_app::URL.parse()?,
+        },
+    };
+    if let Err(e) = bot.set_chat_menu_button().menu_button(dashboard).await {
+        eprintln!("Failed to set the menu button: {}", e);
+    }
+
+    let storage = DbStorage::new(bot_state.db.clone());
     let bot_for_dispatcher = (*bot).clone();
     tokio::spawn(async move {
         Dispatcher::builder(bot_for_dispatcher, handler)
-            .dependencies(dptree::deps![InMemStorage::<State>::new(), bot_state])
+            .dependencies(dptree::deps![storage, bot_state])
             .build()
             .dispatch_with_listener(
                 listener,
@@ -1,33 +1,48 @@
-use crate::config::pressure;
-use crate::db::{DailyStats, Db, SensorData};
+use time::{Duration, OffsetDateTime, PrimitiveDateTime};
+use time_tz::{timezones::db::europe::KYIV, Tz};
+
+use crate::config::{morning, outlook, pressure, schedule, water};
+use crate::db::{
+    CommandStatus, DailyStats, Db, DeviceCommand, OutageCause, PowerOutage, SensorData,
+};
 use crate::services::{
-    analyze_pressure, analyze_soil_moisture, analyze_water_level, format_duration_minutes,
-    format_kyiv,
+    analyze_pressure, analyze_soil_moisture, analyze_water_level, chart_data, current_battery,
+    format_in, format_kyiv, format_kyiv_time, format_time_in, outage_outlook, power_availability,
+    render_chart, weekly_comparison, BatteryStatus, ChartLabels, ChartMetric, NotableChange,
+    PowerPeriod,
 };
+use crate::templates::{Localized, TemplateId, Value};
 
-pub async fn build_status(db: &Db) -> String {
+pub async fn build_status(db: &Db, templates: &Localized<'_>) -> String {
     let Some(data) = db.get_latest_sensor_data().await.ok().flatten() else {
-        return "No sensor data available".to_string();
+        return templates.text(TemplateId::NoSensorData);
     };
 
-    format_status(&data)
+    let battery = current_battery(db, &data).await.ok().flatten();
+    format_status(templates, &data, battery.as_ref())
 }
 
-pub fn format_status(data: &SensorData) -> String {
-    format!(
-        "📊 Current Status\n\n\
-         🌡 Temperature: {:.1}°C\n\
-         💧 Humidity: {:.1}%\n\
-         🌪 Pressure: {:.1} hPa\n\
-         🌱 Soil moisture: {:.1}%\n\
-         💦 Water level: {:.1}%",
-        data.temperature, data.humidity, data.pressure, data.soil_moisture, data.water_level
+pub fn format_status(
+    templates: &Localized<'_>,
+    data: &SensorData,
+    battery: Option<&BatteryStatus>,
+) -> String {
+    templates.render(
+        TemplateId::Status,
+        &[
+            ("temperature", Value::Temperature(data.temperature)),
+            ("humidity", data.humidity.into()),
+            ("pressure", Value::Pressure(data.pressure)),
+            ("soil_moisture", data.soil_moisture.into()),
+            ("water_level", data.water_level.into()),
+            ("battery", Value::Fragment(templates.battery(battery))),
+        ],
     )
 }
 
-pub async fn build_weather(db: &Db) -> String {
+pub async fn build_weather(db: &Db, templates: &Localized<'_>) -> String {
     let Some(current) = db.get_latest_sensor_data().await.ok().flatten() else {
-        return "No sensor data available".to_string();
+        return templates.text(TemplateId::NoSensorData);
     };
 
     let pressure_past = db
@@ -39,101 +54,689 @@ pub async fn build_weather(db: &Db) -> String {
     let trend_str = match pressure_past {
         Some(past) => {
             let analysis = analyze_pressure(current.pressure, past);
-            format!(
-                "📉 {}h trend: {} {} ({:+.1} hPa)\n\n\
-                 {} {}",
-                pressure::TREND_HOURS,
-                analysis.trend.symbol(),
-                analysis.trend.label(),
-                analysis.delta,
-                analysis.forecast.emoji,
-                analysis.forecast.message
+            templates.render(
+                TemplateId::WeatherTrend,
+                &[
+                    ("hours", pressure::TREND_HOURS.into()),
+                    ("symbol", analysis.trend.symbol().into()),
+                    (
+                        "label",
+                        Value::Fragment(templates.text(analysis.trend.label())),
+                    ),
+                    ("delta", Value::Pressure(analysis.delta)),
+                    ("forecast_emoji", analysis.forecast.emoji.into()),
+                    (
+                        "forecast",
+                        Value::Fragment(templates.text(analysis.forecast.message)),
+                    ),
+                ],
             )
         }
-        None => "📉 Trend: -- no history yet".to_string(),
+        None => templates.text(TemplateId::WeatherNoTrend),
     };
 
-    format!(
-        "🌤 Weather\n\n\
-         🌡 Temperature: {:.1}°C\n\
-         💧 Humidity: {:.1}%\n\
-         🌪 Pressure: {:.1} hPa\n\n\
-         {}",
-        current.temperature, current.humidity, current.pressure, trend_str
+    templates.render(
+        TemplateId::Weather,
+        &[
+            ("temperature", Value::Temperature(current.temperature)),
+            ("humidity", current.humidity.into()),
+            ("pressure", Value::Pressure(current.pressure)),
+            ("trend", Value::Fragment(trend_str)),
+        ],
     )
 }
 
-pub async fn build_garden(db: &Db) -> String {
+pub async fn build_garden(db: &Db, templates: &Localized<'_>) -> String {
     let Some(data) = db.get_latest_sensor_data().await.ok().flatten() else {
-        return "No sensor data available".to_string();
+        return templates.text(TemplateId::NoSensorData);
     };
 
     let soil = analyze_soil_moisture(data.soil_moisture);
     let water = analyze_water_level(data.water_level);
 
-    format!(
-        "🌱 Garden Status\n\n\
-         🌱 Soil moisture: {:.1}%\n\
-         {} {}\n\n\
-         💦 Water level: {:.1}%\n\
-         {} {}",
-        data.soil_moisture,
-        soil.status.emoji(),
-        soil.message,
-        data.water_level,
-        water.status.emoji(),
-        water.message
+    templates.render(
+        TemplateId::Garden,
+        &[
+            ("soil_moisture", data.soil_moisture.into()),
+            ("soil_emoji", soil.status.emoji().into()),
+            (
+                "soil_message",
+                Value::Fragment(templates.text(soil.message)),
+            ),
+            ("water_level", data.water_level.into()),
+            (
+                "water_volume",
+                Value::Volume(data.water_level / 100.0 * water::TANK_CAPACITY_LITERS),
+            ),
+            ("water_emoji", water.status.emoji().into()),
+            (
+                "water_message",
+                Value::Fragment(templates.text(water.message)),
+            ),
+        ],
     )
 }
 
-pub async fn build_stats(db: &Db) -> String {
+pub async fn build_stats(db: &Db, templates: &Localized<'_>) -> String {
     let Some(stats) = db.get_daily_stats().await.ok().flatten() else {
-        return "No data for today".to_string();
+        return templates.text(TemplateId::StatsNoData);
     };
 
-    format_stats(&stats)
+    format_stats(templates, &stats)
 }
 
-pub fn format_stats(stats: &DailyStats) -> String {
-    format!(
-        "📈 Today's Stats\n\n\
-         🌡 Temperature:\n\
-           Min: {:.1}°C\n\
-           Max: {:.1}°C\n\
-           Avg: {:.1}°C\n\n\
-         💧 Humidity:\n\
-           Min: {:.1}%\n\
-           Max: {:.1}%",
-        stats.min_temp, stats.max_temp, stats.avg_temp, stats.min_humidity, stats.max_humidity
+pub fn format_stats(templates: &Localized<'_>, stats: &DailyStats) -> String {
+    templates.render(
+        TemplateId::Stats,
+        &[
+            ("min_temp", Value::Temperature(stats.min_temp)),
+            ("max_temp", Value::Temperature(stats.max_temp)),
+            ("avg_temp", Value::Temperature(stats.avg_temp)),
+            ("min_humidity", stats.min_humidity.into()),
+            ("max_humidity", stats.max_humidity.into()),
+        ],
     )
 }
 
-pub async fn build_power_history(db: &Db) -> String {
-    let active = db.get_active_outage().await.ok().flatten();
-    let recent = db.get_recent_outages(5).await.unwrap_or_default();
+/// Stats over the last `span`
+pub async fn build_period_stats(db: &Db, templates: &Localized<'_>, span: Duration) -> String {
+    let period = span_label(templates, span);
+    let Some(stats) = db.get_stats_since(span_start(span)).await.ok().flatten() else {
+        return templates.render(TemplateId::StatsPeriodNoData, &[("period", period.into())]);
+    };
 
-    let mut result = String::from("⚡ Power History\n\n");
+    templates.render(
+        TemplateId::StatsPeriod,
+        &[
+            ("period", period.into()),
+            ("min_temp", Value::Temperature(stats.min_temp)),
+            ("max_temp", Value::Temperature(stats.max_temp)),
+            ("avg_temp", Value::Temperature(stats.avg_temp)),
+            ("min_humidity", stats.min_humidity.into()),
+            ("max_humidity", stats.max_humidity.into()),
+        ],
+    )
+}
 
-    if let Some(outage) = active {
-        let started = format_kyiv(outage.started_at);
-        result.push_str(&format!("🔴 Current outage since {}\n\n", started));
+/// "last 12 h" or "last 7 days", without markup
+pub fn span_label(templates: &Localized<'_>, span: Duration) -> String {
+    if span.whole_hours() % 24 == 0 {
+        templates.plain(
+            TemplateId::SpanDays,
+            &[("days", Value::Integer(span.whole_days()))],
+        )
     } else {
-        result.push_str("🟢 Power is OK\n\n");
+        templates.plain(
+            TemplateId::SpanHours,
+            &[("hours", Value::Integer(span.whole_hours()))],
+        )
     }
+}
+
+/// When the last `span` began, in UTC like the tables
+fn span_start(span: Duration) -> PrimitiveDateTime {
+    let at = OffsetDateTime::now_utc() - span;
+    PrimitiveDateTime::new(at.date(), at.time())
+}
+
+/// Whether the power is on right now, or since when it is off, in `tz`
+pub async fn build_power_current(db: &Db, templates: &Localized<'_>, tz: &Tz) -> String {
+    match db.get_active_outage().await.ok().flatten() {
+        Some(outage) => templates.render(
+            TemplateId::PowerCurrentOutage,
+            &[("since", format_in(outage.started_at, tz).into())],
+        ),
+        None => templates.text(TemplateId::PowerOk),
+    }
+}
 
-    if recent.is_empty() {
-        result.push_str("No recent outages");
+pub async fn build_power_history(db: &Db, templates: &Localized<'_>) -> String {
+    let current = build_power_current(db, templates, KYIV).await;
+    let recent = db.get_recent_outages(5).await.unwrap_or_default();
+
+    let recent = if recent.is_empty() {
+        templates.text(TemplateId::PowerNoRecent)
     } else {
-        result.push_str("Recent outages:\n");
-        for outage in recent.iter().filter(|o| o.ended_at.is_some()) {
-            let started = format_kyiv(outage.started_at);
+        templates.render(
+            TemplateId::PowerRecent,
+            &[(
+                "items",
+                Value::Fragment(format_outages(templates, &recent, KYIV)),
+            )],
+        )
+    };
+
+    templates.render(
+        TemplateId::PowerHistory,
+        &[
+            ("current", Value::Fragment(current)),
+            ("recent", Value::Fragment(recent)),
+            ("schedule", Value::Fragment(build_schedule(db, templates).await)),
+        ],
+    )
+}
+
+/// Finished outages of the last `span` with their total
+pub async fn build_outages(db: &Db, templates: &Localized<'_>, span: Duration) -> String {
+    let period = span_label(templates, span);
+    let outages: Vec<PowerOutage> = db
+        .get_power_outages_since(span_start(span))
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|o| o.ended_at.is_some())
+        .collect();
+    if outages.is_empty() {
+        return templates.render(TemplateId::OutagesPeriodNone, &[("period", period.into())]);
+    }
+
+    let total = outages.iter().filter_map(|o| o.duration_minutes).sum();
+    templates.render(
+        TemplateId::OutagesPeriod,
+        &[
+            ("period", period.into()),
+            ("count", Value::Integer(outages.len() as i64)),
+            ("total", Value::Fragment(templates.duration(total))),
+            (
+                "items",
+                Value::Fragment(format_outages(templates, &outages, KYIV)),
+            ),
+        ],
+    )
+}
+
+/// One line per finished outage, with start times in `tz`
+fn format_outages(templates: &Localized<'_>, outages: &[PowerOutage], tz: &Tz) -> String {
+    outages
+        .iter()
+        .filter(|o| o.ended_at.is_some())
+        .map(|outage| {
             let duration = outage
                 .duration_minutes
-                .map(format_duration_minutes)
+                .map(|m| templates.duration(m))
                 .unwrap_or_else(|| "?".to_string());
-            result.push_str(&format!("• {} ({})\n", started, duration));
-        }
+            let id = match outage.cause {
+                OutageCause::Network => TemplateId::PowerRecentItemNetwork,
+                OutageCause::Power | OutageCause::Unknown => TemplateId::PowerRecentItem,
+            };
+            templates.render(
+                id,
+                &[
+                    ("started", format_in(outage.started_at, tz).into()),
+                    ("duration", Value::Fragment(duration)),
+                ],
+            )
+        })
+        .collect()
+}
+
+/// Queued commands, then recently finished ones, each with who queued it and how long ago
+pub fn format_queue(templates: &Localized<'_>, commands: &[DeviceCommand]) -> String {
+    if commands.is_empty() {
+        return templates.text(TemplateId::QueueEmpty);
     }
 
-    result
+    let now = OffsetDateTime::now_utc();
+    let items = commands
+        .iter()
+        .map(|command| {
+            let age = (now - command.created_at.assume_utc()).whole_minutes() as i32;
+            let who = match (command.queued_by, command.username.as_deref()) {
+                (_, Some(username)) => format!("@{}", username),
+                (Some(id), None) => id.to_string(),
+                (None, None) => "—".to_string(),
+            };
+            let state = match command.status {
+                CommandStatus::Queued => TemplateId::QueueStateQueued,
+                CommandStatus::Sent => TemplateId::QueueStateSent,
+                CommandStatus::Cancelled => TemplateId::QueueStateCancelled,
+            };
+            templates.render(
+                TemplateId::QueueItem,
+                &[
+                    ("id", command.id.into()),
+                    ("seconds", command.duration_secs.into()),
+                    ("who", who.into()),
+                    ("age", Value::Fragment(templates.duration(age.max(0)))),
+                    ("state", Value::Fragment(templates.text(state))),
+                ],
+            )
+        })
+        .collect();
+    templates.render(TemplateId::QueueTitle, &[("items", Value::Fragment(items))])
+}
+
+/// Overnight temperatures, waterings and outages of the last day, then the
+/// current status and weather. Times are shown in the recipient's `tz`
+pub async fn build_morning_summary(db: &Db, templates: &Localized<'_>, tz: &Tz) -> String {
+    let now = OffsetDateTime::now_utc();
+    let hours_ago = |hours: i64| {
+        let at = now - Duration::hours(hours);
+        PrimitiveDateTime::new(at.date(), at.time())
+    };
+    let report_since = hours_ago(morning::REPORT_HOURS);
+
+    let overnight = match db
+        .get_temperature_range_since(hours_ago(morning::OVERNIGHT_HOURS))
+        .await
+    {
+        Ok(Some(range)) => templates.render(
+            TemplateId::MorningOvernight,
+            &[
+                ("min_temp", Value::Temperature(range.min)),
+                ("max_temp", Value::Temperature(range.max)),
+            ],
+        ),
+        _ => templates.text(TemplateId::MorningNoOvernight),
+    };
+
+    let hours = || Value::Integer(morning::REPORT_HOURS);
+    let waterings = db
+        .get_watering_times_since(report_since)
+        .await
+        .unwrap_or_default();
+    let waterings = if waterings.is_empty() {
+        templates.render(TemplateId::MorningNoWaterings, &[("hours", hours())])
+    } else {
+        let times: Vec<String> = waterings
+            .into_iter()
+            .map(|at| format_time_in(at, tz))
+            .collect();
+        templates.render(
+            TemplateId::MorningWaterings,
+            &[("hours", hours()), ("times", times.join(", ").into())],
+        )
+    };
+
+    let current = Value::Fragment(build_power_current(db, templates, tz).await);
+    let outages = db
+        .get_power_outages_since(report_since)
+        .await
+        .unwrap_or_default();
+    let items = format_outages(templates, &outages, tz);
+    let outages = if items.is_empty() {
+        templates.render(
+            TemplateId::MorningNoOutages,
+            &[("current", current), ("hours", hours())],
+        )
+    } else {
+        templates.render(
+            TemplateId::MorningOutages,
+            &[
+                ("current", current),
+                ("hours", hours()),
+                ("items", Value::Fragment(items)),
+            ],
+        )
+    };
+
+    let status = build_status(db, templates).await;
+    let weather = build_weather(db, templates).await;
+
+    templates.render(
+        TemplateId::MorningSummary,
+        &[
+            ("overnight", Value::Fragment(overnight)),
+            ("waterings", Value::Fragment(waterings)),
+            ("status", Value::Fragment(status)),
+            ("weather", Value::Fragment(weather)),
+            ("outages", Value::Fragment(outages)),
+        ],
+    )
+}
+
+/// Every metric this week against last week, then the changes worth noticing
+pub async fn build_weekly_report(db: &Db, templates: &Localized<'_>) -> String {
+    let Ok(week) = weekly_comparison(db).await else {
+        return templates.text(TemplateId::StatsNoData);
+    };
+    let (this, last) = (&week.this, &week.last);
+
+    let percent =
+        |value: f32| templates.render(TemplateId::WeeklyPercent, &[("value", value.into())]);
+    let per_day =
+        |value: f32| templates.render(TemplateId::WeeklyPerDay, &[("value", value.into())]);
+    let liters = |percent: f32| {
+        templates.value(Value::Volume(percent / 100.0 * water::TANK_CAPACITY_LITERS))
+    };
+    let count = |value: i64| templates.value(Value::Integer(value));
+    let duration = |minutes: i64| templates.duration(minutes as i32);
+
+    let metrics = [
+        (
+            TemplateId::WeeklyTemperature,
+            this.avg_temp
+                .map(|v| templates.value(Value::Temperature(v))),
+            last.avg_temp
+                .map(|v| templates.value(Value::Temperature(v))),
+        ),
+        (
+            TemplateId::WeeklyHumidity,
+            this.avg_humidity.map(percent),
+            last.avg_humidity.map(percent),
+        ),
+        (
+            TemplateId::WeeklyPressure,
+            this.avg_pressure
+                .map(|v| templates.value(Value::Pressure(v))),
+            last.avg_pressure
+                .map(|v| templates.value(Value::Pressure(v))),
+        ),
+        (
+            TemplateId::WeeklySoil,
+            this.avg_soil.map(percent),
+            last.avg_soil.map(percent),
+        ),
+        (
+            TemplateId::WeeklyWaterLevel,
+            this.avg_water_level.map(percent),
+            last.avg_water_level.map(percent),
+        ),
+        (
+            TemplateId::WeeklySoilDrying,
+            this.soil_drying_per_day.map(per_day),
+            last.soil_drying_per_day.map(per_day),
+        ),
+        (
+            TemplateId::WeeklyWaterings,
+            Some(count(this.waterings)),
+            Some(count(last.waterings)),
+        ),
+        (
+            TemplateId::WeeklyWaterUsed,
+            this.water_used_percent.map(liters),
+            last.water_used_percent.map(liters),
+        ),
+        (
+            TemplateId::WeeklyOutages,
+            Some(duration(this.outage_minutes)),
+            Some(duration(last.outage_minutes)),
+        ),
+        (
+            TemplateId::WeeklyAlerts,
+            Some(count(this.alert_incidents)),
+            Some(count(last.alert_incidents)),
+        ),
+    ];
+
+    let lines: String = metrics
+        .into_iter()
+        .filter_map(|(label, this, last)| {
+            let label = Value::Fragment(templates.text(label));
+            let this = Value::Fragment(this?);
+            Some(match last {
+                Some(last) => templates.render(
+                    TemplateId::WeeklyLine,
+                    &[
+                        ("label", label),
+                        ("this", this),
+                        ("last", Value::Fragment(last)),
+                    ],
+                ),
+                None => templates.render(
+                    TemplateId::WeeklyLineNew,
+                    &[("label", label), ("this", this)],
+                ),
+            })
+        })
+        .collect();
+
+    let notable: String = week
+        .notable_changes()
+        .into_iter()
+        .map(|change| match change {
+            NotableChange::SoilDriedFaster(p) => templates.render(
+                TemplateId::WeeklySoilFaster,
+                &[("percent", Value::Integer(p))],
+            ),
+            NotableChange::SoilDriedSlower(p) => templates.render(
+                TemplateId::WeeklySoilSlower,
+                &[("percent", Value::Integer(p))],
+            ),
+            NotableChange::MoreWaterUsed(p) => templates.render(
+                TemplateId::WeeklyMoreWater,
+                &[("percent", Value::Integer(p))],
+            ),
+            NotableChange::LessWaterUsed(p) => templates.render(
+                TemplateId::WeeklyLessWater,
+                &[("percent", Value::Integer(p))],
+            ),
+            NotableChange::Warmer => templates.text(TemplateId::WeeklyWarmer),
+            NotableChange::Colder => templates.text(TemplateId::WeeklyColder),
+            NotableChange::MoreOutages(m) => templates.render(
+                TemplateId::WeeklyMoreOutages,
+                &[("duration", Value::Fragment(duration(m)))],
+            ),
+            NotableChange::FewerOutages(m) => templates.render(
+                TemplateId::WeeklyFewerOutages,
+                &[("duration", Value::Fragment(duration(m)))],
+            ),
+        })
+        .collect();
+    let notable = if notable.is_empty() {
+        notable
+    } else {
+        templates.render(
+            TemplateId::WeeklyNotable,
+            &[("items", Value::Fragment(notable))],
+        )
+    };
+
+    templates.render(
+        TemplateId::WeeklyReport,
+        &[
+            ("from", format_kyiv(week.from).into()),
+            ("to", format_kyiv(week.to).into()),
+            ("lines", Value::Fragment(lines)),
+            ("notable", Value::Fragment(notable)),
+        ],
+    )
+}
+
+/// Next scheduled window and how past ones played out; empty without a schedule
+async fn build_schedule(db: &Db, templates: &Localized<'_>) -> String {
+    let upcoming = db.get_upcoming_scheduled_outages(1).await.unwrap_or_default();
+    let past = db
+        .get_schedule_comparison(schedule::REPORT_DAYS, schedule::MATCH_TOLERANCE_MINS)
+        .await
+        .unwrap_or_default();
+
+    let mut sections = Vec::new();
+
+    if let Some(next) = upcoming.first() {
+        sections.push(templates.render(
+            TemplateId::PowerScheduleNext,
+            &[
+                ("start", format_kyiv(next.starts_at).into()),
+                ("end", format_kyiv_time(next.ends_at).into()),
+            ],
+        ));
+    }
+
+    if !past.is_empty() {
+        let items: String = past
+            .iter()
+            .map(|window| {
+                let actual = match (window.actual_started_at, window.actual_ended_at) {
+                    (Some(started), Some(ended)) => templates.render(
+                        TemplateId::PowerScheduleActual,
+                        &[
+                            ("start", format_kyiv_time(started).into()),
+                            ("end", format_kyiv_time(ended).into()),
+                        ],
+                    ),
+                    (Some(started), None) => templates.render(
+                        TemplateId::PowerScheduleActualOngoing,
+                        &[("start", format_kyiv_time(started).into())],
+                    ),
+                    _ => templates.text(TemplateId::PowerScheduleNoOutage),
+                };
+                templates.render(
+                    TemplateId::PowerScheduleCompareItem,
+                    &[
+                        ("start", format_kyiv(window.starts_at).into()),
+                        ("end", format_kyiv_time(window.ends_at).into()),
+                        ("actual", Value::Fragment(actual)),
+                    ],
+                )
+            })
+            .collect();
+        sections.push(templates.render(
+            TemplateId::PowerScheduleCompare,
+            &[("items", Value::Fragment(items))],
+        ));
+    }
+
+    sections.iter().map(|s| format!("\n\n{}", s)).collect()
+}
+
+pub async fn build_power_stats(db: &Db, templates: &Localized<'_>, period: PowerPeriod) -> String {
+    let Ok(stats) = power_availability(db, period).await else {
+        return templates.text(TemplateId::StatsNoData);
+    };
+    let current = stats.current;
+    let previous = stats.previous;
+
+    let busiest = stats.outages_by_hour.iter().copied().max().unwrap_or(0);
+    let hours = if busiest == 0 {
+        String::new()
+    } else {
+        let items: String = stats
+            .outages_by_hour
+            .iter()
+            .enumerate()
+            .filter(|(_, &count)| count > 0)
+            .map(|(hour, &count)| {
+                let bar = "▇".repeat(((count * 10 + busiest - 1) / busiest) as usize);
+                templates.render(
+                    TemplateId::PowerStatsHourItem,
+                    &[
+                        ("hour", format!("{:02}", hour).into()),
+                        ("bar", bar.into()),
+                        ("count", Value::Integer(count)),
+                    ],
+                )
+            })
+            .collect();
+        let section = templates.render(
+            TemplateId::PowerStatsHours,
+            &[("items", Value::Fragment(items))],
+        );
+        format!("\n\n{}", section)
+    };
+
+    templates.render(
+        TemplateId::PowerStats,
+        &[
+            ("period", Value::Fragment(templates.text(period.label()))),
+            ("uptime", current.uptime_percent.into()),
+            (
+                "uptime_change",
+                (current.uptime_percent - previous.uptime_percent).into(),
+            ),
+            ("outages", Value::Integer(current.outage_count)),
+            (
+                "outages_change",
+                Value::Integer(current.outage_count - previous.outage_count),
+            ),
+            (
+                "total",
+                Value::Fragment(templates.duration(current.total_outage_minutes as i32)),
+            ),
+            (
+                "longest",
+                Value::Fragment(templates.duration(current.longest_outage_minutes as i32)),
+            ),
+            ("hours", Value::Fragment(hours)),
+        ],
+    )
+}
+
+pub async fn build_outlook(db: &Db, templates: &Localized<'_>) -> String {
+    let Some(forecast) = outage_outlook(db, outlook::HOURS).await.ok().flatten() else {
+        return templates.text(TemplateId::OutlookNoData);
+    };
+
+    let items: String = forecast
+        .hours
+        .iter()
+        .map(|hour| {
+            let icon = match hour.probability {
+                p if p >= outlook::HEADS_UP_PROBABILITY => "🔴",
+                p if p >= 0.3 => "🟡",
+                _ => "🟢",
+            };
+            templates.render(
+                TemplateId::OutlookItem,
+                &[
+                    ("icon", icon.into()),
+                    ("hour", format!("{:02}:00", hour.starts_at.hour()).into()),
+                    ("probability", (hour.probability * 100.0).into()),
+                ],
+            )
+        })
+        .collect();
+
+    templates.render(
+        TemplateId::Outlook,
+        &[
+            ("hours", Value::Integer(outlook::HOURS)),
+            ("overall", (forecast.overall * 100.0).into()),
+            ("items", Value::Fragment(items)),
+        ],
+    )
+}
+
+/// PNG chart of one metric over the last `span`, or `None` without readings.
+/// `period` labels the span in the title.
+pub async fn build_chart(
+    db: &Db,
+    templates: &Localized<'_>,
+    metric: ChartMetric,
+    span: Duration,
+    period: String,
+) -> Option<Vec<u8>> {
+    let data = match chart_data(db, metric, span).await {
+        Ok(data) if !data.points.is_empty() => data,
+        Ok(_) => return None,
+        Err(e) => {
+            eprintln!("Failed to load chart data: {}", e);
+            return None;
+        }
+    };
+
+    let units = templates.units();
+    let unit = match metric.unit(units) {
+        Some(unit) => templates.unit_symbol(unit),
+        None => "%".to_string(),
+    };
+    let labels = ChartLabels {
+        title: templates.plain(
+            TemplateId::ChartTitle,
+            &[
+                ("metric", templates.label(metric.label()).into()),
+                ("unit", unit.into()),
+                ("period", Value::Fragment(period)),
+            ],
+        ),
+        watering: templates.label(TemplateId::ChartWatering),
+        outage: templates.label(TemplateId::ChartOutage),
+    };
+
+    let rendered = tokio::task::spawn_blocking(move || {
+        render_chart(&data, &labels, |value| metric.convert(units, value))
+    })
+    .await;
+
+    match rendered {
+        Ok(Ok(png)) => Some(png),
+        Ok(Err(e)) => {
+            eprintln!("Failed to render chart: {}", e);
+            None
+        }
+        Err(e) => {
+            eprintln!("Chart rendering task failed: {}", e);
+            None
+        }
+    }
 }
@@ -0,0 +1,51 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use teloxide::dispatching::dialogue::Storage;
+use teloxide::types::ChatId;
+
+use super::State;
+use crate::db::Db;
+
+type StorageError = Box<dyn std::error::Error + Send + Sync>;
+type StorageFuture<T> = Pin<Box<dyn Future<Output = Result<T, StorageError>> + Send>>;
+
+/// Dialogue state kept in Postgres as JSON, so restarts and replicas share it
+pub struct DbStorage {
+    db: Db,
+}
+
+impl DbStorage {
+    pub fn new(db: Db) -> Arc<Self> {
+        Arc::new(Self { db })
+    }
+}
+
+impl Storage<State> for DbStorage {
+    type Error = StorageError;
+
+    fn remove_dialogue(self: Arc<Self>, ChatId(chat_id): ChatId) -> StorageFuture<()> {
+        Box::pin(async move { Ok(self.db.remove_dialogue(chat_id).await?) })
+    }
+
+    fn update_dialogue(
+        self: Arc<Self>,
+        ChatId(chat_id): ChatId,
+        dialogue: State,
+    ) -> StorageFuture<()> {
+        Box::pin(async move {
+            let state = serde_json::to_string(&dialogue)?;
+            Ok(self.db.set_dialogue(chat_id, &state).await?)
+        })
+    }
+
+    fn get_dialogue(self: Arc<Self>, ChatId(chat_id): ChatId) -> StorageFuture<Option<State>> {
+        Box::pin(async move {
+            match self.db.get_dialogue(chat_id).await? {
+                Some(state) => Ok(Some(serde_json::from_str(&state)?)),
+                None => Ok(None),
+            }
+        })
+    }
+}
@@ -10,6 +10,13 @@ pub mod soil {
 pub mod water {
     pub const LOW: f32 = 20.0;
     pub const MEDIUM: f32 = 40.0;
+
+    /// Tank volume at 100% (liters)
+    pub const TANK_CAPACITY_LITERS: f32 = 20.0;
+
+    /// Shortest and longest pump run (seconds)
+    pub const PUMP_MIN_SECS: i32 = 1;
+    pub const PUMP_MAX_SECS: i32 = 300;
 }
 
 /// Temperature thresholds (°C)
@@ -44,4 +51,173 @@ pub mod power {
 
     /// How often to check for outages (seconds)
     pub const CHECK_INTERVAL_SECS: u64 = 120;
+
+    /// Heartbeat source name standing for the sensor board's readings
+    pub const SENSOR_SOURCE: &str = "sensor";
+
+    /// Heartbeat sources silent for longer are treated as retired (hours)
+    pub const SOURCE_EXPIRY_HOURS: i32 = 24;
+}
+
+/// Published outage schedule
+pub mod schedule {
+    /// How often to re-import the schedule (seconds)
+    pub const SYNC_INTERVAL_SECS: u64 = 3600;
+
+    /// How far ahead to warn about a scheduled outage (minutes)
+    pub const WARNING_LEAD_MINS: i32 = 30;
+
+    /// Slack when matching actual outages to scheduled windows (minutes)
+    pub const MATCH_TOLERANCE_MINS: i32 = 60;
+
+    /// How far back reports compare scheduled and actual outages (days)
+    pub const REPORT_DAYS: i32 = 7;
+}
+
+/// Outage outlook from our own outage history
+pub mod outlook {
+    /// Weeks of history for the weekday/hour pattern
+    pub const HISTORY_WEEKS: i64 = 8;
+
+    /// Recent days for the daily cadence
+    pub const CADENCE_DAYS: i64 = 7;
+
+    /// Weight of the daily cadence against the weekday pattern
+    pub const CADENCE_WEIGHT: f32 = 0.6;
+
+    /// How many hours ahead the outlook covers
+    pub const HOURS: i64 = 6;
+
+    /// Probability for the coming hour that triggers a heads-up
+    pub const HEADS_UP_PROBABILITY: f32 = 0.7;
+}
+
+/// UPS powering the board and router
+pub mod battery {
+    /// UPS input voltage below this means mains is gone (V)
+    pub const MAINS_MIN_VOLTAGE: f32 = 11.0;
+
+    /// Battery levels that trigger an alert during an outage (%)
+    pub const ALERT_LEVELS: &[f32] = &[50.0, 20.0, 10.0];
+
+    /// Discharge history needed before estimating runtime (minutes)
+    pub const MIN_ESTIMATE_MINUTES: i64 = 10;
+}
+
+/// Sensor history charts
+pub mod charts {
+    /// Image size (pixels)
+    pub const WIDTH: u32 = 1000;
+    pub const HEIGHT: u32 = 500;
+
+    /// Readings are averaged into about this many points per chart
+    pub const POINTS: i64 = 200;
+}
+
+/// Bot authorization
+pub mod auth {
+    /// Wrong secrets or invites in a row before a lockout
+    pub const MAX_FAILURES: i32 = 5;
+
+    /// Failures older than this are forgotten (minutes)
+    pub const FAILURE_WINDOW_MINS: i32 = 60;
+
+    /// How long a lockout lasts (minutes)
+    pub const LOCKOUT_MINS: i32 = 30;
+
+    /// Invite link lifetime when the admin gives none (hours)
+    pub const INVITE_VALID_HOURS: i32 = 24;
+
+    /// How often guests whose access ended are removed (seconds)
+    pub const EXPIRY_CHECK_SECS: u64 = 300;
+}
+
+/// Daily morning summary
+pub mod morning {
+    /// How often to look for summaries that are due (seconds)
+    pub const CHECK_INTERVAL_SECS: u64 = 60;
+
+    /// Hours back the overnight temperature covers
+    pub const OVERNIGHT_HOURS: i64 = 12;
+
+    /// Hours back the outages and waterings cover
+    pub const REPORT_HOURS: i64 = 24;
+}
+
+/// Weekly garden report
+pub mod weekly {
+    /// Sent on this ISO weekday (7 = Sunday) at this local hour
+    pub const WEEKDAY: i32 = 7;
+    pub const HOUR: i32 = 20;
+
+    /// How often to look for reports that are due (seconds)
+    pub const CHECK_INTERVAL_SECS: u64 = 300;
+
+    /// Relative change in soil drying or water use worth calling out (%)
+    pub const NOTABLE_PERCENT: f32 = 20.0;
+
+    /// Change in average temperature worth calling out (°C)
+    pub const NOTABLE_TEMPERATURE: f32 = 2.0;
+
+    /// Change in outage time worth calling out (minutes)
+    pub const NOTABLE_OUTAGE_MINS: i64 = 120;
+}
+
+/// Periods used by text commands given none
+pub mod commands {
+    pub const HISTORY_HOURS: i64 = 24;
+    pub const OUTAGES_DAYS: i64 = 7;
+}
+
+/// Device command queue view
+pub mod queue {
+    /// Picked-up and cancelled commands shown below the queued ones
+    pub const RECENT_COMMANDS: i64 = 5;
+}
+
+/// Personal access tokens for the HTTP API
+pub mod api_tokens {
+    /// Validity of a token created without an expiry
+    pub const DEFAULT_VALID_DAYS: i32 = 90;
+
+    pub const MAX_NAME_LEN: usize = 32;
+}
+
+/// `/api/readings` history queries
+pub mod readings {
+    /// Span queried without `from` (hours)
+    pub const DEFAULT_HOURS: i64 = 24;
+
+    /// Buckets per page without `limit`
+    pub const DEFAULT_LIMIT: i64 = 500;
+
+    /// Most buckets one page can return
+    pub const MAX_LIMIT: i64 = 2000;
+
+    /// Years `from` and `to` may fall in
+    pub const YEARS: std::ops::RangeInclusive<i32> = 1970..=9999;
+}
+
+/// Pinned status messages edited as readings arrive
+pub mod live {
+    /// Fewest seconds between edits of one message, well under Telegram's limits
+    pub const MIN_EDIT_SECS: i64 = 60;
+
+    /// How often to catch up on throttled readings and check for silence (seconds)
+    pub const CHECK_INTERVAL_SECS: u64 = 30;
+
+    /// How long without readings before the status shows as offline (seconds)
+    pub const OFFLINE_SECS: i64 = super::power::OUTAGE_THRESHOLD_SECS;
+}
+
+/// Telegram Mini App dashboard
+pub mod mini_app {
+    /// Opened by the bot's menu button
+    pub const URL: &str = "https://andrianov.dev/app";
+
+    /// How long a Mini App launch can keep calling the API (seconds)
+    pub const INIT_DATA_MAX_AGE_SECS: i64 = 24 * 3600;
+
+    /// How far `auth_date` may be ahead of the server clock (seconds)
+    pub const INIT_DATA_MAX_SKEW_SECS: i64 = 5 * 60;
 }
@@ -1,7 +1,12 @@
 mod models;
 mod queries;
 
-pub use models::{AlertKind, DailyStats, NotificationSettings, SensorData};
+pub use models::{
+    AlertKind, ApiScope, ApiToken, AuthorizedUser, BatteryDischarge, CommandStatus, DailyStats,
+    DeviceCommand, Locale, MetricSummary, NotificationSettings, OutageCause, PeriodStats,
+    PowerOutage, PowerSource, PowerStats, ReadingBucket, Role, SensorData, SensorGap, SensorRecord,
+    SeriesPoint, TokenGrant,
+};
 
 #[derive(Clone, Debug)]
 pub struct Db {
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use time::PrimitiveDateTime;
 
+use crate::services::{PressureUnit, TemperatureUnit, Units, VolumeUnit};
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, sqlx::Type)]
 #[sqlx(type_name = "TEXT", rename_all = "snake_case")]
 pub enum AlertKind {
@@ -11,6 +13,138 @@ pub enum AlertKind {
     WaterLevelLow,
 }
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum Locale {
+    #[default]
+    En,
+    Uk,
+}
+
+impl Locale {
+    pub const ALL: &'static [Locale] = &[Locale::En, Locale::Uk];
+
+    /// Maps Telegram's `language_code` (IETF tag) to a supported locale
+    pub fn from_language_code(code: Option<&str>) -> Self {
+        match code.and_then(|c| c.split(['-', '_']).next()) {
+            Some("uk") => Locale::Uk,
+            _ => Locale::En,
+        }
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Uk => "uk",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|l| l.code() == code)
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Locale::En => Locale::Uk,
+            Locale::Uk => Locale::En,
+        }
+    }
+}
+
+/// Access level of an authorized user, ordered from least to most privileged
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum Role {
+    /// Reads sensors and gets alerts
+    #[default]
+    Viewer,
+    /// Also runs the pump
+    Operator,
+    /// Also manages users and the bot secret
+    Admin,
+}
+
+impl Role {
+    pub const ALL: &'static [Role] = &[Role::Viewer, Role::Operator, Role::Admin];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Role::Viewer => "viewer",
+            Role::Operator => "operator",
+            Role::Admin => "admin",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|r| r.name() == name)
+    }
+}
+
+/// What a personal access token may do on the HTTP API, capped by its owner's role
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ApiScope {
+    /// Readings, stats and power history
+    Read,
+    /// Queue watering
+    Water,
+    /// Every other scope, but not the device-only routes
+    Admin,
+}
+
+impl ApiScope {
+    pub const ALL: &'static [ApiScope] = &[ApiScope::Read, ApiScope::Water, ApiScope::Admin];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ApiScope::Read => "read",
+            ApiScope::Water => "water",
+            ApiScope::Admin => "admin",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|s| s.name() == name)
+    }
+
+    /// Least role allowed to hold the scope
+    pub fn min_role(self) -> Role {
+        match self {
+            ApiScope::Read => Role::Viewer,
+            ApiScope::Water => Role::Operator,
+            ApiScope::Admin => Role::Admin,
+        }
+    }
+
+    /// Scopes as stored, skipping unknown names
+    pub fn from_names(names: &[String]) -> Vec<Self> {
+        names.iter().filter_map(|n| Self::from_name(n)).collect()
+    }
+}
+
+/// What a gap in readings turned out to be, judged by the device's uptime and boot counter
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum OutageCause {
+    /// The device rebooted, so it lost power
+    Power,
+    /// The device stayed up; only its connection dropped
+    Network,
+    /// The device reported no uptime or boot counter
+    #[default]
+    Unknown,
+}
+
+/// Where a queued device command stands
+#[derive(Clone, Copy, Debug, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum CommandStatus {
+    /// Waiting for the device to poll
+    Queued,
+    /// Picked up by the device
+    Sent,
+    Cancelled,
+}
+
 pub struct AlertState {
     pub active: bool,
     pub last_sent_at: Option<PrimitiveDateTime>,
@@ -23,6 +157,39 @@ pub struct SensorData {
     pub pressure: f32,
     pub soil_moisture: f32,
     pub water_level: f32,
+    /// Seconds since the device booted, if its firmware reports it
+    #[serde(default)]
+    pub uptime_secs: Option<i64>,
+    /// Number of device boots, if its firmware reports it
+    #[serde(default)]
+    pub boot_count: Option<i64>,
+    /// UPS input voltage (V), if the UPS reports it
+    #[serde(default)]
+    pub supply_voltage: Option<f32>,
+    /// UPS battery charge (%), if the UPS reports it
+    #[serde(default)]
+    pub battery_percent: Option<f32>,
+}
+
+pub struct TemperatureRange {
+    pub min: f32,
+    pub max: f32,
+}
+
+/// Averages and totals over a report period; averages are `None` without readings
+pub struct PeriodStats {
+    pub avg_temp: Option<f32>,
+    pub avg_humidity: Option<f32>,
+    pub avg_pressure: Option<f32>,
+    pub avg_soil: Option<f32>,
+    pub avg_water_level: Option<f32>,
+    /// Average hourly fall of soil moisture while drying, scaled to a day (%)
+    pub soil_drying_per_day: Option<f32>,
+    /// Sum of tank level drops (%)
+    pub water_used_percent: Option<f32>,
+    pub waterings: i64,
+    pub outage_minutes: i64,
+    pub alert_incidents: i64,
 }
 
 pub struct DailyStats {
@@ -39,12 +206,175 @@ pub struct PowerOutage {
     pub started_at: PrimitiveDateTime,
     pub ended_at: Option<PrimitiveDateTime>,
     pub duration_minutes: Option<i32>,
+    pub cause: OutageCause,
+    /// Lowest battery level already alerted about during this outage (%)
+    pub battery_alert_percent: Option<f32>,
+}
+
+pub struct ScheduledOutage {
+    pub starts_at: PrimitiveDateTime,
+    pub ends_at: PrimitiveDateTime,
+}
+
+/// A past scheduled window and the actual outage that overlapped it, if any
+pub struct ScheduleComparison {
+    pub starts_at: PrimitiveDateTime,
+    pub ends_at: PrimitiveDateTime,
+    pub actual_started_at: Option<PrimitiveDateTime>,
+    pub actual_ended_at: Option<PrimitiveDateTime>,
+}
+
+/// Power outages only, not connectivity gaps; minutes are clipped to the queried range
+pub struct PowerStats {
+    pub outage_count: i64,
+    pub total_minutes: i64,
+    pub longest_minutes: i64,
 }
 
-pub struct LastSensorTime {
+pub struct OutageHourCount {
+    pub hour: i32,
+    pub count: i64,
+}
+
+/// A gap in readings: the last reading before it and the first one after
+pub struct SensorGap {
+    pub started_at: PrimitiveDateTime,
+    pub ended_at: PrimitiveDateTime,
+    pub uptime_secs: Option<i64>,
+    pub boot_count: Option<i64>,
+    pub previous_boot_count: Option<i64>,
+}
+
+/// First and latest battery readings since an outage started
+pub struct BatteryDischarge {
+    pub first_at: PrimitiveDateTime,
+    pub first_percent: f32,
+    pub last_at: PrimitiveDateTime,
+    pub last_percent: f32,
+}
+
+/// A sensor value averaged over one chart bucket
+pub struct SeriesPoint {
+    pub at: PrimitiveDateTime,
+    pub value: f32,
+}
+
+/// Lowest, average and highest value of one sensor over a bucket
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct MetricSummary {
+    pub min: f32,
+    pub avg: f32,
+    pub max: f32,
+}
+
+/// Readings aggregated over one time bucket
+pub struct ReadingBucket {
+    pub start: PrimitiveDateTime,
+    pub count: i64,
+    pub temperature: MetricSummary,
+    pub humidity: MetricSummary,
+    pub pressure: MetricSummary,
+    pub soil_moisture: MetricSummary,
+    pub water_level: MetricSummary,
+}
+
+/// Which reading is the latest and when it arrived
+pub struct ReadingStamp {
+    pub id: i32,
+    pub created_at: PrimitiveDateTime,
+}
+
+/// A pinned status message the server keeps editing
+pub struct LiveStatusMessage {
+    pub chat_id: i64,
+    pub message_id: i32,
+    /// Whose settings it is rendered with
+    pub telegram_user_id: i64,
+}
+
+/// One stored reading with its time, for exports
+pub struct SensorRecord {
+    pub created_at: PrimitiveDateTime,
+    pub temperature: f32,
+    pub humidity: f32,
+    pub pressure: f32,
+    pub soil_moisture: f32,
+    pub water_level: f32,
+}
+
+/// Something that only reports while the power is on: the sensor board or a heartbeat
+pub struct PowerSource {
+    pub source: String,
+    pub last_seen_at: PrimitiveDateTime,
+    /// When the source came back after its last silence; `None` for the sensor board
+    pub resumed_at: Option<PrimitiveDateTime>,
+}
+
+pub struct AuthorizedUser {
+    pub telegram_user_id: i64,
+    pub username: Option<String>,
+    pub role: Role,
+    /// End of a guest's access
+    pub expires_at: Option<PrimitiveDateTime>,
+}
+
+pub struct Invite {
+    pub token: String,
+    pub expires_at: PrimitiveDateTime,
+}
+
+/// A neighbor who receives power alerts without access to the rest of the bot
+pub struct PowerSubscriber {
+    pub telegram_user_id: i64,
+    pub locale: Locale,
+}
+
+/// A personal access token as listed to its owner; the token itself is never stored
+pub struct ApiToken {
+    pub id: i32,
+    pub name: String,
+    pub scopes: Vec<ApiScope>,
+    pub expires_at: PrimitiveDateTime,
+    pub last_used_at: Option<PrimitiveDateTime>,
+}
+
+/// A freshly created token, shown once
+pub struct NewApiToken {
+    pub token: String,
+    pub expires_at: PrimitiveDateTime,
+}
+
+/// Who a valid token acts for and what it may do
+pub struct TokenGrant {
+    pub telegram_user_id: i64,
+    pub role: Role,
+    pub scopes: Vec<ApiScope>,
+}
+
+impl TokenGrant {
+    /// Whether the token has the scope and its owner's current role still allows it
+    pub fn allows(&self, scope: ApiScope) -> bool {
+        self.role >= scope.min_role()
+            && (self.scopes.contains(&scope) || self.scopes.contains(&ApiScope::Admin))
+    }
+}
+
+/// A pump run in the device queue, with who asked for it
+pub struct DeviceCommand {
+    pub id: i32,
+    pub duration_secs: i32,
+    pub status: CommandStatus,
+    pub queued_by: Option<i64>,
+    pub username: Option<String>,
     pub created_at: PrimitiveDateTime,
 }
 
+/// A group chat linked by an admin, alerted in its own language
+pub struct AuthorizedChat {
+    pub chat_id: i64,
+    pub locale: Locale,
+}
+
 #[derive(Clone, Debug)]
 pub struct NotificationSettings {
     #[allow(dead_code)]
@@ -52,9 +382,30 @@ pub struct NotificationSettings {
     pub soil_moisture_alerts: bool,
     pub temperature_alerts: bool,
     pub power_alerts: bool,
+    pub outlook_alerts: bool,
     pub quiet_hours_enabled: bool,
     pub quiet_hours_start: i16,
     pub quiet_hours_end: i16,
+    pub morning_summary: bool,
+    /// Local hour the morning summary arrives at
+    pub morning_summary_hour: i16,
+    pub weekly_report: bool,
+    /// IANA time zone name
+    pub timezone: String,
+    pub locale: Locale,
+    pub temperature_unit: TemperatureUnit,
+    pub pressure_unit: PressureUnit,
+    pub volume_unit: VolumeUnit,
+}
+
+impl NotificationSettings {
+    pub fn units(&self) -> Units {
+        Units {
+            temperature: self.temperature_unit,
+            pressure: self.pressure_unit,
+            volume: self.volume_unit,
+        }
+    }
 }
 
 impl Default for NotificationSettings {
@@ -64,9 +415,24 @@ impl Default for NotificationSettings {
             soil_moisture_alerts: true,
             temperature_alerts: true,
             power_alerts: true,
+            outlook_alerts: false,
             quiet_hours_enabled: false,
             quiet_hours_start: 23,
             quiet_hours_end: 7,
+            morning_summary: false,
+            morning_summary_hour: 8,
+            weekly_report: true,
+            timezone: "Europe/Kyiv".to_string(),
+            locale: Locale::En,
+            temperature_unit: TemperatureUnit::Celsius,
+            pressure_unit: PressureUnit::Hpa,
+            volume_unit: VolumeUnit::Liters,
         }
     }
 }
+
+pub struct MessageTemplate {
+    pub name: String,
+    pub locale: String,
+    pub body: String,
+}
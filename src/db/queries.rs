@@ -1,22 +1,36 @@
+use time::PrimitiveDateTime;
+
 use super::models::{
-    AlertKind, AlertState, DailyStats, LastSensorTime, NotificationSettings, PowerOutage,
-    SensorData,
+    AlertKind, AlertState, ApiScope, ApiToken, AuthorizedChat, AuthorizedUser, BatteryDischarge,
+    CommandStatus, DailyStats, DeviceCommand, Invite, LiveStatusMessage, Locale, MessageTemplate,
+    MetricSummary, NewApiToken, NotificationSettings, OutageCause, OutageHourCount, PeriodStats,
+    PowerOutage, PowerSource, PowerStats, PowerSubscriber, ReadingBucket, ReadingStamp, Role,
+    ScheduleComparison, ScheduledOutage, SensorData, SensorGap, SensorRecord, SeriesPoint,
+    TemperatureRange, TokenGrant,
 };
 use super::Db;
+use crate::services::{
+    FinishedOutage, OutageWindow, PressureUnit, TemperatureUnit, Units, VolumeUnit,
+};
 
 impl Db {
     pub async fn write_sensor_data(&self, data: SensorData) -> sqlx::Result<()> {
         sqlx::query!(
             r#"
             INSERT INTO sensor_data
-            (temperature, humidity, pressure, soil_moisture, water_level)
-            VALUES ($1, $2, $3, $4, $5)
+            (temperature, humidity, pressure, soil_moisture, water_level, uptime_secs, boot_count,
+             supply_voltage, battery_percent)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             "#,
             data.temperature as f64,
             data.humidity as f64,
             data.pressure as f64,
             data.soil_moisture as f64,
-            data.water_level as f64
+            data.water_level as f64,
+            data.uptime_secs,
+            data.boot_count,
+            data.supply_voltage.map(f64::from),
+            data.battery_percent.map(f64::from)
         )
         .execute(&self.pool)
         .await?;
@@ -31,7 +45,10 @@ impl Db {
                    humidity::real as "humidity!: f32",
                    pressure::real as "pressure!: f32",
                    soil_moisture::real as "soil_moisture!: f32",
-                   water_level::real as "water_level!: f32"
+                   water_level::real as "water_level!: f32",
+                   uptime_secs, boot_count,
+                   supply_voltage::real as "supply_voltage: f32",
+                   battery_percent::real as "battery_percent: f32"
             FROM sensor_data WHERE id = $1
             "#,
             id
@@ -48,7 +65,10 @@ impl Db {
                    humidity::real as "humidity!: f32",
                    pressure::real as "pressure!: f32",
                    soil_moisture::real as "soil_moisture!: f32",
-                   water_level::real as "water_level!: f32"
+                   water_level::real as "water_level!: f32",
+                   uptime_secs, boot_count,
+                   supply_voltage::real as "supply_voltage: f32",
+                   battery_percent::real as "battery_percent: f32"
             FROM sensor_data
             ORDER BY created_at DESC
             LIMIT 1
@@ -73,6 +93,20 @@ impl Db {
         .await
     }
 
+    pub async fn get_latest_reading_stamp(&self) -> sqlx::Result<Option<ReadingStamp>> {
+        sqlx::query_as!(
+            ReadingStamp,
+            r#"
+            SELECT id, created_at
+            FROM sensor_data
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
     pub async fn get_daily_stats(&self) -> sqlx::Result<Option<DailyStats>> {
         sqlx::query_as!(
             DailyStats,
@@ -91,9 +125,119 @@ impl Db {
         .await
     }
 
+    pub async fn get_stats_since(
+        &self,
+        since: PrimitiveDateTime,
+    ) -> sqlx::Result<Option<DailyStats>> {
+        sqlx::query_as!(
+            DailyStats,
+            r#"
+            SELECT
+                MIN(temperature)::real as "min_temp!: f32",
+                MAX(temperature)::real as "max_temp!: f32",
+                AVG(temperature)::real as "avg_temp!: f32",
+                MIN(humidity)::real as "min_humidity!: f32",
+                MAX(humidity)::real as "max_humidity!: f32"
+            FROM sensor_data
+            WHERE created_at >= $1
+            HAVING COUNT(*) > 0
+            "#,
+            since
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn get_temperature_range_since(
+        &self,
+        since: PrimitiveDateTime,
+    ) -> sqlx::Result<Option<TemperatureRange>> {
+        sqlx::query_as!(
+            TemperatureRange,
+            r#"
+            SELECT MIN(temperature)::real as "min!: f32", MAX(temperature)::real as "max!: f32"
+            FROM sensor_data
+            WHERE created_at >= $1
+            HAVING COUNT(*) > 0
+            "#,
+            since
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn get_period_stats(
+        &self,
+        from: PrimitiveDateTime,
+        to: PrimitiveDateTime,
+    ) -> sqlx::Result<PeriodStats> {
+        sqlx::query_as!(
+            PeriodStats,
+            r#"
+            WITH readings AS (
+                SELECT * FROM sensor_data WHERE created_at >= $1 AND created_at < $2
+            ),
+            hourly AS (
+                SELECT date_bin('1 hour', created_at, $1) AS hour,
+                       AVG(soil_moisture) AS soil,
+                       AVG(water_level) AS water
+                FROM readings
+                GROUP BY 1
+            ),
+            changes AS (
+                SELECT soil - LAG(soil) OVER (ORDER BY hour) AS soil_change,
+                       water - LAG(water) OVER (ORDER BY hour) AS water_change,
+                       hour - LAG(hour) OVER (ORDER BY hour) AS gap
+                FROM hourly
+            )
+            SELECT
+                (SELECT AVG(temperature) FROM readings)::real as "avg_temp: f32",
+                (SELECT AVG(humidity) FROM readings)::real as "avg_humidity: f32",
+                (SELECT AVG(pressure) FROM readings)::real as "avg_pressure: f32",
+                (SELECT AVG(soil_moisture) FROM readings)::real as "avg_soil: f32",
+                (SELECT AVG(water_level) FROM readings)::real as "avg_water_level: f32",
+                (SELECT AVG(-soil_change) * 24 FROM changes
+                 WHERE gap = interval '1 hour' AND soil_change < 0)::real
+                    as "soil_drying_per_day: f32",
+                (SELECT SUM(-water_change) FROM changes
+                 WHERE gap = interval '1 hour' AND water_change < 0)::real
+                    as "water_used_percent: f32",
+                (SELECT COUNT(*) FROM watering_events
+                 WHERE created_at >= $1 AND created_at < $2) as "waterings!",
+                (SELECT COALESCE(SUM(EXTRACT(EPOCH FROM
+                     LEAST(COALESCE(ended_at, NOW()::timestamp), $2) - GREATEST(started_at, $1)
+                 )), 0)::bigint / 60
+                 FROM power_outages
+                 WHERE started_at < $2 AND COALESCE(ended_at, NOW()::timestamp) > $1
+                   AND cause <> 'network') as "outage_minutes!",
+                (SELECT COUNT(*) FROM alert_incidents
+                 WHERE created_at >= $1 AND created_at < $2) as "alert_incidents!"
+            "#,
+            from,
+            to
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn record_alert_incident(&self, kind: AlertKind) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"INSERT INTO alert_incidents (alert_kind) VALUES ($1)"#,
+            kind as AlertKind
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn is_user_authorized(&self, telegram_user_id: i64) -> sqlx::Result<bool> {
         let result = sqlx::query_scalar!(
-            r#"SELECT EXISTS(SELECT 1 FROM authorized_users WHERE telegram_user_id = $1) as "exists!""#,
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM authorized_users
+                WHERE telegram_user_id = $1 AND (expires_at IS NULL OR expires_at > NOW())
+            ) as "exists!"
+            "#,
             telegram_user_id
         )
         .fetch_one(&self.pool)
@@ -101,6 +245,8 @@ impl Db {
         Ok(result)
     }
 
+    /// Until there is an admin, the first user to authorize becomes one.
+    /// A guest who enters the secret keeps their role but loses the time limit
     pub async fn authorize_user(
         &self,
         telegram_user_id: i64,
@@ -108,9 +254,11 @@ impl Db {
     ) -> sqlx::Result<()> {
         sqlx::query!(
             r#"
-            INSERT INTO authorized_users (telegram_user_id, username)
-            VALUES ($1, $2)
-            ON CONFLICT (telegram_user_id) DO NOTHING
+            INSERT INTO authorized_users (telegram_user_id, username, role)
+            SELECT $1, $2,
+                   CASE WHEN EXISTS(SELECT 1 FROM authorized_users WHERE role = 'admin')
+                        THEN 'viewer' ELSE 'admin' END
+            ON CONFLICT (telegram_user_id) DO UPDATE SET expires_at = NULL
             "#,
             telegram_user_id,
             username
@@ -120,11 +268,162 @@ impl Db {
         Ok(())
     }
 
-    #[allow(dead_code)]
-    pub async fn get_authorized_user_ids(&self) -> sqlx::Result<Vec<i64>> {
-        let rows = sqlx::query_scalar!(r#"SELECT telegram_user_id FROM authorized_users"#)
-            .fetch_all(&self.pool)
+    pub async fn is_power_subscriber(&self, telegram_user_id: i64) -> sqlx::Result<bool> {
+        let result = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM power_subscribers WHERE telegram_user_id = $1) as "exists!""#,
+            telegram_user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(result)
+    }
+
+    pub async fn add_power_subscriber(
+        &self,
+        telegram_user_id: i64,
+        username: Option<&str>,
+        locale: Locale,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO power_subscribers (telegram_user_id, username, locale)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (telegram_user_id) DO UPDATE SET username = $2, locale = $3
+            "#,
+            telegram_user_id,
+            username,
+            locale as Locale
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn remove_power_subscriber(&self, telegram_user_id: i64) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"DELETE FROM power_subscribers WHERE telegram_user_id = $1"#,
+            telegram_user_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Subscribers who are not also authorized users, so nobody gets an alert twice
+    pub async fn get_power_subscribers(&self) -> sqlx::Result<Vec<PowerSubscriber>> {
+        sqlx::query_as!(
+            PowerSubscriber,
+            r#"
+            SELECT ps.telegram_user_id, ps.locale as "locale: Locale"
+            FROM power_subscribers ps
+            WHERE NOT EXISTS (
+                SELECT 1 FROM authorized_users au
+                WHERE au.telegram_user_id = ps.telegram_user_id
+            )
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Links a group chat, or updates its title and language when linked again
+    pub async fn authorize_chat(
+        &self,
+        chat_id: i64,
+        title: Option<&str>,
+        locale: Locale,
+        authorized_by: i64,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO authorized_chats (chat_id, title, locale, authorized_by)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (chat_id) DO UPDATE SET title = $2, locale = $3
+            "#,
+            chat_id,
+            title,
+            locale as Locale,
+            authorized_by
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Whether the chat was linked. Its live status stops being updated
+    pub async fn unauthorize_chat(&self, chat_id: i64) -> sqlx::Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM live_status_messages WHERE chat_id = $1",
+            chat_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        let result = sqlx::query!("DELETE FROM authorized_chats WHERE chat_id = $1", chat_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn is_chat_authorized(&self, chat_id: i64) -> sqlx::Result<bool> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM authorized_chats WHERE chat_id = $1) as "exists!""#,
+            chat_id
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn get_authorized_chats(&self) -> sqlx::Result<Vec<AuthorizedChat>> {
+        sqlx::query_as!(
+            AuthorizedChat,
+            r#"SELECT chat_id, locale as "locale: Locale" FROM authorized_chats"#
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Serialized dialogue state of a chat
+    pub async fn get_dialogue(&self, chat_id: i64) -> sqlx::Result<Option<String>> {
+        sqlx::query_scalar!(
+            r#"SELECT state FROM bot_dialogues WHERE chat_id = $1"#,
+            chat_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn set_dialogue(&self, chat_id: i64, state: &str) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO bot_dialogues (chat_id, state) VALUES ($1, $2)
+            ON CONFLICT (chat_id) DO UPDATE SET state = excluded.state, updated_at = NOW()
+            "#,
+            chat_id,
+            state
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn remove_dialogue(&self, chat_id: i64) -> sqlx::Result<()> {
+        sqlx::query!(r#"DELETE FROM bot_dialogues WHERE chat_id = $1"#, chat_id)
+            .execute(&self.pool)
             .await?;
+        Ok(())
+    }
+
+    pub async fn get_authorized_user_ids(&self) -> sqlx::Result<Vec<i64>> {
+        let rows = sqlx::query_scalar!(
+            r#"
+            SELECT telegram_user_id FROM authorized_users
+            WHERE expires_at IS NULL OR expires_at > NOW()
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
         Ok(rows)
     }
 
@@ -172,109 +471,943 @@ impl Db {
         Ok(())
     }
 
-    pub async fn get_last_sensor_time(&self) -> sqlx::Result<Option<LastSensorTime>> {
-        sqlx::query_as!(
-            LastSensorTime,
-            r#"SELECT created_at FROM sensor_data ORDER BY created_at DESC LIMIT 1"#
+    /// `None` when the user is not authorized
+    pub async fn get_user_role(&self, telegram_user_id: i64) -> sqlx::Result<Option<Role>> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT role as "role: Role"
+            FROM authorized_users
+            WHERE telegram_user_id = $1 AND (expires_at IS NULL OR expires_at > NOW())
+            "#,
+            telegram_user_id
         )
         .fetch_optional(&self.pool)
         .await
     }
 
-    pub async fn get_active_outage(&self) -> sqlx::Result<Option<PowerOutage>> {
+    pub async fn get_authorized_users(&self) -> sqlx::Result<Vec<AuthorizedUser>> {
         sqlx::query_as!(
-            PowerOutage,
+            AuthorizedUser,
             r#"
-            SELECT id, started_at, ended_at, duration_minutes
-            FROM power_outages
-            WHERE ended_at IS NULL
-            ORDER BY started_at DESC
-            LIMIT 1
+            SELECT telegram_user_id, username, role as "role: Role", expires_at
+            FROM authorized_users
+            ORDER BY created_at
             "#
         )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Finds an authorized user by numeric id or `@username`
+    pub async fn find_authorized_user(&self, user: &str) -> sqlx::Result<Option<AuthorizedUser>> {
+        let id = user.parse::<i64>().ok();
+        let username = user.strip_prefix('@').unwrap_or(user);
+        sqlx::query_as!(
+            AuthorizedUser,
+            r#"
+            SELECT telegram_user_id, username, role as "role: Role", expires_at
+            FROM authorized_users
+            WHERE telegram_user_id = $1 OR lower(username) = lower($2)
+            LIMIT 1
+            "#,
+            id,
+            username
+        )
         .fetch_optional(&self.pool)
         .await
     }
 
-    pub async fn start_outage(&self) -> sqlx::Result<()> {
+    pub async fn set_user_role(&self, telegram_user_id: i64, role: Role) -> sqlx::Result<()> {
         sqlx::query!(
-            r#"INSERT INTO power_outages (started_at) VALUES (NOW())"#
+            r#"UPDATE authorized_users SET role = $2 WHERE telegram_user_id = $1"#,
+            telegram_user_id,
+            role as Role
         )
         .execute(&self.pool)
         .await?;
         Ok(())
     }
 
-    pub async fn end_outage(&self) -> sqlx::Result<Option<i32>> {
-        let result = sqlx::query_scalar!(
+    /// Removes the user with their settings and dialogue, so they start over unauthorized
+    pub async fn revoke_user(&self, telegram_user_id: i64) -> sqlx::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!(
+            r#"DELETE FROM notification_settings WHERE telegram_user_id = $1"#,
+            telegram_user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"DELETE FROM authorized_users WHERE telegram_user_id = $1"#,
+            telegram_user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"DELETE FROM api_tokens WHERE telegram_user_id = $1"#,
+            telegram_user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"DELETE FROM live_status_messages WHERE telegram_user_id = $1"#,
+            telegram_user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"DELETE FROM bot_dialogues WHERE chat_id = $1"#,
+            telegram_user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+
+    /// The rotated bot secret, if it replaced the one from the environment
+    pub async fn get_bot_secret(&self) -> sqlx::Result<Option<String>> {
+        sqlx::query_scalar!(r#"SELECT value FROM bot_settings WHERE key = 'bot_secret'"#)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    /// Replaces the bot secret with a random one and returns it
+    pub async fn rotate_bot_secret(&self) -> sqlx::Result<String> {
+        sqlx::query_scalar!(
             r#"
-            UPDATE power_outages
-            SET ended_at = NOW(),
-                duration_minutes = EXTRACT(EPOCH FROM (NOW() - started_at))::integer / 60
-            WHERE ended_at IS NULL
-            RETURNING duration_minutes
+            INSERT INTO bot_settings (key, value)
+            VALUES ('bot_secret', replace(gen_random_uuid()::text, '-', ''))
+            ON CONFLICT (key) DO UPDATE SET value = excluded.value, updated_at = NOW()
+            RETURNING value
             "#
         )
-        .fetch_optional(&self.pool)
-        .await?;
-        Ok(result.flatten())
+        .fetch_one(&self.pool)
+        .await
     }
 
-    pub async fn get_recent_outages(&self, limit: i64) -> sqlx::Result<Vec<PowerOutage>> {
+    /// Creates an invite link token valid for `valid_hours`. With `access_hours`
+    /// the invited user is a guest whose access ends that long after joining
+    pub async fn create_invite(
+        &self,
+        role: Role,
+        created_by: i64,
+        max_uses: i32,
+        valid_hours: i32,
+        access_hours: Option<i32>,
+    ) -> sqlx::Result<Invite> {
         sqlx::query_as!(
-            PowerOutage,
+            Invite,
             r#"
-            SELECT id, started_at, ended_at, duration_minutes
-            FROM power_outages
-            ORDER BY started_at DESC
-            LIMIT $1
+            INSERT INTO invites (token, role, max_uses, access_hours, expires_at, created_by)
+            VALUES (replace(gen_random_uuid()::text, '-', ''), $1, $2, $3,
+                    NOW() + make_interval(hours => $4), $5)
+            RETURNING token, expires_at
             "#,
-            limit
+            role as Role,
+            max_uses,
+            access_hours,
+            valid_hours,
+            created_by
         )
-        .fetch_all(&self.pool)
+        .fetch_one(&self.pool)
         .await
     }
 
-    pub async fn get_notification_settings(
+    /// Creates a random token valid for `valid_days`, storing only its hash.
+    /// `None` when the user already has a token with that name
+    pub async fn create_api_token(
         &self,
-        user_id: i64,
-    ) -> sqlx::Result<NotificationSettings> {
-        let settings = sqlx::query_as!(
-            NotificationSettings,
+        telegram_user_id: i64,
+        name: &str,
+        scopes: &[ApiScope],
+        valid_days: i32,
+    ) -> sqlx::Result<Option<NewApiToken>> {
+        let scopes: Vec<String> = scopes.iter().map(|s| s.name().to_string()).collect();
+        sqlx::query_as!(
+            NewApiToken,
             r#"
-            SELECT telegram_user_id, soil_moisture_alerts, temperature_alerts,
-                   power_alerts, quiet_hours_enabled, quiet_hours_start, quiet_hours_end
-            FROM notification_settings
-            WHERE telegram_user_id = $1
+            WITH new AS (
+                SELECT 'gdn_' || replace(gen_random_uuid()::text, '-', '')
+                              || replace(gen_random_uuid()::text, '-', '') AS token
+            ), inserted AS (
+                INSERT INTO api_tokens (telegram_user_id, name, token_hash, scopes, expires_at)
+                SELECT $1, $2, encode(sha256(convert_to(new.token, 'UTF8')), 'hex'), $3,
+                       NOW() + make_interval(days => $4)
+                FROM new
+                ON CONFLICT (telegram_user_id, name) DO NOTHING
+                RETURNING expires_at
+            )
+            SELECT new.token as "token!", inserted.expires_at
+            FROM new, inserted
             "#,
-            user_id
+            telegram_user_id,
+            name,
+            &scopes,
+            valid_days
         )
         .fetch_optional(&self.pool)
-        .await?;
-
-        Ok(settings.unwrap_or_else(|| NotificationSettings {
-            telegram_user_id: user_id,
-            ..Default::default()
-        }))
+        .await
     }
 
-    pub async fn ensure_notification_settings(&self, user_id: i64) -> sqlx::Result<()> {
-        sqlx::query!(
+    pub async fn get_api_tokens(&self, telegram_user_id: i64) -> sqlx::Result<Vec<ApiToken>> {
+        let rows = sqlx::query!(
             r#"
-            INSERT INTO notification_settings (telegram_user_id)
-            VALUES ($1)
-            ON CONFLICT (telegram_user_id) DO NOTHING
+            SELECT id, name, scopes, expires_at, last_used_at
+            FROM api_tokens
+            WHERE telegram_user_id = $1
+            ORDER BY created_at
             "#,
-            user_id
+            telegram_user_id
         )
-        .execute(&self.pool)
+        .fetch_all(&self.pool)
         .await?;
-        Ok(())
+
+        Ok(rows
+            .into_iter()
+            .map(|r| ApiToken {
+                id: r.id,
+                name: r.name,
+                scopes: ApiScope::from_names(&r.scopes),
+                expires_at: r.expires_at,
+                last_used_at: r.last_used_at,
+            })
+            .collect())
     }
 
-    pub async fn toggle_soil_alerts(&self, user_id: i64) -> sqlx::Result<bool> {
-        let result = sqlx::query_scalar!(
-            r#"
+    /// Deletes one of the user's tokens; false if it was already gone
+    pub async fn revoke_api_token(&self, telegram_user_id: i64, id: i32) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM api_tokens WHERE id = $1 AND telegram_user_id = $2",
+            id,
+            telegram_user_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The grant of an unexpired token whose owner still has access, marking it used
+    pub async fn authenticate_api_token(&self, token: &str) -> sqlx::Result<Option<TokenGrant>> {
+        let row = sqlx::query!(
+            r#"
+            UPDATE api_tokens t SET last_used_at = NOW()
+            FROM authorized_users au
+            WHERE t.token_hash = encode(sha256(convert_to($1, 'UTF8')), 'hex')
+              AND t.expires_at > NOW()
+              AND au.telegram_user_id = t.telegram_user_id
+              AND (au.expires_at IS NULL OR au.expires_at > NOW())
+            RETURNING t.telegram_user_id, t.scopes, au.role as "role: Role"
+            "#,
+            token
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|r| TokenGrant {
+            telegram_user_id: r.telegram_user_id,
+            role: r.role,
+            scopes: ApiScope::from_names(&r.scopes),
+        }))
+    }
+
+    /// Uses up one redemption of the invite and authorizes the user with its role.
+    /// `None` when the invite is unknown, expired or used up
+    pub async fn redeem_invite(
+        &self,
+        token: &str,
+        telegram_user_id: i64,
+        username: Option<&str>,
+    ) -> sqlx::Result<Option<Role>> {
+        let mut tx = self.pool.begin().await?;
+        let Some(invite) = sqlx::query!(
+            r#"
+            UPDATE invites SET uses = uses + 1
+            WHERE token = $1 AND uses < max_uses AND expires_at > NOW()
+            RETURNING role as "role: Role", access_hours
+            "#,
+            token
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(None);
+        };
+        sqlx::query!(
+            r#"
+            INSERT INTO authorized_users (telegram_user_id, username, role, expires_at)
+            VALUES ($1, $2, $3, NOW() + make_interval(hours => $4))
+            ON CONFLICT (telegram_user_id) DO UPDATE
+            SET username = excluded.username, role = excluded.role, expires_at = excluded.expires_at
+            "#,
+            telegram_user_id,
+            username,
+            invite.role as Role,
+            invite.access_hours
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(Some(invite.role))
+    }
+
+    /// End of the user's lockout after too many wrong attempts, if still locked
+    pub async fn get_auth_lockout(
+        &self,
+        telegram_user_id: i64,
+    ) -> sqlx::Result<Option<PrimitiveDateTime>> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT locked_until as "locked_until!"
+            FROM auth_attempts
+            WHERE telegram_user_id = $1 AND locked_until > NOW()
+            "#,
+            telegram_user_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Counts a wrong attempt and returns the failures so far. The count starts over
+    /// after a lockout or when the last attempt is older than `window_mins`
+    pub async fn record_failed_auth(
+        &self,
+        telegram_user_id: i64,
+        window_mins: i32,
+    ) -> sqlx::Result<i32> {
+        sqlx::query_scalar!(
+            r#"
+            INSERT INTO auth_attempts (telegram_user_id, failures)
+            VALUES ($1, 1)
+            ON CONFLICT (telegram_user_id) DO UPDATE
+            SET failures = CASE
+                    WHEN auth_attempts.locked_until IS NOT NULL
+                      OR auth_attempts.last_attempt_at < NOW() - make_interval(mins => $2)
+                    THEN 1
+                    ELSE auth_attempts.failures + 1
+                END,
+                locked_until = NULL,
+                last_attempt_at = NOW()
+            RETURNING failures
+            "#,
+            telegram_user_id,
+            window_mins
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn lock_auth(&self, telegram_user_id: i64, mins: i32) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE auth_attempts SET locked_until = NOW() + make_interval(mins => $2)
+            WHERE telegram_user_id = $1
+            "#,
+            telegram_user_id,
+            mins
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn clear_failed_auth(&self, telegram_user_id: i64) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"DELETE FROM auth_attempts WHERE telegram_user_id = $1"#,
+            telegram_user_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_user_ids_with_role(&self, role: Role) -> sqlx::Result<Vec<i64>> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT telegram_user_id FROM authorized_users
+            WHERE role = $1 AND (expires_at IS NULL OR expires_at > NOW())
+            "#,
+            role as Role
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Removes guests whose access ended, like `revoke_user` does
+    pub async fn remove_expired_users(&self) -> sqlx::Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!(
+            r#"
+            DELETE FROM notification_settings WHERE telegram_user_id IN (
+                SELECT telegram_user_id FROM authorized_users WHERE expires_at <= NOW()
+            )
+            "#
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"
+            DELETE FROM bot_dialogues WHERE chat_id IN (
+                SELECT telegram_user_id FROM authorized_users WHERE expires_at <= NOW()
+            )
+            "#
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"
+            DELETE FROM api_tokens WHERE telegram_user_id IN (
+                SELECT telegram_user_id FROM authorized_users WHERE expires_at <= NOW()
+            )
+            "#
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"
+            DELETE FROM live_status_messages WHERE telegram_user_id IN (
+                SELECT telegram_user_id FROM authorized_users WHERE expires_at <= NOW()
+            )
+            "#
+        )
+        .execute(&mut *tx)
+        .await?;
+        let removed = sqlx::query!(r#"DELETE FROM authorized_users WHERE expires_at <= NOW()"#)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        tx.commit().await?;
+        Ok(removed)
+    }
+
+    /// Records a ping, starting a new streak when the source was silent for `silence_secs`
+    pub async fn record_heartbeat(&self, source: &str, silence_secs: i64) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO heartbeats (source) VALUES ($1)
+            ON CONFLICT (source) DO UPDATE
+            SET last_seen_at = NOW(),
+                resumed_at = CASE
+                    WHEN heartbeats.last_seen_at < NOW() - make_interval(secs => $2)
+                    THEN NOW()
+                    ELSE heartbeats.resumed_at
+                END
+            "#,
+            source,
+            silence_secs as f64
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The sensor board plus heartbeat sources heard from within `expiry_hours`
+    pub async fn get_power_sources(
+        &self,
+        sensor_source: &str,
+        expiry_hours: i32,
+    ) -> sqlx::Result<Vec<PowerSource>> {
+        sqlx::query_as!(
+            PowerSource,
+            r#"
+            SELECT $1::text as "source!",
+                   MAX(created_at) as "last_seen_at!",
+                   NULL::timestamp as resumed_at
+            FROM sensor_data
+            HAVING COUNT(*) > 0
+            UNION ALL
+            SELECT source, last_seen_at, resumed_at
+            FROM heartbeats
+            WHERE last_seen_at > NOW() - make_interval(hours => $2)
+              AND source <> $1
+            ORDER BY 1
+            "#,
+            sensor_source,
+            expiry_hours
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn get_first_sensor_time(&self) -> sqlx::Result<Option<PrimitiveDateTime>> {
+        sqlx::query_scalar!(r#"SELECT MIN(created_at) FROM sensor_data"#)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    pub async fn get_active_outage(&self) -> sqlx::Result<Option<PowerOutage>> {
+        sqlx::query_as!(
+            PowerOutage,
+            r#"
+            SELECT id, started_at, ended_at, duration_minutes,
+                   cause as "cause: OutageCause",
+                   battery_alert_percent::real as "battery_alert_percent: f32"
+            FROM power_outages
+            WHERE ended_at IS NULL
+            ORDER BY started_at DESC
+            LIMIT 1
+            "#
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Opens an outage at the last reading before it, not when it was noticed
+    /// `on_battery` marks an outage seen on the UPS battery rather than as a gap in readings
+    pub async fn start_outage(
+        &self,
+        started_at: PrimitiveDateTime,
+        cause: OutageCause,
+        on_battery: bool,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"INSERT INTO power_outages (started_at, cause, on_battery) VALUES ($1, $2, $3)"#,
+            started_at,
+            cause as OutageCause,
+            on_battery
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_sensor_time(&self, id: i32) -> sqlx::Result<Option<PrimitiveDateTime>> {
+        sqlx::query_scalar!(r#"SELECT created_at FROM sensor_data WHERE id = $1"#, id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    pub async fn get_battery_discharge(
+        &self,
+        since: PrimitiveDateTime,
+    ) -> sqlx::Result<Option<BatteryDischarge>> {
+        sqlx::query_as!(
+            BatteryDischarge,
+            r#"
+            SELECT f.created_at as first_at,
+                   f.battery_percent::real as "first_percent!: f32",
+                   l.created_at as last_at,
+                   l.battery_percent::real as "last_percent!: f32"
+            FROM (
+                SELECT created_at, battery_percent FROM sensor_data
+                WHERE created_at >= $1 AND battery_percent IS NOT NULL
+                ORDER BY created_at
+                LIMIT 1
+            ) f, (
+                SELECT created_at, battery_percent FROM sensor_data
+                WHERE created_at >= $1 AND battery_percent IS NOT NULL
+                ORDER BY created_at DESC
+                LIMIT 1
+            ) l
+            "#,
+            since
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn set_battery_alert(&self, outage_id: i32, percent: f32) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"UPDATE power_outages SET battery_alert_percent = $2 WHERE id = $1"#,
+            outage_id,
+            percent as f64
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The first reading after `started_at`, with the boot counter from just before it
+    pub async fn get_gap_after(
+        &self,
+        started_at: PrimitiveDateTime,
+    ) -> sqlx::Result<Option<SensorGap>> {
+        sqlx::query_as!(
+            SensorGap,
+            r#"
+            SELECT $1::timestamp as "started_at!",
+                   after.created_at as ended_at,
+                   after.uptime_secs,
+                   after.boot_count,
+                   before.boot_count as previous_boot_count
+            FROM (
+                SELECT created_at, uptime_secs, boot_count
+                FROM sensor_data
+                WHERE created_at > $1
+                ORDER BY created_at
+                LIMIT 1
+            ) after
+            LEFT JOIN LATERAL (
+                SELECT boot_count
+                FROM sensor_data
+                WHERE created_at <= $1
+                ORDER BY created_at DESC
+                LIMIT 1
+            ) before ON true
+            "#,
+            started_at
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Gaps between consecutive readings longer than `threshold_secs`
+    pub async fn get_sensor_gaps(&self, threshold_secs: i64) -> sqlx::Result<Vec<SensorGap>> {
+        sqlx::query_as!(
+            SensorGap,
+            r#"
+            SELECT previous_at as "started_at!", created_at as ended_at,
+                   uptime_secs, boot_count, previous_boot_count
+            FROM (
+                SELECT created_at, uptime_secs, boot_count,
+                       LAG(created_at) OVER (ORDER BY created_at) as previous_at,
+                       LAG(boot_count) OVER (ORDER BY created_at) as previous_boot_count
+                FROM sensor_data
+            ) readings
+            WHERE created_at - previous_at > make_interval(secs => $1)
+            ORDER BY created_at
+            "#,
+            threshold_secs as f64
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn end_outage(
+        &self,
+        ended_at: PrimitiveDateTime,
+        cause: OutageCause,
+    ) -> sqlx::Result<Option<PowerOutage>> {
+        sqlx::query_as!(
+            PowerOutage,
+            r#"
+            UPDATE power_outages
+            SET ended_at = $1,
+                duration_minutes = EXTRACT(EPOCH FROM ($1 - started_at))::integer / 60,
+                cause = $2
+            WHERE ended_at IS NULL
+            RETURNING id, started_at, ended_at, duration_minutes, cause as "cause: OutageCause",
+                      battery_alert_percent::real as "battery_alert_percent: f32"
+            "#,
+            ended_at,
+            cause as OutageCause
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Replaces the finished outages derived from gaps, returning how many were recorded.
+    /// Outages seen on battery have no gap, so they are kept and gaps overlapping them skipped
+    pub async fn replace_finished_outages(&self, outages: &[FinishedOutage]) -> sqlx::Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!(r#"DELETE FROM power_outages WHERE ended_at IS NOT NULL AND NOT on_battery"#)
+            .execute(&mut *tx)
+            .await?;
+        let mut recorded = 0;
+        for outage in outages {
+            recorded += sqlx::query!(
+                r#"
+                INSERT INTO power_outages (started_at, ended_at, duration_minutes, cause)
+                SELECT $1, $2, EXTRACT(EPOCH FROM ($2::timestamp - $1::timestamp))::integer / 60, $3
+                WHERE NOT EXISTS (
+                    SELECT 1 FROM power_outages
+                    WHERE on_battery AND started_at < $2
+                      AND COALESCE(ended_at, NOW()::timestamp) > $1
+                )
+                "#,
+                outage.started_at,
+                outage.ended_at,
+                outage.cause as OutageCause
+            )
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+        tx.commit().await?;
+        Ok(recorded)
+    }
+
+    pub async fn get_recent_outages(&self, limit: i64) -> sqlx::Result<Vec<PowerOutage>> {
+        sqlx::query_as!(
+            PowerOutage,
+            r#"
+            SELECT id, started_at, ended_at, duration_minutes,
+                   cause as "cause: OutageCause",
+                   battery_alert_percent::real as "battery_alert_percent: f32"
+            FROM power_outages
+            ORDER BY started_at DESC
+            LIMIT $1
+            "#,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn get_power_stats(
+        &self,
+        from: PrimitiveDateTime,
+        to: PrimitiveDateTime,
+    ) -> sqlx::Result<PowerStats> {
+        sqlx::query_as!(
+            PowerStats,
+            r#"
+            SELECT COUNT(*) as "outage_count!",
+                   COALESCE(SUM(minutes), 0)::bigint as "total_minutes!",
+                   COALESCE(MAX(minutes), 0)::bigint as "longest_minutes!"
+            FROM (
+                SELECT EXTRACT(EPOCH FROM (
+                    LEAST(COALESCE(ended_at, NOW()::timestamp), $2) - GREATEST(started_at, $1)
+                ))::bigint / 60 as minutes
+                FROM power_outages
+                WHERE started_at < $2 AND COALESCE(ended_at, NOW()::timestamp) > $1
+                  AND cause <> 'network'
+            ) o
+            "#,
+            from,
+            to
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn get_outage_start_hours(
+        &self,
+        from: PrimitiveDateTime,
+        to: PrimitiveDateTime,
+    ) -> sqlx::Result<Vec<OutageHourCount>> {
+        sqlx::query_as!(
+            OutageHourCount,
+            r#"
+            SELECT EXTRACT(HOUR FROM started_at AT TIME ZONE 'UTC' AT TIME ZONE 'Europe/Kyiv')::integer
+                       as "hour!",
+                   COUNT(*) as "count!"
+            FROM power_outages
+            WHERE started_at >= $1 AND started_at < $2 AND cause <> 'network'
+            GROUP BY 1
+            ORDER BY 1
+            "#,
+            from,
+            to
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Replaces the upcoming windows imported from `source`; past ones are kept for reports
+    pub async fn replace_scheduled_outages(
+        &self,
+        source: &str,
+        windows: &[OutageWindow],
+    ) -> sqlx::Result<()> {
+        let starts: Vec<_> = windows.iter().map(|w| w.starts_at).collect();
+        let ends: Vec<_> = windows.iter().map(|w| w.ends_at).collect();
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!(
+            r#"
+            DELETE FROM scheduled_outages
+            WHERE source = $1
+              AND starts_at > NOW()
+              AND (starts_at, ends_at) NOT IN (
+                  SELECT * FROM UNNEST($2::timestamp[], $3::timestamp[])
+              )
+            "#,
+            source,
+            &starts,
+            &ends
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO scheduled_outages (source, starts_at, ends_at)
+            SELECT $1, * FROM UNNEST($2::timestamp[], $3::timestamp[])
+            ON CONFLICT (source, starts_at, ends_at) DO NOTHING
+            "#,
+            source,
+            &starts,
+            &ends
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+
+    /// Scheduled window covering now, allowing the grid to cut power a little early
+    pub async fn get_current_scheduled_outage(
+        &self,
+        tolerance_mins: i32,
+    ) -> sqlx::Result<Option<ScheduledOutage>> {
+        sqlx::query_as!(
+            ScheduledOutage,
+            r#"
+            SELECT starts_at, ends_at
+            FROM scheduled_outages
+            WHERE starts_at <= NOW() + make_interval(mins => $1)
+              AND ends_at > NOW()
+            ORDER BY starts_at
+            LIMIT 1
+            "#,
+            tolerance_mins
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn get_upcoming_scheduled_outages(
+        &self,
+        limit: i64,
+    ) -> sqlx::Result<Vec<ScheduledOutage>> {
+        sqlx::query_as!(
+            ScheduledOutage,
+            r#"
+            SELECT DISTINCT starts_at, ends_at
+            FROM scheduled_outages
+            WHERE ends_at > NOW()
+            ORDER BY starts_at
+            LIMIT $1
+            "#,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Marks windows starting within `lead_mins` as warned, returning the earliest one
+    /// that had not been warned about yet
+    pub async fn take_outage_warning(
+        &self,
+        lead_mins: i32,
+    ) -> sqlx::Result<Option<ScheduledOutage>> {
+        let warned = sqlx::query_as!(
+            ScheduledOutage,
+            r#"
+            UPDATE scheduled_outages
+            SET warned_at = NOW()
+            WHERE warned_at IS NULL
+              AND starts_at > NOW()
+              AND starts_at <= NOW() + make_interval(mins => $1)
+            RETURNING starts_at, ends_at
+            "#,
+            lead_mins
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(warned.into_iter().min_by_key(|w| w.starts_at))
+    }
+
+    pub async fn get_schedule_comparison(
+        &self,
+        days: i32,
+        tolerance_mins: i32,
+    ) -> sqlx::Result<Vec<ScheduleComparison>> {
+        sqlx::query_as!(
+            ScheduleComparison,
+            r#"
+            SELECT s.starts_at as "starts_at!", s.ends_at as "ends_at!",
+                   a.started_at as actual_started_at, a.ended_at as actual_ended_at
+            FROM (
+                SELECT DISTINCT starts_at, ends_at
+                FROM scheduled_outages
+                WHERE starts_at <= NOW()
+                  AND ends_at > NOW() - make_interval(days => $1)
+            ) s
+            LEFT JOIN LATERAL (
+                SELECT MIN(po.started_at) as started_at,
+                       CASE WHEN bool_or(po.ended_at IS NULL) THEN NULL
+                            ELSE MAX(po.ended_at) END as ended_at
+                FROM power_outages po
+                WHERE po.started_at < s.ends_at + make_interval(mins => $2)
+                  AND COALESCE(po.ended_at, NOW()) > s.starts_at - make_interval(mins => $2)
+                  AND po.cause <> 'network'
+            ) a ON true
+            ORDER BY s.starts_at DESC
+            "#,
+            days,
+            tolerance_mins
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Power outages (not connectivity gaps) still ongoing or ended after `since`
+    pub async fn get_power_outages_since(
+        &self,
+        since: PrimitiveDateTime,
+    ) -> sqlx::Result<Vec<PowerOutage>> {
+        sqlx::query_as!(
+            PowerOutage,
+            r#"
+            SELECT id, started_at, ended_at, duration_minutes,
+                   cause as "cause: OutageCause",
+                   battery_alert_percent::real as "battery_alert_percent: f32"
+            FROM power_outages
+            WHERE COALESCE(ended_at, NOW()::timestamp) > $1 AND cause <> 'network'
+            ORDER BY started_at
+            "#,
+            since
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn get_notification_settings(
+        &self,
+        user_id: i64,
+    ) -> sqlx::Result<NotificationSettings> {
+        let settings = self.find_notification_settings(user_id).await?;
+
+        Ok(settings.unwrap_or_else(|| NotificationSettings {
+            telegram_user_id: user_id,
+            ..Default::default()
+        }))
+    }
+
+    pub async fn find_notification_settings(
+        &self,
+        user_id: i64,
+    ) -> sqlx::Result<Option<NotificationSettings>> {
+        sqlx::query_as!(
+            NotificationSettings,
+            r#"
+            SELECT telegram_user_id, soil_moisture_alerts, temperature_alerts,
+                   power_alerts, outlook_alerts, quiet_hours_enabled, quiet_hours_start,
+                   quiet_hours_end, morning_summary, morning_summary_hour, weekly_report, timezone,
+                   locale as "locale: Locale",
+                   temperature_unit as "temperature_unit: TemperatureUnit",
+                   pressure_unit as "pressure_unit: PressureUnit",
+                   volume_unit as "volume_unit: VolumeUnit"
+            FROM notification_settings
+            WHERE telegram_user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn ensure_notification_settings(
+        &self,
+        user_id: i64,
+        locale: Locale,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO notification_settings (telegram_user_id, locale)
+            VALUES ($1, $2)
+            ON CONFLICT (telegram_user_id) DO NOTHING
+            "#,
+            user_id,
+            locale as Locale
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn toggle_soil_alerts(&self, user_id: i64) -> sqlx::Result<bool> {
+        let result = sqlx::query_scalar!(
+            r#"
             UPDATE notification_settings
             SET soil_moisture_alerts = NOT soil_moisture_alerts
             WHERE telegram_user_id = $1
@@ -317,6 +1450,21 @@ impl Db {
         Ok(result)
     }
 
+    pub async fn toggle_outlook_alerts(&self, user_id: i64) -> sqlx::Result<bool> {
+        let result = sqlx::query_scalar!(
+            r#"
+            UPDATE notification_settings
+            SET outlook_alerts = NOT outlook_alerts
+            WHERE telegram_user_id = $1
+            RETURNING outlook_alerts
+            "#,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(result)
+    }
+
     pub async fn toggle_quiet_hours(&self, user_id: i64) -> sqlx::Result<bool> {
         let result = sqlx::query_scalar!(
             r#"
@@ -332,6 +1480,143 @@ impl Db {
         Ok(result)
     }
 
+    pub async fn toggle_morning_summary(&self, user_id: i64) -> sqlx::Result<bool> {
+        sqlx::query_scalar!(
+            r#"
+            UPDATE notification_settings
+            SET morning_summary = NOT morning_summary
+            WHERE telegram_user_id = $1
+            RETURNING morning_summary
+            "#,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Also turns the summary on, since picking a time means wanting it
+    pub async fn set_morning_summary_hour(&self, user_id: i64, hour: i16) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE notification_settings
+            SET morning_summary_hour = $2, morning_summary = true
+            WHERE telegram_user_id = $1
+            "#,
+            user_id,
+            hour
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Stores the zone under its canonical name; `None` when Postgres doesn't know it,
+    /// since the summary schedule is computed there
+    pub async fn set_timezone(&self, user_id: i64, timezone: &str) -> sqlx::Result<Option<String>> {
+        sqlx::query_scalar!(
+            r#"
+            UPDATE notification_settings
+            SET timezone = tz.name
+            FROM (
+                SELECT name FROM pg_timezone_names WHERE lower(name) = lower($2) LIMIT 1
+            ) tz
+            WHERE telegram_user_id = $1
+            RETURNING timezone
+            "#,
+            user_id,
+            timezone
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Users whose summary hour has come in their time zone and who haven't had
+    /// today's summary yet, marking it sent
+    pub async fn take_due_morning_summaries(&self) -> sqlx::Result<Vec<i64>> {
+        sqlx::query_scalar!(
+            r#"
+            UPDATE notification_settings ns
+            SET morning_summary_sent_on = (NOW() AT TIME ZONE ns.timezone)::date
+            FROM authorized_users au
+            WHERE au.telegram_user_id = ns.telegram_user_id
+              AND (au.expires_at IS NULL OR au.expires_at > NOW())
+              AND ns.morning_summary
+              AND EXTRACT(HOUR FROM NOW() AT TIME ZONE ns.timezone) = ns.morning_summary_hour
+              AND (ns.morning_summary_sent_on IS NULL
+                   OR ns.morning_summary_sent_on < (NOW() AT TIME ZONE ns.timezone)::date)
+            RETURNING ns.telegram_user_id
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn toggle_weekly_report(&self, user_id: i64) -> sqlx::Result<bool> {
+        sqlx::query_scalar!(
+            r#"
+            UPDATE notification_settings
+            SET weekly_report = NOT weekly_report
+            WHERE telegram_user_id = $1
+            RETURNING weekly_report
+            "#,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Like `take_due_morning_summaries`, for the weekly report on `weekday` (ISO)
+    /// from `hour` on
+    pub async fn take_due_weekly_reports(&self, weekday: i32, hour: i32) -> sqlx::Result<Vec<i64>> {
+        sqlx::query_scalar!(
+            r#"
+            UPDATE notification_settings ns
+            SET weekly_report_sent_on = (NOW() AT TIME ZONE ns.timezone)::date
+            FROM authorized_users au
+            WHERE au.telegram_user_id = ns.telegram_user_id
+              AND (au.expires_at IS NULL OR au.expires_at > NOW())
+              AND ns.weekly_report
+              AND EXTRACT(ISODOW FROM NOW() AT TIME ZONE ns.timezone)::int = $1
+              AND EXTRACT(HOUR FROM NOW() AT TIME ZONE ns.timezone)::int >= $2
+              AND (ns.weekly_report_sent_on IS NULL
+                   OR ns.weekly_report_sent_on < (NOW() AT TIME ZONE ns.timezone)::date)
+            RETURNING ns.telegram_user_id
+            "#,
+            weekday,
+            hour
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn set_locale(&self, user_id: i64, locale: Locale) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"UPDATE notification_settings SET locale = $2 WHERE telegram_user_id = $1"#,
+            user_id,
+            locale as Locale
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_units(&self, user_id: i64, units: Units) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE notification_settings
+            SET temperature_unit = $2, pressure_unit = $3, volume_unit = $4
+            WHERE telegram_user_id = $1
+            "#,
+            user_id,
+            units.temperature as TemperatureUnit,
+            units.pressure as PressureUnit,
+            units.volume as VolumeUnit
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn get_users_for_alert(&self, kind: AlertKind) -> sqlx::Result<Vec<i64>> {
         let column = match kind {
             AlertKind::SoilMoistureLow | AlertKind::SoilMoistureHigh => "soil_moisture_alerts",
@@ -345,6 +1630,7 @@ impl Db {
             FROM authorized_users au
             LEFT JOIN notification_settings ns ON au.telegram_user_id = ns.telegram_user_id
             WHERE COALESCE(ns.{}, true) = true
+              AND (au.expires_at IS NULL OR au.expires_at > NOW())
             "#,
             column
         );
@@ -358,50 +1644,397 @@ impl Db {
     pub async fn get_users_for_power_alert(&self) -> sqlx::Result<Vec<i64>> {
         let rows = sqlx::query_scalar!(
             r#"
-            SELECT au.telegram_user_id
+            SELECT au.telegram_user_id as "telegram_user_id!"
             FROM authorized_users au
             LEFT JOIN notification_settings ns ON au.telegram_user_id = ns.telegram_user_id
             WHERE COALESCE(ns.power_alerts, true) = true
+              AND (au.expires_at IS NULL OR au.expires_at > NOW())
             "#
         )
         .fetch_all(&self.pool)
         .await?;
-        Ok(rows.into_iter().flatten().collect())
+        Ok(rows)
+    }
+
+    /// Outlook heads-ups are opt-in, unlike the other alerts
+    pub async fn get_users_for_outlook_alert(&self) -> sqlx::Result<Vec<i64>> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT au.telegram_user_id as "telegram_user_id!"
+            FROM authorized_users au
+            JOIN notification_settings ns ON au.telegram_user_id = ns.telegram_user_id
+            WHERE ns.outlook_alerts = true
+              AND (au.expires_at IS NULL OR au.expires_at > NOW())
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
     }
 
-    pub async fn add_pump_command(&self, duration_secs: i32) -> sqlx::Result<()> {
+    pub async fn add_pump_command(
+        &self,
+        duration_secs: i32,
+        queued_by: Option<i64>,
+    ) -> sqlx::Result<()> {
         sqlx::query!(
             r#"
-            INSERT INTO device_commands (command_type, payload)
-            VALUES ('pump', jsonb_build_object('duration_secs', $1::integer))
+            INSERT INTO device_commands (command_type, payload, queued_by)
+            VALUES ('pump', jsonb_build_object('duration_secs', $1::integer), $2)
             "#,
-            duration_secs
+            duration_secs,
+            queued_by
         )
         .execute(&self.pool)
         .await?;
         Ok(())
     }
 
+    /// Hands the oldest queued pump run to the device, keeping it for the queue view
     pub async fn get_pending_pump_command(&self) -> sqlx::Result<Option<i32>> {
         let row = sqlx::query!(
             r#"
-            SELECT id, (payload->>'duration_secs')::integer as "duration_secs!"
-            FROM device_commands
-            WHERE command_type = 'pump'
-            ORDER BY created_at ASC
-            LIMIT 1
-            "#
+            UPDATE device_commands
+            SET status = $1, updated_at = NOW()
+            WHERE id = (
+                SELECT id FROM device_commands
+                WHERE command_type = 'pump' AND status = $2
+                ORDER BY created_at ASC
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING (payload->>'duration_secs')::integer as "duration_secs!"
+            "#,
+            CommandStatus::Sent as CommandStatus,
+            CommandStatus::Queued as CommandStatus
         )
         .fetch_optional(&self.pool)
         .await?;
 
         if let Some(r) = row {
-            sqlx::query!("DELETE FROM device_commands WHERE id = $1", r.id)
-                .execute(&self.pool)
-                .await?;
+            sqlx::query!(
+                "INSERT INTO watering_events (duration_secs) VALUES ($1)",
+                r.duration_secs
+            )
+            .execute(&self.pool)
+            .await?;
             Ok(Some(r.duration_secs))
         } else {
             Ok(None)
         }
     }
+
+    /// Queued pump runs, oldest first, then the latest finished ones
+    pub async fn get_command_queue(&self, recent: i64) -> sqlx::Result<Vec<DeviceCommand>> {
+        sqlx::query_as!(
+            DeviceCommand,
+            r#"
+            SELECT dc.id, (dc.payload->>'duration_secs')::integer as "duration_secs!",
+                   dc.status as "status: CommandStatus", dc.queued_by, au.username,
+                   (dc.created_at AT TIME ZONE 'UTC') as "created_at!"
+            FROM device_commands dc
+            LEFT JOIN authorized_users au ON au.telegram_user_id = dc.queued_by
+            WHERE dc.command_type = 'pump'
+              AND (dc.status = $1 OR dc.id IN (
+                  SELECT id FROM device_commands
+                  WHERE command_type = 'pump' AND status <> $1
+                  ORDER BY created_at DESC
+                  LIMIT $2
+              ))
+            ORDER BY dc.status = $1 DESC,
+                     CASE WHEN dc.status = $1 THEN dc.created_at END ASC,
+                     dc.created_at DESC
+            "#,
+            CommandStatus::Queued as CommandStatus,
+            recent
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Cancels a command the device has not picked up yet
+    pub async fn cancel_command(&self, id: i32) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            "UPDATE device_commands SET status = $1, updated_at = NOW() WHERE id = $2 AND status = $3",
+            CommandStatus::Cancelled as CommandStatus,
+            id,
+            CommandStatus::Queued as CommandStatus
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Start times of pump runs the device picked up since `since`
+    pub async fn get_watering_times_since(
+        &self,
+        since: PrimitiveDateTime,
+    ) -> sqlx::Result<Vec<PrimitiveDateTime>> {
+        sqlx::query_scalar!(
+            r#"SELECT created_at FROM watering_events WHERE created_at >= $1 ORDER BY created_at"#,
+            since
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// One sensor column averaged over `bucket_secs` buckets since `since`
+    pub async fn get_sensor_records_between(
+        &self,
+        from: PrimitiveDateTime,
+        to: PrimitiveDateTime,
+    ) -> sqlx::Result<Vec<SensorRecord>> {
+        sqlx::query_as!(
+            SensorRecord,
+            r#"
+            SELECT created_at,
+                   temperature::real as "temperature!: f32",
+                   humidity::real as "humidity!: f32",
+                   pressure::real as "pressure!: f32",
+                   soil_moisture::real as "soil_moisture!: f32",
+                   water_level::real as "water_level!: f32"
+            FROM sensor_data
+            WHERE created_at >= $1 AND created_at < $2
+            ORDER BY created_at
+            "#,
+            from,
+            to
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn get_sensor_series(
+        &self,
+        column: &str,
+        since: PrimitiveDateTime,
+        bucket_secs: i64,
+    ) -> sqlx::Result<Vec<SeriesPoint>> {
+        sqlx::query_as!(
+            SeriesPoint,
+            r#"
+            SELECT date_bin(make_interval(secs => $3), created_at, $2) as "at!",
+                   AVG(CASE $1
+                       WHEN 'temperature' THEN temperature
+                       WHEN 'humidity' THEN humidity
+                       WHEN 'pressure' THEN pressure
+                       WHEN 'soil_moisture' THEN soil_moisture
+                       WHEN 'water_level' THEN water_level
+                   END)::real as "value!: f32"
+            FROM sensor_data
+            WHERE created_at >= $2
+            GROUP BY 1
+            ORDER BY 1
+            "#,
+            column,
+            since,
+            bucket_secs as f64
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Min, average and max of every sensor per `bucket_secs` bucket in `[from, to)`,
+    /// starting at the first bucket with readings. Buckets align to the Unix epoch
+    pub async fn get_reading_buckets(
+        &self,
+        from: PrimitiveDateTime,
+        to: PrimitiveDateTime,
+        bucket_secs: i64,
+        limit: i64,
+    ) -> sqlx::Result<Vec<ReadingBucket>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT date_bin(make_interval(secs => $3), created_at, TIMESTAMP '1970-01-01') as "start!",
+                   COUNT(*) as "count!",
+                   MIN(temperature)::real as "temperature_min!: f32",
+                   AVG(temperature)::real as "temperature_avg!: f32",
+                   MAX(temperature)::real as "temperature_max!: f32",
+                   MIN(humidity)::real as "humidity_min!: f32",
+                   AVG(humidity)::real as "humidity_avg!: f32",
+                   MAX(humidity)::real as "humidity_max!: f32",
+                   MIN(pressure)::real as "pressure_min!: f32",
+                   AVG(pressure)::real as "pressure_avg!: f32",
+                   MAX(pressure)::real as "pressure_max!: f32",
+                   MIN(soil_moisture)::real as "soil_moisture_min!: f32",
+                   AVG(soil_moisture)::real as "soil_moisture_avg!: f32",
+                   MAX(soil_moisture)::real as "soil_moisture_max!: f32",
+                   MIN(water_level)::real as "water_level_min!: f32",
+                   AVG(water_level)::real as "water_level_avg!: f32",
+                   MAX(water_level)::real as "water_level_max!: f32"
+            FROM sensor_data
+            WHERE created_at >= $1 AND created_at < $2
+            GROUP BY 1
+            ORDER BY 1
+            LIMIT $4
+            "#,
+            from,
+            to,
+            bucket_secs as f64,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| ReadingBucket {
+                start: r.start,
+                count: r.count,
+                temperature: MetricSummary {
+                    min: r.temperature_min,
+                    avg: r.temperature_avg,
+                    max: r.temperature_max,
+                },
+                humidity: MetricSummary {
+                    min: r.humidity_min,
+                    avg: r.humidity_avg,
+                    max: r.humidity_max,
+                },
+                pressure: MetricSummary {
+                    min: r.pressure_min,
+                    avg: r.pressure_avg,
+                    max: r.pressure_max,
+                },
+                soil_moisture: MetricSummary {
+                    min: r.soil_moisture_min,
+                    avg: r.soil_moisture_avg,
+                    max: r.soil_moisture_max,
+                },
+                water_level: MetricSummary {
+                    min: r.water_level_min,
+                    avg: r.water_level_avg,
+                    max: r.water_level_max,
+                },
+            })
+            .collect())
+    }
+
+    pub async fn get_message_templates(&self) -> sqlx::Result<Vec<MessageTemplate>> {
+        sqlx::query_as!(
+            MessageTemplate,
+            r#"SELECT name, locale, body FROM message_templates ORDER BY locale, name"#
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Starts keeping the message up to date, replacing the chat's previous one
+    pub async fn add_live_status(
+        &self,
+        chat_id: i64,
+        message_id: i32,
+        user_id: i64,
+        reading_id: i32,
+        offline: bool,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO live_status_messages
+            (chat_id, message_id, telegram_user_id, reading_id, offline)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (chat_id) DO UPDATE
+            SET message_id = $2, telegram_user_id = $3, reading_id = $4, offline = $5,
+                edited_at = NOW()
+            "#,
+            chat_id,
+            message_id,
+            user_id,
+            reading_id,
+            offline
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Stops updating the chat's live status, returning its message id
+    pub async fn remove_live_status(&self, chat_id: i64) -> sqlx::Result<Option<i32>> {
+        sqlx::query_scalar!(
+            "DELETE FROM live_status_messages WHERE chat_id = $1 RETURNING message_id",
+            chat_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Live statuses showing another reading or offline state and not edited for
+    /// `min_secs`, marked as showing this one. Skips those whose owner lost access
+    /// or whose group was unlinked
+    pub async fn take_due_live_statuses(
+        &self,
+        reading_id: i32,
+        offline: bool,
+        min_secs: i64,
+    ) -> sqlx::Result<Vec<LiveStatusMessage>> {
+        sqlx::query_as!(
+            LiveStatusMessage,
+            r#"
+            UPDATE live_status_messages
+            SET reading_id = $1, offline = $2, edited_at = NOW()
+            WHERE (reading_id IS DISTINCT FROM $1 OR offline <> $2)
+              AND edited_at <= NOW() - make_interval(secs => $3)
+              AND EXISTS (
+                  SELECT 1 FROM authorized_users au
+                  WHERE au.telegram_user_id = live_status_messages.telegram_user_id
+                    AND (au.expires_at IS NULL OR au.expires_at > NOW())
+              )
+              AND (
+                  chat_id = telegram_user_id
+                  OR EXISTS (
+                      SELECT 1 FROM authorized_chats ac
+                      WHERE ac.chat_id = live_status_messages.chat_id
+                  )
+              )
+            RETURNING chat_id, message_id, telegram_user_id
+            "#,
+            reading_id,
+            offline,
+            min_secs as f64
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres DATABASE_URL"]
+    async fn live_statuses_stop_when_access_ends(pool: sqlx::PgPool) -> sqlx::Result<()> {
+        let db = Db::new(pool);
+        for user_id in [1, 2, 3] {
+            db.authorize_user(user_id, None).await?;
+            db.add_live_status(user_id, 10, user_id, 1, false).await?;
+        }
+        db.authorize_chat(-100, None, Locale::En, 1).await?;
+        db.add_live_status(-100, 11, 1, 1, false).await?;
+        db.add_live_status(-200, 12, 1, 1, false).await?;
+
+        db.revoke_user(2).await?;
+        sqlx::query("UPDATE authorized_users SET expires_at = NOW() WHERE telegram_user_id = 3")
+            .execute(&db.pool)
+            .await?;
+
+        let mut due: Vec<i64> = db
+            .take_due_live_statuses(2, false, 0)
+            .await?
+            .into_iter()
+            .map(|m| m.chat_id)
+            .collect();
+        due.sort();
+        assert_eq!(due, vec![-100, 1]);
+
+        db.unauthorize_chat(-100).await?;
+        assert_eq!(db.remove_expired_users().await?, 1);
+        let due = db.take_due_live_statuses(3, false, 0).await?;
+        assert_eq!(due.iter().map(|m| m.chat_id).collect::<Vec<_>>(), vec![1]);
+
+        let left: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM live_status_messages")
+            .fetch_one(&db.pool)
+            .await?;
+        assert_eq!(left, 2);
+        Ok(())
+    }
 }
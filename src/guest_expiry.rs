@@ -0,0 +1,23 @@
+use std::time::Duration;
+
+use tokio::time::interval;
+
+use crate::config::auth;
+use crate::db::Db;
+
+/// Periodically removes invited guests whose access has ended
+pub fn spawn_guest_expiry(db: Db) {
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(auth::EXPIRY_CHECK_SECS));
+
+        loop {
+            interval.tick().await;
+
+            match db.remove_expired_users().await {
+                Ok(0) => {}
+                Ok(removed) => println!("Removed {} guest(s) with expired access", removed),
+                Err(e) => eprintln!("Failed to remove expired guests: {}", e),
+            }
+        }
+    });
+}
@@ -3,10 +3,16 @@ use sqlx::PgPool;
 
 use crate::alerter::Alerter;
 use crate::db::{AlertKind, Db};
-use crate::power_monitor::check_power_restored;
+use crate::live_status::LiveStatus;
+use crate::power_monitor::check_power_reading;
 use crate::services::{should_alert_soil_low, should_alert_temp_high};
+use crate::templates::{TemplateId, Value};
 
-pub async fn spawn_sensor_listener(pool: PgPool, alerter: Alerter) -> anyhow::Result<()> {
+pub async fn spawn_sensor_listener(
+    pool: PgPool,
+    alerter: Alerter,
+    live_status: LiveStatus,
+) -> anyhow::Result<()> {
     let mut listener = PgListener::connect_with(&pool).await?;
     listener.listen("sensor_data").await?;
 
@@ -21,6 +27,9 @@ pub async fn spawn_sensor_listener(pool: PgPool, alerter: Alerter) -> anyhow::Re
                         if let Err(e) = process_sensor_data(&db, &alerter, id).await {
                             eprintln!("Failed to process sensor data: {}", e);
                         }
+                        if let Err(e) = live_status.refresh().await {
+                            eprintln!("Failed to refresh live statuses: {}", e);
+                        }
                     }
                 }
                 Err(e) => {
@@ -35,17 +44,22 @@ pub async fn spawn_sensor_listener(pool: PgPool, alerter: Alerter) -> anyhow::Re
 }
 
 async fn process_sensor_data(db: &Db, alerter: &Alerter, id: i32) -> anyhow::Result<()> {
-    check_power_restored(db, alerter).await?;
-
     let Some(data) = db.get_sensor_data_by_id(id).await? else {
         return Ok(());
     };
 
+    check_power_reading(db, alerter, id, &data).await?;
+
     alerter
         .check_and_alert(
             AlertKind::SoilMoistureLow,
             should_alert_soil_low(&data),
-            &format!("⚠️ Low soil moisture: {:.1}%", data.soil_moisture),
+            |t| {
+                t.render(
+                    TemplateId::AlertSoilLow,
+                    &[("soil_moisture", data.soil_moisture.into())],
+                )
+            },
         )
         .await?;
 
@@ -53,7 +67,12 @@ async fn process_sensor_data(db: &Db, alerter: &Alerter, id: i32) -> anyhow::Res
         .check_and_alert(
             AlertKind::TemperatureHigh,
             should_alert_temp_high(&data),
-            &format!("🔥 High temperature: {:.1}°C", data.temperature),
+            |t| {
+                t.render(
+                    TemplateId::AlertTempHigh,
+                    &[("temperature", Value::Temperature(data.temperature))],
+                )
+            },
         )
         .await?;
 
@@ -0,0 +1,133 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use teloxide::{
+    prelude::*,
+    types::{ChatId, MessageId},
+    ApiError, RequestError,
+};
+use time::{OffsetDateTime, PrimitiveDateTime};
+use tokio::time::interval;
+
+use crate::bot::{format_status, live_status_keyboard};
+use crate::config::live;
+use crate::db::{Db, SensorData};
+use crate::services::{current_battery, format_kyiv, BatteryStatus};
+use crate::templates::{Localized, TemplateId, Templates};
+
+/// The latest reading as shown in pinned status messages
+pub struct LiveReading {
+    pub id: i32,
+    pub offline: bool,
+    read_at: PrimitiveDateTime,
+    data: SensorData,
+    battery: Option<BatteryStatus>,
+}
+
+impl LiveReading {
+    pub async fn latest(db: &Db) -> anyhow::Result<Option<Self>> {
+        let Some(stamp) = db.get_latest_reading_stamp().await? else {
+            return Ok(None);
+        };
+        let Some(data) = db.get_sensor_data_by_id(stamp.id).await? else {
+            return Ok(None);
+        };
+
+        let age = OffsetDateTime::now_utc() - stamp.created_at.assume_utc();
+        Ok(Some(Self {
+            id: stamp.id,
+            offline: age.whole_seconds() > live::OFFLINE_SECS,
+            read_at: stamp.created_at,
+            battery: current_battery(db, &data).await?,
+            data,
+        }))
+    }
+
+    /// Status with when the reading arrived, or an offline badge once readings stop
+    pub fn format(&self, t: &Localized<'_>) -> String {
+        let footer = if self.offline {
+            TemplateId::LiveStatusOffline
+        } else {
+            TemplateId::LiveStatusUpdated
+        };
+        let mut status = format_status(t, &self.data, self.battery.as_ref());
+        status.push_str(&t.render(footer, &[("time", format_kyiv(self.read_at).into())]));
+        status
+    }
+}
+
+/// Edits pinned status messages as readings arrive, at most once a minute each
+#[derive(Clone)]
+pub struct LiveStatus {
+    bot: Arc<Bot>,
+    db: Db,
+    templates: Arc<Templates>,
+}
+
+impl LiveStatus {
+    pub fn new(bot: Arc<Bot>, db: Db, templates: Arc<Templates>) -> Self {
+        Self { bot, db, templates }
+    }
+
+    /// Edits the messages showing an older reading or a stale offline badge
+    /// whose last edit is old enough
+    pub async fn refresh(&self) -> anyhow::Result<()> {
+        let Some(reading) = LiveReading::latest(&self.db).await? else {
+            return Ok(());
+        };
+        let messages = self
+            .db
+            .take_due_live_statuses(reading.id, reading.offline, live::MIN_EDIT_SECS)
+            .await?;
+
+        for message in messages {
+            let settings = self
+                .db
+                .get_notification_settings(message.telegram_user_id)
+                .await
+                .unwrap_or_default();
+            let t = self.templates.for_user(&settings);
+            let chat_id = ChatId(message.chat_id);
+
+            let edited = t
+                .edit_message_text(
+                    &self.bot,
+                    chat_id,
+                    MessageId(message.message_id),
+                    reading.format(&t),
+                )
+                .reply_markup(live_status_keyboard(&t))
+                .await;
+            match edited {
+                Ok(_) | Err(RequestError::Api(ApiError::MessageNotModified)) => {}
+                // Deleted by the user or the chat is gone, so stop trying
+                Err(RequestError::Api(
+                    ApiError::MessageToEditNotFound
+                    | ApiError::MessageIdInvalid
+                    | ApiError::BotBlocked
+                    | ApiError::ChatNotFound,
+                )) => {
+                    self.db.remove_live_status(message.chat_id).await?;
+                }
+                Err(e) => eprintln!("Failed to update live status in {}: {}", chat_id, e),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Catches up on readings skipped by the throttle and marks silent sensors offline
+pub fn spawn_live_status(live_status: LiveStatus) {
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(live::CHECK_INTERVAL_SECS));
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = live_status.refresh().await {
+                eprintln!("Live status error: {}", e);
+            }
+        }
+    });
+}
@@ -1,26 +1,41 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::State,
-    http::{HeaderMap, StatusCode},
+    extract::{Query, State},
+    http::StatusCode,
     routing::{get, get_service, post},
     Json, Router,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use sqlx::postgres::PgPoolOptions;
 use teloxide::Bot;
 use tokio::net::TcpListener;
 use tower_http::services::{ServeDir, ServeFile};
 
 mod alerter;
+mod api_auth;
 mod bot;
 mod config;
 mod db;
+mod guest_expiry;
 mod listener;
+mod live_status;
+mod mini_app;
+mod morning_summary;
+mod outage_schedule;
 mod power_monitor;
 mod services;
+mod status_page;
+mod templates;
+mod weekly_report;
 
-use db::{Db, SensorData};
+use api_auth::ApiClient;
+use db::{ApiScope, Db, SensorData};
+use services::{
+    parse_api_time, power_availability, readings_page, BucketSize, ChartMetric, PowerAvailability,
+    PowerPeriod, ReadingsPage, ReadingsRequest,
+};
+use time::{Duration, OffsetDateTime, PrimitiveDateTime};
 
 #[derive(Clone)]
 struct AppState {
@@ -49,29 +64,67 @@ async fn main() {
         .expect("Failed to run migrations");
 
     let db = Db::new(pool.clone());
+
+    if std::env::args().nth(1).as_deref() == Some("backfill-outages") {
+        let gaps = db
+            .get_sensor_gaps(config::power::OUTAGE_THRESHOLD_SECS)
+            .await
+            .expect("Failed to find sensor data gaps");
+        let outages: Vec<_> = gaps.iter().map(services::classify_gap).collect();
+        let count = db
+            .replace_finished_outages(&outages)
+            .await
+            .expect("Failed to rebuild power outages");
+        println!(
+            "Rebuilt {} power outages from sensor data gaps, keeping those seen on battery",
+            count
+        );
+        return;
+    }
     let bot = Arc::new(Bot::from_env());
+    let bot_token = bot.token().to_string();
+
+    let templates = Arc::new(
+        templates::Templates::load(&db)
+            .await
+            .expect("Failed to load message templates"),
+    );
 
     let bot_state = bot::BotState {
         db: db.clone(),
         bot_secret,
+        templates: templates.clone(),
     };
 
     let bot_router = bot::init_bot(bot.clone(), webhook_secret, bot_state)
         .await
         .expect("Failed to init bot");
 
-    let alerter = alerter::Alerter::new(bot, db.clone());
+    morning_summary::spawn_morning_summary(bot.clone(), db.clone(), templates.clone());
+    weekly_report::spawn_weekly_report(bot.clone(), db.clone(), templates.clone());
 
-    listener::spawn_sensor_listener(pool, alerter.clone())
+    let live_status = live_status::LiveStatus::new(bot.clone(), db.clone(), templates.clone());
+    live_status::spawn_live_status(live_status.clone());
+
+    let alerter = alerter::Alerter::new(bot, db.clone(), templates.clone());
+
+    listener::spawn_sensor_listener(pool, alerter.clone(), live_status)
         .await
         .expect("Failed to spawn sensor listener");
 
+    outage_schedule::spawn_schedule_sync(db.clone());
+    guest_expiry::spawn_guest_expiry(db.clone());
     power_monitor::spawn_power_monitor(db.clone(), alerter);
 
-    let state = AppState { db, api_key };
+    let state = AppState {
+        db: db.clone(),
+        api_key,
+    };
 
     let app = Router::new()
         .merge(bot_router)
+        .merge(status_page::routes(db.clone(), templates.clone()))
+        .merge(mini_app::routes(db.clone(), templates, bot_token))
         .nest("/api", api_routes(state))
         .route_service("/cv", get_service(ServeFile::new("static/cv/cv.pdf")))
         .route_service("/cv/", get_service(ServeFile::new("static/cv/cv.pdf")))
@@ -87,24 +140,21 @@ fn api_routes(state: AppState) -> Router {
     Router::new()
         .route("/tasks", get(get_tasks))
         .route("/sensor", post(post_sensor))
+        .route("/heartbeat", post(post_heartbeat))
+        .route("/power/stats", get(get_power_stats))
+        .route("/status", get(get_status))
+        .route("/readings", get(get_readings))
+        .route("/water", post(post_water))
         .with_state(state)
 }
 
-fn check_api_key(headers: &HeaderMap, expected: &str) -> bool {
-    headers
-        .get("X-Api-Key")
-        .and_then(|v| v.to_str().ok())
-        .map(|v| v == expected)
-        .unwrap_or(false)
-}
-
 async fn post_sensor(
     State(state): State<AppState>,
-    headers: HeaderMap,
+    client: ApiClient,
     Json(data): Json<SensorData>,
 ) -> StatusCode {
-    if !check_api_key(&headers, &state.api_key) {
-        return StatusCode::UNAUTHORIZED;
+    if !client.is_device() {
+        return StatusCode::FORBIDDEN;
     }
 
     println!(
@@ -121,6 +171,39 @@ async fn post_sensor(
     }
 }
 
+#[derive(Deserialize)]
+struct Heartbeat {
+    source: String,
+}
+
+/// Pinged by anything that only runs while the power is on (router, Pi, ...)
+async fn post_heartbeat(
+    State(state): State<AppState>,
+    client: ApiClient,
+    Json(heartbeat): Json<Heartbeat>,
+) -> StatusCode {
+    if !client.is_device() {
+        return StatusCode::FORBIDDEN;
+    }
+
+    let source = heartbeat.source.trim();
+    if source.is_empty() || source.len() > 32 || source == config::power::SENSOR_SOURCE {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    match state
+        .db
+        .record_heartbeat(source, config::power::OUTAGE_THRESHOLD_SECS)
+        .await
+    {
+        Ok(_) => StatusCode::OK,
+        Err(e) => {
+            eprintln!("Failed to record heartbeat: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
 #[derive(Serialize)]
 struct TasksResponse {
     pump_duration: u16,
@@ -128,10 +211,10 @@ struct TasksResponse {
 
 async fn get_tasks(
     State(state): State<AppState>,
-    headers: HeaderMap,
+    client: ApiClient,
 ) -> Result<Json<TasksResponse>, StatusCode> {
-    if !check_api_key(&headers, &state.api_key) {
-        return Err(StatusCode::UNAUTHORIZED);
+    if !client.is_device() {
+        return Err(StatusCode::FORBIDDEN);
     }
 
     let pump_duration = state
@@ -144,3 +227,144 @@ async fn get_tasks(
 
     Ok(Json(TasksResponse { pump_duration }))
 }
+
+#[derive(Deserialize)]
+struct PowerStatsQuery {
+    #[serde(default)]
+    period: PowerPeriod,
+}
+
+async fn get_power_stats(
+    State(state): State<AppState>,
+    client: ApiClient,
+    Query(query): Query<PowerStatsQuery>,
+) -> Result<Json<PowerAvailability>, StatusCode> {
+    if !client.allows(ApiScope::Read) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    match power_availability(&state.db, query.period).await {
+        Ok(stats) => Ok(Json(stats)),
+        Err(e) => {
+            eprintln!("Failed to compute power stats: {:?}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Latest reading, for scripts with a `read` token
+async fn get_status(
+    State(state): State<AppState>,
+    client: ApiClient,
+) -> Result<Json<SensorData>, StatusCode> {
+    if !client.allows(ApiScope::Read) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    match state.db.get_latest_sensor_data().await {
+        Ok(Some(data)) => Ok(Json(data)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            eprintln!("Failed to get latest sensor data: {:?}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct WaterRequest {
+    seconds: i32,
+}
+
+/// Queues watering for scripts with a `water` token
+async fn post_water(
+    State(state): State<AppState>,
+    client: ApiClient,
+    Json(request): Json<WaterRequest>,
+) -> StatusCode {
+    if !client.allows(ApiScope::Water) {
+        return StatusCode::FORBIDDEN;
+    }
+    if !(config::water::PUMP_MIN_SECS..=config::water::PUMP_MAX_SECS).contains(&request.seconds) {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    match state
+        .db
+        .add_pump_command(request.seconds, client.user_id())
+        .await
+    {
+        Ok(_) => StatusCode::OK,
+        Err(e) => {
+            eprintln!("Failed to queue watering from the API: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ReadingsQuery {
+    from: Option<String>,
+    to: Option<String>,
+    #[serde(default)]
+    bucket: BucketSize,
+    /// Comma-separated metric names; all of them when missing
+    metrics: Option<String>,
+    limit: Option<i64>,
+}
+
+/// Min, average and max per bucket over `[from, to)`, the last day by default.
+/// Pages hold up to `limit` buckets; pass `next` as `from` for the following one
+async fn get_readings(
+    State(state): State<AppState>,
+    client: ApiClient,
+    Query(query): Query<ReadingsQuery>,
+) -> Result<Json<ReadingsPage>, StatusCode> {
+    if !client.allows(ApiScope::Read) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let to = match query.to.as_deref() {
+        Some(to) => parse_api_time(to).ok_or(StatusCode::BAD_REQUEST)?,
+        None => {
+            let now = OffsetDateTime::now_utc();
+            PrimitiveDateTime::new(now.date(), now.time())
+        }
+    };
+    let from = match query.from.as_deref() {
+        Some(from) => parse_api_time(from).ok_or(StatusCode::BAD_REQUEST)?,
+        None => to
+            .checked_sub(Duration::hours(config::readings::DEFAULT_HOURS))
+            .ok_or(StatusCode::BAD_REQUEST)?,
+    };
+    if from >= to {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let metrics = match query.metrics.as_deref().map(str::trim) {
+        None | Some("") => ChartMetric::ALL.to_vec(),
+        Some(names) => names
+            .split(',')
+            .map(|name| ChartMetric::from_name(name.trim()))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(StatusCode::BAD_REQUEST)?,
+    };
+    let limit = query
+        .limit
+        .unwrap_or(config::readings::DEFAULT_LIMIT)
+        .clamp(1, config::readings::MAX_LIMIT);
+
+    let request = ReadingsRequest {
+        from,
+        to,
+        bucket: query.bucket,
+        metrics,
+        limit,
+    };
+    match readings_page(&state.db, &request).await {
+        Ok(page) => Ok(Json(page)),
+        Err(e) => {
+            eprintln!("Failed to query readings: {:?}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
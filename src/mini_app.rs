@@ -0,0 +1,479 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{FromRequestParts, Path, Query, State},
+    http::{header, request::Parts, StatusCode},
+    response::{Html, IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use time::{Duration, OffsetDateTime};
+
+use crate::bot::build_chart;
+use crate::config::{mini_app, water};
+use crate::db::{Db, NotificationSettings, Role};
+use crate::live_status::LiveReading;
+use crate::services::{ChartMetric, PowerPeriod};
+use crate::templates::{Localized, TemplateId, Templates};
+
+static PAGE: &str = include_str!("../assets/mini_app.html");
+
+#[derive(Clone)]
+struct AppState {
+    db: Db,
+    templates: Arc<Templates>,
+    bot_token: String,
+}
+
+/// Telegram Mini App dashboard and the API behind it
+pub fn routes(db: Db, templates: Arc<Templates>, bot_token: String) -> Router {
+    Router::new()
+        .route("/app", get(page))
+        .route("/api/app/status", get(status))
+        .route("/api/app/chart", get(chart))
+        .route("/api/app/water", post(water_now))
+        .route("/api/app/settings", get(settings))
+        .route("/api/app/settings/{key}", post(toggle_setting))
+        .with_state(AppState {
+            db,
+            templates,
+            bot_token,
+        })
+}
+
+async fn page() -> Html<&'static str> {
+    Html(PAGE)
+}
+
+/// An authorized user, verified from the Mini App's `initData` sent as
+/// `Authorization: tma <initData>`
+struct AppUser {
+    id: i64,
+    role: Role,
+    settings: NotificationSettings,
+}
+
+impl AppUser {
+    /// Renders as HTML, which the page inserts as is
+    fn templates<'a>(&self, state: &'a AppState) -> Localized<'a> {
+        state.templates.for_user(&self.settings).html()
+    }
+}
+
+impl FromRequestParts<AppState> for AppUser {
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, StatusCode> {
+        let init_data = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("tma "))
+            .ok_or(StatusCode::UNAUTHORIZED)?;
+        let id = verify_init_data(init_data, &state.bot_token, OffsetDateTime::now_utc())
+            .ok_or(StatusCode::UNAUTHORIZED)?;
+
+        let role = match state.db.get_user_role(id).await {
+            Ok(Some(role)) => role,
+            Ok(None) => return Err(StatusCode::FORBIDDEN),
+            Err(e) => {
+                eprintln!("Failed to get Mini App user role: {:?}", e);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        };
+        let settings = state
+            .db
+            .get_notification_settings(id)
+            .await
+            .unwrap_or_default();
+
+        Ok(Self { id, role, settings })
+    }
+}
+
+#[derive(Deserialize)]
+struct InitDataUser {
+    id: i64,
+}
+
+/// Telegram user id from Mini App `initData`, if it is signed with the bot token
+/// and recent enough
+fn verify_init_data(init_data: &str, bot_token: &str, now: OffsetDateTime) -> Option<i64> {
+    let mut hash = None;
+    let mut fields = Vec::new();
+    for (key, value) in form_urlencoded::parse(init_data.as_bytes()) {
+        if key == "hash" {
+            hash = Some(value.into_owned());
+        } else {
+            fields.push((key.into_owned(), value.into_owned()));
+        }
+    }
+    let hash = hex::decode(hash?).ok()?;
+
+    fields.sort();
+    let check_string = fields
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let secret = Hmac::<Sha256>::new_from_slice(b"WebAppData")
+        .ok()?
+        .chain_update(bot_token)
+        .finalize()
+        .into_bytes();
+    Hmac::<Sha256>::new_from_slice(&secret)
+        .ok()?
+        .chain_update(check_string)
+        .verify_slice(&hash)
+        .ok()?;
+
+    let field = |name: &str| fields.iter().find(|(key, _)| key == name).map(|f| &f.1);
+    let auth_date: i64 = field("auth_date")?.parse().ok()?;
+    let age = now.unix_timestamp().checked_sub(auth_date)?;
+    if !(-mini_app::INIT_DATA_MAX_SKEW_SECS..=mini_app::INIT_DATA_MAX_AGE_SECS).contains(&age) {
+        return None;
+    }
+    let user: InitDataUser = serde_json::from_str(field("user")?).ok()?;
+    Some(user.id)
+}
+
+#[derive(Serialize)]
+struct Choice {
+    name: &'static str,
+    label: String,
+}
+
+#[derive(Serialize)]
+struct Labels {
+    status: String,
+    charts: String,
+    water: String,
+    settings: String,
+    water_prompt: String,
+}
+
+#[derive(Serialize)]
+struct StatusResponse {
+    role: &'static str,
+    can_water: bool,
+    water_min: i32,
+    water_max: i32,
+    /// Latest reading with its time or an offline badge, as HTML
+    status: Option<String>,
+    labels: Labels,
+    metrics: Vec<Choice>,
+    periods: Vec<Choice>,
+}
+
+/// Latest reading plus what the page needs to draw itself in the user's language
+async fn status(State(state): State<AppState>, user: AppUser) -> Json<StatusResponse> {
+    let t = user.templates(&state);
+    let status = match LiveReading::latest(&state.db).await {
+        Ok(reading) => reading.map(|reading| reading.format(&t)),
+        Err(e) => {
+            eprintln!("Failed to load the latest reading: {:?}", e);
+            None
+        }
+    };
+
+    Json(StatusResponse {
+        role: user.role.name(),
+        can_water: user.role >= Role::Operator,
+        water_min: water::PUMP_MIN_SECS,
+        water_max: water::PUMP_MAX_SECS,
+        status,
+        labels: Labels {
+            status: t.label(TemplateId::ButtonStatus),
+            charts: t.label(TemplateId::ButtonCharts),
+            water: t.label(TemplateId::ButtonWater),
+            settings: t.label(TemplateId::ButtonSettings),
+            water_prompt: t.plain(
+                TemplateId::MiniAppWaterPrompt,
+                &[
+                    ("min", water::PUMP_MIN_SECS.into()),
+                    ("max", water::PUMP_MAX_SECS.into()),
+                ],
+            ),
+        },
+        metrics: ChartMetric::ALL
+            .iter()
+            .map(|m| Choice {
+                name: m.name(),
+                label: t.label(m.label()),
+            })
+            .collect(),
+        periods: PowerPeriod::ALL
+            .iter()
+            .map(|p| Choice {
+                name: p.name(),
+                label: t.label(p.button()),
+            })
+            .collect(),
+    })
+}
+
+#[derive(Deserialize)]
+struct ChartQuery {
+    metric: String,
+    #[serde(default)]
+    period: PowerPeriod,
+}
+
+/// PNG chart like the bot's, 404 without readings
+async fn chart(
+    State(state): State<AppState>,
+    user: AppUser,
+    Query(query): Query<ChartQuery>,
+) -> Response {
+    let Some(metric) = ChartMetric::from_name(&query.metric) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    let t = state.templates.for_user(&user.settings);
+    let span = Duration::days(query.period.days());
+    let label = t.label(query.period.label());
+
+    match build_chart(&state.db, &t, metric, span, label).await {
+        Some(png) => ([(header::CONTENT_TYPE, "image/png")], png).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+struct WaterRequest {
+    seconds: i32,
+}
+
+#[derive(Serialize)]
+struct MessageResponse {
+    message: String,
+}
+
+/// Queues watering for operators and admins
+async fn water_now(
+    State(state): State<AppState>,
+    user: AppUser,
+    Json(request): Json<WaterRequest>,
+) -> (StatusCode, Json<MessageResponse>) {
+    let t = user.templates(&state);
+    let seconds = request.seconds;
+
+    let (status, message) = if user.role < Role::Operator {
+        (StatusCode::FORBIDDEN, t.text(TemplateId::NotPermitted))
+    } else if !(water::PUMP_MIN_SECS..=water::PUMP_MAX_SECS).contains(&seconds) {
+        let message = t.render(
+            TemplateId::WaterDurationOutOfRange,
+            &[
+                ("min", water::PUMP_MIN_SECS.into()),
+                ("max", water::PUMP_MAX_SECS.into()),
+            ],
+        );
+        (StatusCode::BAD_REQUEST, message)
+    } else {
+        match state.db.add_pump_command(seconds, Some(user.id)).await {
+            Ok(_) => (
+                StatusCode::OK,
+                t.render(TemplateId::WaterQueued, &[("seconds", seconds.into())]),
+            ),
+            Err(e) => {
+                eprintln!("Failed to queue watering from the Mini App: {:?}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    t.text(TemplateId::WaterQueueFailed),
+                )
+            }
+        }
+    };
+
+    (status, Json(MessageResponse { message }))
+}
+
+/// Switches the dashboard shows, by key
+const TOGGLES: &[(&str, TemplateId)] = &[
+    ("soil", TemplateId::SoilAlerts),
+    ("temp", TemplateId::TemperatureAlerts),
+    ("power", TemplateId::PowerAlerts),
+    ("outlook", TemplateId::OutlookAlerts),
+    ("quiet", TemplateId::QuietHours),
+    ("morning", TemplateId::MorningSummaries),
+    ("weekly", TemplateId::WeeklyReports),
+];
+
+#[derive(Serialize)]
+struct Toggle {
+    key: &'static str,
+    label: String,
+    enabled: bool,
+}
+
+async fn settings(State(state): State<AppState>, user: AppUser) -> Json<Vec<Toggle>> {
+    Json(toggles(&user.templates(&state), &user.settings))
+}
+
+async fn toggle_setting(
+    State(state): State<AppState>,
+    user: AppUser,
+    Path(key): Path<String>,
+) -> Result<Json<Vec<Toggle>>, StatusCode> {
+    let db = &state.db;
+    let _ = db
+        .ensure_notification_settings(user.id, user.settings.locale)
+        .await;
+
+    let result = match key.as_str() {
+        "soil" => db.toggle_soil_alerts(user.id).await,
+        "temp" => db.toggle_temperature_alerts(user.id).await,
+        "power" => db.toggle_power_alerts(user.id).await,
+        "outlook" => db.toggle_outlook_alerts(user.id).await,
+        "quiet" => db.toggle_quiet_hours(user.id).await,
+        "morning" => db.toggle_morning_summary(user.id).await,
+        "weekly" => db.toggle_weekly_report(user.id).await,
+        _ => return Err(StatusCode::NOT_FOUND),
+    };
+    let settings = match result {
+        Ok(_) => db.get_notification_settings(user.id).await,
+        Err(e) => Err(e),
+    };
+
+    match settings {
+        Ok(settings) => {
+            let t = state.templates.for_user(&settings).html();
+            Ok(Json(toggles(&t, &settings)))
+        }
+        Err(e) => {
+            eprintln!("Failed to toggle {} from the Mini App: {:?}", key, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+fn toggles(t: &Localized<'_>, settings: &NotificationSettings) -> Vec<Toggle> {
+    TOGGLES
+        .iter()
+        .map(|&(key, label)| Toggle {
+            key,
+            label: t.label(label),
+            enabled: match key {
+                "soil" => settings.soil_moisture_alerts,
+                "temp" => settings.temperature_alerts,
+                "power" => settings.power_alerts,
+                "outlook" => settings.outlook_alerts,
+                "quiet" => settings.quiet_hours_enabled,
+                "morning" => settings.morning_summary,
+                _ => settings.weekly_report,
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN: &str = "123456:test-token";
+
+    /// initData signed the way Telegram signs it
+    fn signed(fields: &[(&str, &str)], token: &str) -> String {
+        let mut sorted = fields.to_vec();
+        sorted.sort();
+        let check_string = sorted
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let secret = Hmac::<Sha256>::new_from_slice(b"WebAppData")
+            .unwrap()
+            .chain_update(token)
+            .finalize()
+            .into_bytes();
+        let hash = Hmac::<Sha256>::new_from_slice(&secret)
+            .unwrap()
+            .chain_update(check_string)
+            .finalize()
+            .into_bytes();
+
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(fields)
+            .append_pair("hash", &hex::encode(hash))
+            .finish()
+    }
+
+    fn now() -> OffsetDateTime {
+        OffsetDateTime::from_unix_timestamp(1_760_000_000).unwrap()
+    }
+
+    #[test]
+    fn accepts_signed_init_data() {
+        let data = signed(
+            &[
+                ("auth_date", "1759999000"),
+                ("query_id", "AAE"),
+                ("user", r#"{"id":42,"first_name":"Ann"}"#),
+            ],
+            TOKEN,
+        );
+        assert_eq!(verify_init_data(&data, TOKEN, now()), Some(42));
+    }
+
+    #[test]
+    fn rejects_other_token() {
+        let data = signed(
+            &[("auth_date", "1759999000"), ("user", r#"{"id":42}"#)],
+            "654321:other-token",
+        );
+        assert_eq!(verify_init_data(&data, TOKEN, now()), None);
+    }
+
+    #[test]
+    fn rejects_tampered_fields() {
+        let data = signed(
+            &[("auth_date", "1759999000"), ("user", r#"{"id":42}"#)],
+            TOKEN,
+        );
+        let tampered = data.replace("%3A42", "%3A43");
+        assert_eq!(verify_init_data(&tampered, TOKEN, now()), None);
+
+        let extra = format!("{}&role=admin", data);
+        assert_eq!(verify_init_data(&extra, TOKEN, now()), None);
+    }
+
+    #[test]
+    fn rejects_stale_future_or_incomplete_init_data() {
+        let stale = signed(
+            &[("auth_date", "1700000000"), ("user", r#"{"id":42}"#)],
+            TOKEN,
+        );
+        assert_eq!(verify_init_data(&stale, TOKEN, now()), None);
+
+        let skewed = signed(
+            &[("auth_date", "1760000060"), ("user", r#"{"id":42}"#)],
+            TOKEN,
+        );
+        assert_eq!(verify_init_data(&skewed, TOKEN, now()), Some(42));
+
+        let future = signed(
+            &[("auth_date", "1760086400"), ("user", r#"{"id":42}"#)],
+            TOKEN,
+        );
+        assert_eq!(verify_init_data(&future, TOKEN, now()), None);
+
+        let far_past = signed(
+            &[
+                ("auth_date", &i64::MIN.to_string()),
+                ("user", r#"{"id":42}"#),
+            ],
+            TOKEN,
+        );
+        assert_eq!(verify_init_data(&far_past, TOKEN, now()), None);
+
+        let no_user = signed(&[("auth_date", "1759999000")], TOKEN);
+        assert_eq!(verify_init_data(&no_user, TOKEN, now()), None);
+
+        assert_eq!(verify_init_data("auth_date=1759999000", TOKEN, now()), None);
+        assert_eq!(verify_init_data("hash=zz", TOKEN, now()), None);
+        assert_eq!(verify_init_data("", TOKEN, now()), None);
+    }
+}
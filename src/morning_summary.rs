@@ -0,0 +1,43 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use teloxide::{prelude::*, types::ChatId};
+use tokio::time::interval;
+
+use crate::bot::build_morning_summary;
+use crate::config::morning;
+use crate::db::Db;
+use crate::services::user_timezone;
+use crate::templates::Templates;
+
+/// Sends each opted-in user their morning summary at the hour they chose, in their time zone
+pub fn spawn_morning_summary(bot: Arc<Bot>, db: Db, templates: Arc<Templates>) {
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(morning::CHECK_INTERVAL_SECS));
+
+        loop {
+            interval.tick().await;
+
+            let user_ids = match db.take_due_morning_summaries().await {
+                Ok(user_ids) => user_ids,
+                Err(e) => {
+                    eprintln!("Failed to find due morning summaries: {}", e);
+                    continue;
+                }
+            };
+
+            for user_id in user_ids {
+                let settings = db
+                    .get_notification_settings(user_id)
+                    .await
+                    .unwrap_or_default();
+                let t = templates.for_user(&settings);
+                let tz = user_timezone(&settings.timezone);
+                let summary = build_morning_summary(&db, &t, tz).await;
+                if let Err(e) = t.send_message(&bot, ChatId(user_id), summary).await {
+                    eprintln!("Failed to send morning summary to {}: {}", user_id, e);
+                }
+            }
+        }
+    });
+}
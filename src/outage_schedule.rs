@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use tokio::time::interval;
+
+use crate::config::schedule;
+use crate::db::Db;
+use crate::services::fetch_schedule;
+
+/// Periodically imports the published outage schedule from `OUTAGE_SCHEDULE_FILE`
+/// (JSON or ICS) and/or `OUTAGE_SCHEDULE_URL`
+pub fn spawn_schedule_sync(db: Db) {
+    let sources: Vec<String> = ["OUTAGE_SCHEDULE_FILE", "OUTAGE_SCHEDULE_URL"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .filter(|source| !source.is_empty())
+        .collect();
+
+    if sources.is_empty() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(schedule::SYNC_INTERVAL_SECS));
+
+        loop {
+            interval.tick().await;
+
+            for source in &sources {
+                if let Err(e) = sync_schedule(&db, source).await {
+                    eprintln!("Failed to import outage schedule from {}: {:#}", source, e);
+                }
+            }
+        }
+    });
+}
+
+async fn sync_schedule(db: &Db, source: &str) -> anyhow::Result<()> {
+    let windows = fetch_schedule(source).await?;
+    db.replace_scheduled_outages(source, &windows).await?;
+    println!(
+        "Imported {} scheduled outages from {}",
+        windows.len(),
+        source
+    );
+    Ok(())
+}
@@ -1,16 +1,21 @@
 use std::time::Duration;
 
-use time::OffsetDateTime;
+use time::{OffsetDateTime, PrimitiveDateTime};
 use tokio::time::interval;
 
 use crate::alerter::Alerter;
-use crate::config::power;
-use crate::db::Db;
-use crate::services::{format_kyiv, now_kyiv};
+use crate::config::{outlook, power, schedule};
+use crate::db::{Db, OutageCause, PowerOutage, PowerSource, SensorData};
+use crate::services::{
+    battery_alert_level, battery_status, classify_gap, format_kyiv, format_kyiv_time,
+    is_on_battery, outage_outlook, FinishedOutage,
+};
+use crate::templates::{TemplateId, Value};
 
 pub fn spawn_power_monitor(db: Db, alerter: Alerter) {
     tokio::spawn(async move {
         let mut interval = interval(Duration::from_secs(power::CHECK_INTERVAL_SECS));
+        let mut last_heads_up = None;
 
         loop {
             interval.tick().await;
@@ -18,60 +23,299 @@ pub fn spawn_power_monitor(db: Db, alerter: Alerter) {
             if let Err(e) = check_power_status(&db, &alerter).await {
                 eprintln!("Power monitor error: {}", e);
             }
+
+            if let Err(e) = warn_scheduled_outage(&db, &alerter).await {
+                eprintln!("Scheduled outage warning error: {}", e);
+            }
+
+            if let Err(e) = warn_predicted_outage(&db, &alerter, &mut last_heads_up).await {
+                eprintln!("Outage heads-up error: {}", e);
+            }
         }
     });
 }
 
+/// Declares an outage when most power sources go silent, so one crashed device
+/// is not mistaken for a blackout. Ends outages the sensor board can't report on.
 async fn check_power_status(db: &Db, alerter: &Alerter) -> anyhow::Result<()> {
-    let last_sensor = db.get_last_sensor_time().await?;
+    let sources = db
+        .get_power_sources(power::SENSOR_SOURCE, power::SOURCE_EXPIRY_HOURS)
+        .await?;
     let active_outage = db.get_active_outage().await?;
 
-    let Some(last) = last_sensor else {
+    let now = OffsetDateTime::now_utc();
+    let silent: Vec<&PowerSource> = sources
+        .iter()
+        .filter(|s| {
+            (now - s.last_seen_at.assume_utc()).whole_seconds() > power::OUTAGE_THRESHOLD_SECS
+        })
+        .collect();
+    let Some(last_seen) = silent.iter().map(|s| s.last_seen_at).max() else {
         return Ok(());
     };
 
-    let now = OffsetDateTime::now_utc();
-    let last_utc = last.created_at.assume_utc();
-    let elapsed_secs = (now - last_utc).whole_seconds();
-
-    let is_power_down = elapsed_secs > power::OUTAGE_THRESHOLD_SECS;
+    let is_power_down = silent.len() * 2 > sources.len();
 
-    match (is_power_down, active_outage.is_some()) {
-        (true, false) => {
-            db.start_outage().await?;
-            let time_str = format_kyiv(last.created_at);
+    match (is_power_down, active_outage) {
+        (true, None) => {
+            db.start_outage(last_seen, OutageCause::Unknown, false)
+                .await?;
+            let time_str = format_kyiv(last_seen);
+            let scheduled = db
+                .get_current_scheduled_outage(schedule::MATCH_TOLERANCE_MINS)
+                .await?;
+            let latest = db.get_latest_sensor_data().await?;
+            let battery = latest.as_ref().and_then(|data| battery_status(data, None));
+            // Only worth naming the sources when there is more than the sensor board
+            let names = match sources.len() {
+                1 => None,
+                _ => Some(
+                    silent
+                        .iter()
+                        .map(|s| s.source.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ),
+            };
             alerter
-                .broadcast_power_alert(&format!("⚡ Power outage detected!\nLast data: {}", time_str))
+                .broadcast_power_alert(|t| {
+                    let sources = match &names {
+                        Some(names) => format!(
+                            "\n{}",
+                            t.render(
+                                TemplateId::PowerSourcesSilent,
+                                &[("sources", names.as_str().into())]
+                            )
+                        ),
+                        None => String::new(),
+                    };
+                    match &scheduled {
+                        Some(window) => t.render(
+                            TemplateId::PowerOutageDetectedScheduled,
+                            &[
+                                ("last_data", time_str.as_str().into()),
+                                ("end", format_kyiv_time(window.ends_at).into()),
+                                ("sources", Value::Fragment(sources)),
+                                ("battery", Value::Fragment(t.battery(battery.as_ref()))),
+                            ],
+                        ),
+                        None => t.render(
+                            TemplateId::PowerOutageDetected,
+                            &[
+                                ("last_data", time_str.as_str().into()),
+                                ("sources", Value::Fragment(sources)),
+                                ("battery", Value::Fragment(t.battery(battery.as_ref()))),
+                            ],
+                        ),
+                    }
+                })
                 .await?;
         }
-        (false, true) => {}
+        (false, Some(outage)) if outage.cause != OutageCause::Power => {
+            // Sensor readings end outages themselves; this covers a sensor board
+            // that stays down while the other sources are back
+            let resumed_at = sources
+                .iter()
+                .filter_map(|s| s.resumed_at)
+                .filter(|&resumed| resumed > outage.started_at)
+                .min();
+            if let Some(ended_at) = resumed_at {
+                finish_outage(db, alerter, ended_at, OutageCause::Unknown).await?;
+            }
+        }
         _ => {}
     }
 
     Ok(())
 }
 
-/// Call this when new sensor data arrives to check if power was restored
-pub async fn check_power_restored(db: &Db, alerter: &Alerter) -> anyhow::Result<()> {
+/// Warn once about a scheduled outage starting within the lead time
+async fn warn_scheduled_outage(db: &Db, alerter: &Alerter) -> anyhow::Result<()> {
+    let Some(window) = db.take_outage_warning(schedule::WARNING_LEAD_MINS).await? else {
+        return Ok(());
+    };
+
+    let now = OffsetDateTime::now_utc();
+    let minutes = (window.starts_at.assume_utc() - now).whole_minutes().max(1) as i32;
+    let start = format_kyiv_time(window.starts_at);
+    let end = format_kyiv_time(window.ends_at);
+
+    alerter
+        .broadcast_power_alert(|t| {
+            t.render(
+                TemplateId::PowerOutageExpected,
+                &[
+                    ("minutes", minutes.into()),
+                    ("start", start.as_str().into()),
+                    ("end", end.as_str().into()),
+                ],
+            )
+        })
+        .await
+}
+
+/// Heads-up before a likely outage in the coming hour, once per hour.
+/// `last_heads_up` remembers the hour already announced.
+async fn warn_predicted_outage(
+    db: &Db,
+    alerter: &Alerter,
+    last_heads_up: &mut Option<OffsetDateTime>,
+) -> anyhow::Result<()> {
+    if db.get_active_outage().await?.is_some() {
+        return Ok(());
+    }
+    let Some(forecast) = outage_outlook(db, 1).await? else {
+        return Ok(());
+    };
+    let Some(hour) = forecast.hours.first() else {
+        return Ok(());
+    };
+    if hour.probability < outlook::HEADS_UP_PROBABILITY || *last_heads_up == Some(hour.starts_at) {
+        return Ok(());
+    }
+
+    *last_heads_up = Some(hour.starts_at);
+    let hour_str = format!("{:02}:00", hour.starts_at.hour());
+    let probability = hour.probability * 100.0;
+
+    alerter
+        .broadcast_outlook_alert(|t| {
+            t.render(
+                TemplateId::OutlookHeadsUp,
+                &[
+                    ("hour", hour_str.as_str().into()),
+                    ("probability", probability.into()),
+                ],
+            )
+        })
+        .await
+}
+
+/// Call this when new sensor data arrives. A reading on battery starts an outage
+/// or tracks the battery through it; a reading on mains ends the active outage.
+pub async fn check_power_reading(
+    db: &Db,
+    alerter: &Alerter,
+    id: i32,
+    data: &SensorData,
+) -> anyhow::Result<()> {
     let active_outage = db.get_active_outage().await?;
 
-    if active_outage.is_some() {
-        // Power is back!
-        if let Some(duration) = db.end_outage().await? {
-            let now = now_kyiv();
-            let time_str = now
-                .format(&time::format_description::parse("[day].[month] [hour]:[minute]").unwrap())
-                .unwrap_or_else(|_| "??".to_string());
+    match (is_on_battery(data), active_outage) {
+        (true, None) => start_battery_outage(db, alerter, id, data).await,
+        (true, Some(outage)) => check_battery(db, alerter, &outage, data).await,
+        (false, Some(outage)) => end_outage(db, alerter, &outage, id).await,
+        (false, None) => Ok(()),
+    }
+}
 
-            let duration_str = crate::services::format_duration_minutes(duration);
+async fn start_battery_outage(
+    db: &Db,
+    alerter: &Alerter,
+    id: i32,
+    data: &SensorData,
+) -> anyhow::Result<()> {
+    let Some(started_at) = db.get_sensor_time(id).await? else {
+        return Ok(());
+    };
+    db.start_outage(started_at, OutageCause::Power, true)
+        .await?;
 
-            alerter
-                .broadcast_power_alert(&format!(
-                    "✅ Power restored at {}\nOutage duration: {}",
-                    time_str, duration_str
-                ))
-                .await?;
+    let since = format_kyiv(started_at);
+    let battery = battery_status(data, None);
+    alerter
+        .broadcast_power_alert(|t| {
+            t.render(
+                TemplateId::PowerOutageOnBattery,
+                &[
+                    ("since", since.as_str().into()),
+                    ("battery", Value::Fragment(t.battery(battery.as_ref()))),
+                ],
+            )
+        })
+        .await
+}
+
+/// Alert once per battery level crossed during the outage
+async fn check_battery(
+    db: &Db,
+    alerter: &Alerter,
+    outage: &PowerOutage,
+    data: &SensorData,
+) -> anyhow::Result<()> {
+    let Some(percent) = data.battery_percent else {
+        return Ok(());
+    };
+    let Some(level) = battery_alert_level(percent, outage.battery_alert_percent) else {
+        return Ok(());
+    };
+    db.set_battery_alert(outage.id, level).await?;
+
+    let discharge = db.get_battery_discharge(outage.started_at).await?;
+    let battery = battery_status(data, discharge.as_ref());
+    alerter
+        .broadcast_power_alert(|t| {
+            let state = t.battery(battery.as_ref());
+            t.render(
+                TemplateId::BatteryLow,
+                &[("state", Value::Fragment(state.trim_start().to_string()))],
+            )
+        })
+        .await
+}
+
+async fn end_outage(
+    db: &Db,
+    alerter: &Alerter,
+    outage: &PowerOutage,
+    id: i32,
+) -> anyhow::Result<()> {
+    // An outage seen on battery is known to be a power loss and ends with this reading;
+    // one seen as a gap is judged by the device's uptime
+    let finished = if outage.cause == OutageCause::Power {
+        let Some(ended_at) = db.get_sensor_time(id).await? else {
+            return Ok(());
+        };
+        FinishedOutage {
+            started_at: outage.started_at,
+            ended_at,
+            cause: OutageCause::Power,
         }
+    } else {
+        let Some(gap) = db.get_gap_after(outage.started_at).await? else {
+            return Ok(());
+        };
+        classify_gap(&gap)
+    };
+
+    finish_outage(db, alerter, finished.ended_at, finished.cause).await
+}
+
+async fn finish_outage(
+    db: &Db,
+    alerter: &Alerter,
+    ended_at: PrimitiveDateTime,
+    cause: OutageCause,
+) -> anyhow::Result<()> {
+    if let Some(outage) = db.end_outage(ended_at, cause).await? {
+        let time_str = outage.ended_at.map(format_kyiv).unwrap_or_default();
+        let duration = outage.duration_minutes.unwrap_or(0);
+        let id = match outage.cause {
+            OutageCause::Network => TemplateId::NetworkRestored,
+            OutageCause::Power | OutageCause::Unknown => TemplateId::PowerRestored,
+        };
+
+        alerter
+            .broadcast_power_alert(|t| {
+                t.render(
+                    id,
+                    &[
+                        ("time", time_str.as_str().into()),
+                        ("duration", Value::Fragment(t.duration(duration))),
+                    ],
+                )
+            })
+            .await?;
     }
 
     Ok(())
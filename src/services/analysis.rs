@@ -1,5 +1,6 @@
 use crate::config::{pressure, soil, water};
 use crate::db::SensorData;
+use crate::templates::TemplateId;
 
 #[derive(Debug, Clone, Copy)]
 pub enum Status {
@@ -32,53 +33,53 @@ impl Status {
 
 pub struct SoilAnalysis {
     pub status: Status,
-    pub message: &'static str,
+    pub message: TemplateId,
 }
 
 pub fn analyze_soil_moisture(value: f32) -> SoilAnalysis {
     if value < soil::VERY_DRY {
         SoilAnalysis {
             status: Status::Critical,
-            message: "Very dry - water now!",
+            message: TemplateId::SoilVeryDry,
         }
     } else if value < soil::DRY {
         SoilAnalysis {
             status: Status::Warning,
-            message: "Getting dry",
+            message: TemplateId::SoilGettingDry,
         }
     } else if value < soil::WET {
         SoilAnalysis {
             status: Status::Good,
-            message: "Good",
+            message: TemplateId::SoilGood,
         }
     } else {
         SoilAnalysis {
             status: Status::High,
-            message: "Very wet",
+            message: TemplateId::SoilVeryWet,
         }
     }
 }
 
 pub struct WaterAnalysis {
     pub status: Status,
-    pub message: &'static str,
+    pub message: TemplateId,
 }
 
 pub fn analyze_water_level(value: f32) -> WaterAnalysis {
     if value < water::LOW {
         WaterAnalysis {
             status: Status::Critical,
-            message: "Low - refill needed",
+            message: TemplateId::WaterLow,
         }
     } else if value < water::MEDIUM {
         WaterAnalysis {
             status: Status::Warning,
-            message: "Getting low",
+            message: TemplateId::WaterGettingLow,
         }
     } else {
         WaterAnalysis {
             status: Status::Good,
-            message: "OK",
+            message: TemplateId::WaterOk,
         }
     }
 }
@@ -103,20 +104,20 @@ impl PressureTrend {
         }
     }
 
-    pub fn label(&self) -> &'static str {
+    pub fn label(&self) -> TemplateId {
         match self {
-            PressureTrend::FallingFast => "falling fast",
-            PressureTrend::Falling => "falling",
-            PressureTrend::Stable => "stable",
-            PressureTrend::Rising => "rising",
-            PressureTrend::RisingFast => "rising fast",
+            PressureTrend::FallingFast => TemplateId::TrendFallingFast,
+            PressureTrend::Falling => TemplateId::TrendFalling,
+            PressureTrend::Stable => TemplateId::TrendStable,
+            PressureTrend::Rising => TemplateId::TrendRising,
+            PressureTrend::RisingFast => TemplateId::TrendRisingFast,
         }
     }
 }
 
 pub struct WeatherForecast {
     pub emoji: &'static str,
-    pub message: &'static str,
+    pub message: TemplateId,
 }
 
 pub struct PressureAnalysis {
@@ -143,22 +144,22 @@ pub fn analyze_pressure(current: f32, past: f32) -> PressureAnalysis {
     let forecast = if delta < pressure::STORM_THRESHOLD {
         WeatherForecast {
             emoji: "⛈",
-            message: "Storm likely",
+            message: TemplateId::ForecastStorm,
         }
     } else if delta < pressure::RAIN_THRESHOLD {
         WeatherForecast {
             emoji: "🌧",
-            message: "Rain possible",
+            message: TemplateId::ForecastRain,
         }
     } else if delta > pressure::CLEAR_THRESHOLD {
         WeatherForecast {
             emoji: "☀️",
-            message: "Clear weather",
+            message: TemplateId::ForecastClear,
         }
     } else {
         WeatherForecast {
             emoji: "🌤",
-            message: "No significant change",
+            message: TemplateId::ForecastNoChange,
         }
     };
 
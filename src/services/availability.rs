@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime, PrimitiveDateTime};
+
+use crate::db::{Db, PowerStats};
+use crate::templates::TemplateId;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerPeriod {
+    Day,
+    #[default]
+    Week,
+    Month,
+}
+
+impl PowerPeriod {
+    pub const ALL: &'static [PowerPeriod] =
+        &[PowerPeriod::Day, PowerPeriod::Week, PowerPeriod::Month];
+
+    pub fn days(self) -> i64 {
+        match self {
+            PowerPeriod::Day => 1,
+            PowerPeriod::Week => 7,
+            PowerPeriod::Month => 30,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PowerPeriod::Day => "day",
+            PowerPeriod::Week => "week",
+            PowerPeriod::Month => "month",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|p| p.name() == name)
+    }
+
+    pub fn label(self) -> TemplateId {
+        match self {
+            PowerPeriod::Day => TemplateId::PowerPeriodDay,
+            PowerPeriod::Week => TemplateId::PowerPeriodWeek,
+            PowerPeriod::Month => TemplateId::PowerPeriodMonth,
+        }
+    }
+
+    pub fn button(self) -> TemplateId {
+        match self {
+            PowerPeriod::Day => TemplateId::ButtonPeriodDay,
+            PowerPeriod::Week => TemplateId::ButtonPeriodWeek,
+            PowerPeriod::Month => TemplateId::ButtonPeriodMonth,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct PeriodAvailability {
+    pub uptime_percent: f32,
+    pub outage_count: i64,
+    pub total_outage_minutes: i64,
+    pub longest_outage_minutes: i64,
+}
+
+impl PeriodAvailability {
+    fn new(stats: PowerStats, period_minutes: i64) -> Self {
+        let downtime = stats.total_minutes.min(period_minutes) as f32;
+        Self {
+            uptime_percent: 100.0 * (1.0 - downtime / period_minutes as f32),
+            outage_count: stats.outage_count,
+            total_outage_minutes: stats.total_minutes,
+            longest_outage_minutes: stats.longest_minutes,
+        }
+    }
+}
+
+/// Availability over the last period, the one before it, and outage starts by Kyiv hour
+#[derive(Clone, Debug, Serialize)]
+pub struct PowerAvailability {
+    pub period: PowerPeriod,
+    pub current: PeriodAvailability,
+    pub previous: PeriodAvailability,
+    pub outages_by_hour: [i64; 24],
+}
+
+pub async fn power_availability(db: &Db, period: PowerPeriod) -> sqlx::Result<PowerAvailability> {
+    let now = OffsetDateTime::now_utc();
+    let now = PrimitiveDateTime::new(now.date(), now.time());
+    let length = Duration::days(period.days());
+    let start = now - length;
+    let previous_start = start - length;
+
+    let current = db.get_power_stats(start, now).await?;
+    let previous = db.get_power_stats(previous_start, start).await?;
+
+    let mut outages_by_hour = [0; 24];
+    for row in db.get_outage_start_hours(start, now).await? {
+        if let Some(count) = outages_by_hour.get_mut(row.hour as usize) {
+            *count = row.count;
+        }
+    }
+
+    Ok(PowerAvailability {
+        period,
+        current: PeriodAvailability::new(current, length.whole_minutes()),
+        previous: PeriodAvailability::new(previous, length.whole_minutes()),
+        outages_by_hour,
+    })
+}
@@ -0,0 +1,56 @@
+use crate::config::battery;
+use crate::db::{BatteryDischarge, Db, SensorData};
+
+pub struct BatteryStatus {
+    pub percent: f32,
+    /// Estimated minutes left, once the discharge rate is known
+    pub runtime_minutes: Option<i32>,
+}
+
+/// The UPS input drops when mains is gone and the board runs on battery
+pub fn is_on_battery(data: &SensorData) -> bool {
+    data.supply_voltage
+        .is_some_and(|v| v < battery::MAINS_MIN_VOLTAGE)
+}
+
+/// Linear estimate from the first and latest battery readings of the outage
+pub fn estimate_runtime(discharge: &BatteryDischarge) -> Option<i32> {
+    let minutes = (discharge.last_at - discharge.first_at).whole_minutes();
+    let used = discharge.first_percent - discharge.last_percent;
+    if minutes < battery::MIN_ESTIMATE_MINUTES || used <= 0.0 {
+        return None;
+    }
+
+    let rate = used / minutes as f32;
+    Some((discharge.last_percent / rate) as i32)
+}
+
+/// The lowest alert level at or above `percent` that is below the last one alerted
+pub fn battery_alert_level(percent: f32, last_alerted: Option<f32>) -> Option<f32> {
+    battery::ALERT_LEVELS
+        .iter()
+        .copied()
+        .filter(|&level| percent <= level)
+        .filter(|&level| last_alerted.is_none_or(|last| level < last))
+        .reduce(f32::min)
+}
+
+/// Battery level from a reading, with a runtime estimate while discharging
+pub fn battery_status(
+    data: &SensorData,
+    discharge: Option<&BatteryDischarge>,
+) -> Option<BatteryStatus> {
+    Some(BatteryStatus {
+        percent: data.battery_percent?,
+        runtime_minutes: discharge.and_then(estimate_runtime),
+    })
+}
+
+/// Battery status for a reading, estimating runtime from the active outage's discharge
+pub async fn current_battery(db: &Db, data: &SensorData) -> sqlx::Result<Option<BatteryStatus>> {
+    let discharge = match db.get_active_outage().await? {
+        Some(outage) if is_on_battery(data) => db.get_battery_discharge(outage.started_at).await?,
+        _ => None,
+    };
+    Ok(battery_status(data, discharge.as_ref()))
+}
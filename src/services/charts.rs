@@ -0,0 +1,243 @@
+use std::sync::Once;
+
+use anyhow::bail;
+use image::{codecs::png::PngEncoder, ColorType, ImageEncoder};
+use plotters::prelude::*;
+use plotters::style::{register_font, FontStyle};
+use time::{Duration, OffsetDateTime, PrimitiveDateTime};
+use time_tz::{timezones::db::europe::KYIV, OffsetDateTimeExt};
+
+use crate::config::charts;
+use crate::db::{Db, SeriesPoint};
+use crate::services::Units;
+use crate::templates::TemplateId;
+
+const FONT: &str = "sans-serif";
+static FONT_BYTES: &[u8] = include_bytes!("../../assets/fonts/DejaVuSans.ttf");
+static REGISTER_FONT: Once = Once::new();
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChartMetric {
+    Temperature,
+    Humidity,
+    Pressure,
+    SoilMoisture,
+    WaterLevel,
+}
+
+impl ChartMetric {
+    pub const ALL: &'static [ChartMetric] = &[
+        ChartMetric::Temperature,
+        ChartMetric::Humidity,
+        ChartMetric::Pressure,
+        ChartMetric::SoilMoisture,
+        ChartMetric::WaterLevel,
+    ];
+
+    /// Also the `sensor_data` column it is drawn from
+    pub fn name(self) -> &'static str {
+        match self {
+            ChartMetric::Temperature => "temperature",
+            ChartMetric::Humidity => "humidity",
+            ChartMetric::Pressure => "pressure",
+            ChartMetric::SoilMoisture => "soil_moisture",
+            ChartMetric::WaterLevel => "water_level",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|m| m.name() == name)
+    }
+
+    pub fn label(self) -> TemplateId {
+        match self {
+            ChartMetric::Temperature => TemplateId::ChartTemperature,
+            ChartMetric::Humidity => TemplateId::ChartHumidity,
+            ChartMetric::Pressure => TemplateId::ChartPressure,
+            ChartMetric::SoilMoisture => TemplateId::ChartSoilMoisture,
+            ChartMetric::WaterLevel => TemplateId::ChartWaterLevel,
+        }
+    }
+
+    /// Unit template for the axis, `None` for percentages
+    pub fn unit(self, units: Units) -> Option<TemplateId> {
+        match self {
+            ChartMetric::Temperature => Some(units.temperature.template()),
+            ChartMetric::Pressure => Some(units.pressure.template()),
+            ChartMetric::Humidity | ChartMetric::SoilMoisture | ChartMetric::WaterLevel => None,
+        }
+    }
+
+    /// Converts a stored value into the user's units
+    pub fn convert(self, units: Units, value: f32) -> f32 {
+        match self {
+            ChartMetric::Temperature => units.temperature.convert(value),
+            ChartMetric::Pressure => units.pressure.convert(value),
+            ChartMetric::Humidity | ChartMetric::SoilMoisture | ChartMetric::WaterLevel => value,
+        }
+    }
+}
+
+/// Everything drawn on a chart, in UTC like the tables
+pub struct ChartData {
+    pub from: PrimitiveDateTime,
+    pub to: PrimitiveDateTime,
+    pub bucket: Duration,
+    pub points: Vec<SeriesPoint>,
+    pub waterings: Vec<PrimitiveDateTime>,
+    pub outages: Vec<(PrimitiveDateTime, PrimitiveDateTime)>,
+}
+
+/// Chart of the metric over the last `span`
+pub async fn chart_data(db: &Db, metric: ChartMetric, span: Duration) -> sqlx::Result<ChartData> {
+    let now = OffsetDateTime::now_utc();
+    let to = PrimitiveDateTime::new(now.date(), now.time());
+    let from = to - span;
+    let bucket = span / charts::POINTS as i32;
+
+    let points = db
+        .get_sensor_series(metric.name(), from, bucket.whole_seconds())
+        .await?;
+    let waterings = db.get_watering_times_since(from).await?;
+    let outages = db
+        .get_power_outages_since(from)
+        .await?
+        .into_iter()
+        .map(|o| (o.started_at.max(from), o.ended_at.unwrap_or(to)))
+        .collect();
+
+    Ok(ChartData {
+        from,
+        to,
+        bucket,
+        points,
+        waterings,
+        outages,
+    })
+}
+
+/// Localized text drawn on the chart
+pub struct ChartLabels {
+    pub title: String,
+    pub watering: String,
+    pub outage: String,
+}
+
+/// Renders a line chart as PNG, with outages shaded and waterings as vertical lines.
+/// `convert` maps stored values into display units.
+pub fn render_chart(
+    data: &ChartData,
+    labels: &ChartLabels,
+    convert: impl Fn(f32) -> f32,
+) -> anyhow::Result<Vec<u8>> {
+    REGISTER_FONT.call_once(|| {
+        if register_font(FONT, FontStyle::Normal, FONT_BYTES).is_err() {
+            eprintln!("Failed to register chart font");
+        }
+    });
+
+    let (width, height) = (charts::WIDTH, charts::HEIGHT);
+    let x = |dt: PrimitiveDateTime| dt.assume_utc().unix_timestamp();
+    let values: Vec<(i64, f32)> = data
+        .points
+        .iter()
+        .map(|p| (x(p.at), convert(p.value)))
+        .collect();
+    if values.is_empty() {
+        bail!("No readings to chart");
+    }
+
+    let min = values.iter().map(|v| v.1).fold(f32::INFINITY, f32::min);
+    let max = values.iter().map(|v| v.1).fold(f32::NEG_INFINITY, f32::max);
+    let padding = ((max - min) * 0.1).max(0.5);
+    let (y_min, y_max) = (min - padding, max + padding);
+    let daily_labels = data.to - data.from <= Duration::DAY;
+
+    let mut buffer = vec![0u8; (width * height * 3) as usize];
+    {
+        let root = BitMapBackend::with_buffer(&mut buffer, (width, height)).into_drawing_area();
+        root.fill(&WHITE)?;
+
+        let mut chart = ChartBuilder::on(&root)
+            .caption(&labels.title, (FONT, 26))
+            .margin(16)
+            .x_label_area_size(32)
+            .y_label_area_size(56)
+            .build_cartesian_2d(x(data.from)..x(data.to), y_min..y_max)?;
+
+        chart
+            .configure_mesh()
+            .x_labels(8)
+            .x_label_formatter(&|&ts| format_axis_time(ts, daily_labels))
+            .y_label_formatter(&|v| format!("{:.1}", v))
+            .label_style((FONT, 14))
+            .light_line_style(RGBColor(235, 235, 235))
+            .draw()?;
+
+        let outage_color = RGBColor(230, 80, 60).mix(0.2);
+        if !data.outages.is_empty() {
+            chart
+                .draw_series(data.outages.iter().map(|&(start, end)| {
+                    Rectangle::new([(x(start), y_min), (x(end), y_max)], outage_color.filled())
+                }))?
+                .label(&labels.outage)
+                .legend(move |(lx, ly)| {
+                    Rectangle::new([(lx, ly - 5), (lx + 16, ly + 5)], outage_color.filled())
+                });
+        }
+
+        let watering_color = RGBColor(40, 150, 220);
+        if !data.waterings.is_empty() {
+            chart
+                .draw_series(data.waterings.iter().map(|&at| {
+                    PathElement::new(
+                        vec![(x(at), y_min), (x(at), y_max)],
+                        watering_color.stroke_width(2),
+                    )
+                }))?
+                .label(&labels.watering)
+                .legend(move |(lx, ly)| {
+                    PathElement::new(
+                        vec![(lx, ly), (lx + 16, ly)],
+                        watering_color.stroke_width(2),
+                    )
+                });
+        }
+
+        // Break the line where readings stop rather than bridging the gap
+        let max_step = 2 * data.bucket.whole_seconds();
+        let line_style = RGBColor(30, 110, 60).stroke_width(2);
+        for segment in values.chunk_by(|a, b| b.0 - a.0 <= max_step) {
+            chart.draw_series(LineSeries::new(segment.iter().copied(), line_style))?;
+        }
+
+        if !data.outages.is_empty() || !data.waterings.is_empty() {
+            chart
+                .configure_series_labels()
+                .position(SeriesLabelPosition::UpperLeft)
+                .label_font((FONT, 14))
+                .background_style(WHITE.mix(0.8))
+                .border_style(RGBColor(200, 200, 200))
+                .draw()?;
+        }
+
+        root.present()?;
+    }
+
+    let mut png = Vec::new();
+    PngEncoder::new(&mut png).write_image(&buffer, width, height, ColorType::Rgb8)?;
+    Ok(png)
+}
+
+/// Kyiv time for the last day, dates for longer ranges
+fn format_axis_time(ts: i64, daily: bool) -> String {
+    let Ok(dt) = OffsetDateTime::from_unix_timestamp(ts) else {
+        return String::new();
+    };
+    let kyiv = dt.to_timezone(KYIV);
+    if daily {
+        format!("{:02}:{:02}", kyiv.hour(), kyiv.minute())
+    } else {
+        format!("{:02}.{:02}", kyiv.day(), kyiv.month() as u8)
+    }
+}
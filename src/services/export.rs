@@ -0,0 +1,26 @@
+use std::fmt::Write;
+
+use time::macros::format_description;
+
+use crate::db::SensorRecord;
+
+/// Readings as CSV in stored units, timestamps in UTC
+pub fn sensor_csv(records: &[SensorRecord]) -> String {
+    let format = format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+    let mut csv = String::from(
+        "time_utc,temperature_c,humidity_percent,pressure_hpa,soil_moisture_percent,water_level_percent\n",
+    );
+    for record in records {
+        let _ = writeln!(
+            csv,
+            "{},{:.2},{:.2},{:.2},{:.2},{:.2}",
+            record.created_at.format(&format).unwrap_or_default(),
+            record.temperature,
+            record.humidity,
+            record.pressure,
+            record.soil_moisture,
+            record.water_level,
+        );
+    }
+    csv
+}
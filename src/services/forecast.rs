@@ -0,0 +1,99 @@
+use std::collections::HashSet;
+
+use time::{Date, Duration, OffsetDateTime, PrimitiveDateTime, Time};
+use time_tz::{timezones::db::europe::KYIV, OffsetDateTimeExt};
+
+use crate::config::outlook;
+use crate::db::Db;
+
+/// A Kyiv calendar hour, the unit outage patterns are counted in
+type HourSlot = (Date, u8);
+
+pub struct HourOutlook {
+    /// Start of the hour (Kyiv time)
+    pub starts_at: OffsetDateTime,
+    /// Chance of being without power at some point in this hour (0..1)
+    pub probability: f32,
+}
+
+pub struct Outlook {
+    pub hours: Vec<HourOutlook>,
+    /// Chance of at least one outage over all of `hours` (0..1)
+    pub overall: f32,
+}
+
+/// Predicts outages for the coming hours from our own outage history: how often
+/// the same hour was hit on recent days (the daily cadence of rolling blackouts)
+/// and on the same weekday in earlier weeks. `None` until there is a day of history.
+pub async fn outage_outlook(db: &Db, hour_count: i64) -> sqlx::Result<Option<Outlook>> {
+    let now = OffsetDateTime::now_utc();
+    let Some(first) = db.get_first_sensor_time().await? else {
+        return Ok(None);
+    };
+    let history_days = (now - first.assume_utc()).whole_days();
+    if history_days < 1 {
+        return Ok(None);
+    }
+
+    let lookback = Duration::weeks(outlook::HISTORY_WEEKS);
+    let outages = db
+        .get_power_outages_since(to_primitive(now - lookback))
+        .await?;
+
+    let mut outage_hours = HashSet::new();
+    for outage in &outages {
+        let started = outage.started_at.assume_utc();
+        let ended = outage.ended_at.map(|e| e.assume_utc()).unwrap_or(now);
+        let mut hour = truncate_to_hour(started);
+        while hour < ended {
+            outage_hours.insert(slot(hour));
+            hour += Duration::HOUR;
+        }
+    }
+
+    let days = history_days.min(outlook::CADENCE_DAYS);
+    let weeks = (history_days / 7).min(outlook::HISTORY_WEEKS);
+
+    let first_hour = truncate_to_hour(now) + Duration::HOUR;
+    let hours: Vec<HourOutlook> = (0..hour_count)
+        .map(|i| {
+            let starts_at = first_hour + Duration::hours(i);
+            let (date, hour) = slot(starts_at);
+            let hit_rate = |step: i64, samples: i64| {
+                let hits = (1..=samples)
+                    .filter(|k| outage_hours.contains(&(date - Duration::days(step * k), hour)))
+                    .count();
+                hits as f32 / samples.max(1) as f32
+            };
+
+            let daily = hit_rate(1, days);
+            let probability = if weeks > 0 {
+                outlook::CADENCE_WEIGHT * daily
+                    + (1.0 - outlook::CADENCE_WEIGHT) * hit_rate(7, weeks)
+            } else {
+                daily
+            };
+
+            HourOutlook {
+                starts_at: starts_at.to_timezone(KYIV),
+                probability,
+            }
+        })
+        .collect();
+
+    let overall = 1.0 - hours.iter().map(|h| 1.0 - h.probability).product::<f32>();
+    Ok(Some(Outlook { hours, overall }))
+}
+
+fn slot(dt: OffsetDateTime) -> HourSlot {
+    let kyiv = dt.to_timezone(KYIV);
+    (kyiv.date(), kyiv.hour())
+}
+
+fn truncate_to_hour(dt: OffsetDateTime) -> OffsetDateTime {
+    dt.replace_time(Time::from_hms(dt.hour(), 0, 0).unwrap_or(Time::MIDNIGHT))
+}
+
+fn to_primitive(dt: OffsetDateTime) -> PrimitiveDateTime {
+    PrimitiveDateTime::new(dt.date(), dt.time())
+}
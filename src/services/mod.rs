@@ -1,5 +1,25 @@
 pub mod analysis;
+pub mod availability;
+pub mod battery;
+pub mod charts;
+pub mod export;
+pub mod forecast;
+pub mod outages;
+pub mod readings;
+pub mod schedule;
 pub mod timezone;
+pub mod units;
+pub mod weekly;
 
 pub use analysis::*;
+pub use availability::*;
+pub use battery::*;
+pub use charts::*;
+pub use export::*;
+pub use forecast::*;
+pub use outages::*;
+pub use readings::*;
+pub use schedule::*;
 pub use timezone::*;
+pub use units::*;
+pub use weekly::*;
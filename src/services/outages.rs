@@ -0,0 +1,83 @@
+use time::{Duration, PrimitiveDateTime};
+
+use crate::db::{OutageCause, SensorGap};
+
+pub struct FinishedOutage {
+    pub started_at: PrimitiveDateTime,
+    pub ended_at: PrimitiveDateTime,
+    pub cause: OutageCause,
+}
+
+/// Classifies a gap by whether the device rebooted during it. After a reboot,
+/// power came back at boot time rather than at the first reading.
+pub fn classify_gap(gap: &SensorGap) -> FinishedOutage {
+    let gap_secs = (gap.ended_at - gap.started_at).whole_seconds();
+    let boot_changed = matches!(
+        (gap.boot_count, gap.previous_boot_count),
+        (Some(now), Some(before)) if now != before
+    );
+
+    let (cause, ended_at) = match gap.uptime_secs {
+        Some(uptime) if boot_changed || uptime < gap_secs => {
+            let booted_at = gap.ended_at - Duration::seconds(uptime);
+            (OutageCause::Power, booted_at.max(gap.started_at))
+        }
+        _ if boot_changed => (OutageCause::Power, gap.ended_at),
+        Some(_) => (OutageCause::Network, gap.ended_at),
+        None => (OutageCause::Unknown, gap.ended_at),
+    };
+
+    FinishedOutage {
+        started_at: gap.started_at,
+        ended_at,
+        cause,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    fn gap(uptime_secs: Option<i64>, boot_count: Option<i64>) -> SensorGap {
+        SensorGap {
+            started_at: datetime!(2025-12-30 10:00),
+            ended_at: datetime!(2025-12-30 12:00),
+            uptime_secs,
+            boot_count,
+            previous_boot_count: Some(7),
+        }
+    }
+
+    #[test]
+    fn reboot_ends_outage_at_boot_time() {
+        let outage = classify_gap(&gap(Some(600), Some(7)));
+        assert_eq!(outage.cause, OutageCause::Power);
+        assert_eq!(outage.ended_at, datetime!(2025-12-30 11:50));
+    }
+
+    #[test]
+    fn boot_counter_change_means_power() {
+        let outage = classify_gap(&gap(Some(3 * 3600), Some(8)));
+        assert_eq!(outage.cause, OutageCause::Power);
+        assert_eq!(outage.ended_at, datetime!(2025-12-30 10:00));
+
+        let outage = classify_gap(&gap(None, Some(8)));
+        assert_eq!(outage.cause, OutageCause::Power);
+        assert_eq!(outage.ended_at, datetime!(2025-12-30 12:00));
+    }
+
+    #[test]
+    fn uptime_through_gap_means_network() {
+        let outage = classify_gap(&gap(Some(3 * 3600), Some(7)));
+        assert_eq!(outage.cause, OutageCause::Network);
+        assert_eq!(outage.ended_at, datetime!(2025-12-30 12:00));
+    }
+
+    #[test]
+    fn no_uptime_is_unknown() {
+        let outage = classify_gap(&gap(None, None));
+        assert_eq!(outage.cause, OutageCause::Unknown);
+        assert_eq!(outage.started_at, datetime!(2025-12-30 10:00));
+    }
+}
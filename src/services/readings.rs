@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime, PrimitiveDateTime, UtcOffset};
+
+use crate::config::readings;
+use crate::db::{Db, MetricSummary, ReadingBucket};
+use crate::services::ChartMetric;
+
+/// Bucket widths the readings API aggregates over
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BucketSize {
+    #[serde(rename = "5m")]
+    FiveMinutes,
+    #[default]
+    #[serde(rename = "1h")]
+    Hour,
+    #[serde(rename = "1d")]
+    Day,
+}
+
+impl BucketSize {
+    pub fn secs(self) -> i64 {
+        match self {
+            BucketSize::FiveMinutes => 5 * 60,
+            BucketSize::Hour => 3600,
+            BucketSize::Day => 24 * 3600,
+        }
+    }
+}
+
+/// What to aggregate: `[from, to)` in UTC, the metrics to include and the page size
+pub struct ReadingsRequest {
+    pub from: PrimitiveDateTime,
+    pub to: PrimitiveDateTime,
+    pub bucket: BucketSize,
+    pub metrics: Vec<ChartMetric>,
+    pub limit: i64,
+}
+
+#[derive(Serialize)]
+pub struct BucketSummary {
+    /// RFC 3339, UTC
+    pub start: String,
+    pub count: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<MetricSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub humidity: Option<MetricSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pressure: Option<MetricSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub soil_moisture: Option<MetricSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub water_level: Option<MetricSummary>,
+}
+
+/// One page of buckets; `next` is the `from` of the following page, if any
+#[derive(Serialize)]
+pub struct ReadingsPage {
+    pub bucket: BucketSize,
+    pub buckets: Vec<BucketSummary>,
+    pub next: Option<String>,
+}
+
+/// Parses an RFC 3339 time or Unix seconds as naive UTC, limited to years
+/// the database and bucket arithmetic handle
+pub fn parse_api_time(text: &str) -> Option<PrimitiveDateTime> {
+    let time = match text.parse::<i64>() {
+        Ok(secs) => OffsetDateTime::from_unix_timestamp(secs).ok()?,
+        Err(_) => OffsetDateTime::parse(text, &Rfc3339).ok()?,
+    };
+    let utc = time.checked_to_offset(UtcOffset::UTC)?;
+    readings::YEARS
+        .contains(&utc.year())
+        .then(|| PrimitiveDateTime::new(utc.date(), utc.time()))
+}
+
+pub async fn readings_page(db: &Db, request: &ReadingsRequest) -> sqlx::Result<ReadingsPage> {
+    // One extra bucket tells whether there is another page
+    let mut buckets = db
+        .get_reading_buckets(
+            request.from,
+            request.to,
+            request.bucket.secs(),
+            request.limit + 1,
+        )
+        .await?;
+    let next = if buckets.len() as i64 > request.limit {
+        buckets.pop().map(|b| format_api_time(b.start))
+    } else {
+        None
+    };
+
+    Ok(ReadingsPage {
+        bucket: request.bucket,
+        buckets: buckets
+            .into_iter()
+            .map(|bucket| summarize(bucket, &request.metrics))
+            .collect(),
+        next,
+    })
+}
+
+fn summarize(bucket: ReadingBucket, metrics: &[ChartMetric]) -> BucketSummary {
+    let pick = |metric, summary| metrics.contains(&metric).then_some(summary);
+    BucketSummary {
+        start: format_api_time(bucket.start),
+        count: bucket.count,
+        temperature: pick(ChartMetric::Temperature, bucket.temperature),
+        humidity: pick(ChartMetric::Humidity, bucket.humidity),
+        pressure: pick(ChartMetric::Pressure, bucket.pressure),
+        soil_moisture: pick(ChartMetric::SoilMoisture, bucket.soil_moisture),
+        water_level: pick(ChartMetric::WaterLevel, bucket.water_level),
+    }
+}
+
+fn format_api_time(time: PrimitiveDateTime) -> String {
+    time.assume_utc().format(&Rfc3339).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    #[test]
+    fn api_times() {
+        assert_eq!(
+            parse_api_time("2025-12-30T16:00:00+02:00"),
+            Some(datetime!(2025-12-30 14:00))
+        );
+        assert_eq!(parse_api_time("0"), Some(datetime!(1970-01-01 00:00)));
+        assert_eq!(
+            parse_api_time("1767103200"),
+            Some(datetime!(2025-12-30 14:00))
+        );
+        assert_eq!(parse_api_time("-1"), None);
+        assert_eq!(parse_api_time("9999-12-31T23:59:59-02:00"), None);
+        assert_eq!(parse_api_time(&i64::MAX.to_string()), None);
+        assert_eq!(parse_api_time("yesterday"), None);
+    }
+}
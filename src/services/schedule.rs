@@ -0,0 +1,230 @@
+use anyhow::{bail, Context};
+use serde::Deserialize;
+use time::{
+    format_description::well_known::Rfc3339, macros::format_description, OffsetDateTime,
+    PrimitiveDateTime, UtcOffset,
+};
+use time_tz::{timezones, timezones::db::europe::KYIV, PrimitiveDateTimeExt};
+
+/// A published outage window, stored in UTC like the rest of the tables
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OutageWindow {
+    pub starts_at: PrimitiveDateTime,
+    pub ends_at: PrimitiveDateTime,
+}
+
+#[derive(Deserialize)]
+struct JsonWindow {
+    start: String,
+    end: String,
+}
+
+/// Reads a schedule from a local file or an http(s) URL
+pub async fn fetch_schedule(source: &str) -> anyhow::Result<Vec<OutageWindow>> {
+    let content = if source.starts_with("http://") || source.starts_with("https://") {
+        reqwest::get(source)
+            .await?
+            .error_for_status()?
+            .text()
+            .await?
+    } else {
+        tokio::fs::read_to_string(source).await?
+    };
+
+    parse_schedule(&content)
+}
+
+/// Parses an iCalendar feed, or a JSON array of `{"start": ..., "end": ...}` RFC 3339 times
+pub fn parse_schedule(content: &str) -> anyhow::Result<Vec<OutageWindow>> {
+    let windows = if content.trim_start().starts_with("BEGIN:VCALENDAR") {
+        parse_ics(content)?
+    } else {
+        parse_json(content)?
+    };
+
+    if let Some(w) = windows.iter().find(|w| w.ends_at <= w.starts_at) {
+        bail!(
+            "Outage window ends before it starts: {} - {}",
+            w.starts_at,
+            w.ends_at
+        );
+    }
+
+    Ok(windows)
+}
+
+fn parse_json(content: &str) -> anyhow::Result<Vec<OutageWindow>> {
+    let windows: Vec<JsonWindow> = serde_json::from_str(content)?;
+
+    windows
+        .iter()
+        .map(|w| {
+            Ok(OutageWindow {
+                starts_at: parse_rfc3339(&w.start)?,
+                ends_at: parse_rfc3339(&w.end)?,
+            })
+        })
+        .collect()
+}
+
+fn parse_rfc3339(value: &str) -> anyhow::Result<PrimitiveDateTime> {
+    let dt = OffsetDateTime::parse(value, &Rfc3339)
+        .with_context(|| format!("Invalid time: {}", value))?;
+    Ok(to_utc(dt))
+}
+
+fn parse_ics(content: &str) -> anyhow::Result<Vec<OutageWindow>> {
+    // Long lines are folded onto continuation lines starting with whitespace
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut windows = Vec::new();
+    let mut in_event = false;
+    let mut start = None;
+    let mut end = None;
+
+    for line in &lines {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (name, params) = key.split_once(';').unwrap_or((key, ""));
+
+        match name {
+            "BEGIN" if value == "VEVENT" => {
+                in_event = true;
+                start = None;
+                end = None;
+            }
+            "END" if value == "VEVENT" => {
+                in_event = false;
+                match (start.take(), end.take()) {
+                    (Some(starts_at), Some(ends_at)) => {
+                        windows.push(OutageWindow { starts_at, ends_at })
+                    }
+                    _ => bail!("Event without DTSTART or DTEND"),
+                }
+            }
+            "DTSTART" if in_event => start = Some(parse_ics_time(params, value)?),
+            "DTEND" if in_event => end = Some(parse_ics_time(params, value)?),
+            _ => {}
+        }
+    }
+
+    Ok(windows)
+}
+
+/// `20251230T140000Z` is UTC; local times use their TZID, or Kyiv when floating
+fn parse_ics_time(params: &str, value: &str) -> anyhow::Result<PrimitiveDateTime> {
+    let format = format_description!("[year][month][day]T[hour][minute][second]");
+
+    if let Some(utc) = value.strip_suffix('Z') {
+        return PrimitiveDateTime::parse(utc, format)
+            .with_context(|| format!("Invalid time: {}", value));
+    }
+
+    let local = PrimitiveDateTime::parse(value, format)
+        .with_context(|| format!("Invalid time: {}", value))?;
+    let tz = match params.split(';').find_map(|p| p.strip_prefix("TZID=")) {
+        Some(name) => {
+            timezones::get_by_name(name).with_context(|| format!("Unknown time zone: {}", name))?
+        }
+        None => KYIV,
+    };
+    let dt = local
+        .assume_timezone(tz)
+        .take_first()
+        .with_context(|| format!("Nonexistent local time: {}", value))?;
+
+    Ok(to_utc(dt))
+}
+
+fn to_utc(dt: OffsetDateTime) -> PrimitiveDateTime {
+    let utc = dt.to_offset(UtcOffset::UTC);
+    PrimitiveDateTime::new(utc.date(), utc.time())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    #[test]
+    fn json_windows() {
+        let windows = parse_schedule(
+            r#"[{"start": "2025-12-30T16:00:00+02:00", "end": "2025-12-30T20:00:00+02:00"}]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            windows,
+            vec![OutageWindow {
+                starts_at: datetime!(2025-12-30 14:00),
+                ends_at: datetime!(2025-12-30 18:00),
+            }]
+        );
+    }
+
+    #[test]
+    fn ics_windows() {
+        let windows = parse_schedule(
+            "BEGIN:VCALENDAR\r\n\
+             BEGIN:VEVENT\r\n\
+             DTSTART:20251230T140000Z\r\n\
+             DTEND:20251230T180000Z\r\n\
+             END:VEVENT\r\n\
+             BEGIN:VEVENT\r\n\
+             DTSTART;TZID=Europe/Kyiv:20251231T080000\r\n\
+             DTEND;TZID=Europe/Kyiv:2025\r\n 1231T120000\r\n\
+             END:VEVENT\r\n\
+             BEGIN:VEVENT\r\n\
+             DTSTART:20260701T080000\r\n\
+             DTEND:20260701T100000\r\n\
+             END:VEVENT\r\n\
+             END:VCALENDAR\r\n",
+        )
+        .unwrap();
+        assert_eq!(
+            windows,
+            vec![
+                OutageWindow {
+                    starts_at: datetime!(2025-12-30 14:00),
+                    ends_at: datetime!(2025-12-30 18:00),
+                },
+                OutageWindow {
+                    starts_at: datetime!(2025-12-31 06:00),
+                    ends_at: datetime!(2025-12-31 10:00),
+                },
+                OutageWindow {
+                    starts_at: datetime!(2026-07-01 05:00),
+                    ends_at: datetime!(2026-07-01 07:00),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_bad_schedules() {
+        assert!(parse_schedule(
+            r#"[{"start": "2025-12-30T20:00:00Z", "end": "2025-12-30T16:00:00Z"}]"#
+        )
+        .is_err());
+        assert!(
+            parse_schedule(r#"[{"start": "tomorrow", "end": "2025-12-30T16:00:00Z"}]"#).is_err()
+        );
+        assert!(parse_schedule("not a schedule").is_err());
+        assert!(parse_schedule(
+            "BEGIN:VCALENDAR\nBEGIN:VEVENT\nDTSTART:20251230T140000Z\nEND:VEVENT\nEND:VCALENDAR\n"
+        )
+        .is_err());
+        assert!(parse_schedule(
+            "BEGIN:VCALENDAR\nBEGIN:VEVENT\n\
+             DTSTART;TZID=Mars/Olympus:20251230T140000\nDTEND:20251230T180000Z\n\
+             END:VEVENT\nEND:VCALENDAR\n"
+        )
+        .is_err());
+    }
+}
@@ -1,29 +1,37 @@
 use time::OffsetDateTime;
-use time_tz::{timezones::db::europe::KYIV, OffsetDateTimeExt};
+use time_tz::{timezones, timezones::db::europe::KYIV, OffsetDateTimeExt, Tz};
 
+#[allow(dead_code)]
 pub fn now_kyiv() -> OffsetDateTime {
     OffsetDateTime::now_utc().to_timezone(KYIV)
 }
 
+/// A user's time zone by its IANA name, Kyiv when the name is unknown
+pub fn user_timezone(name: &str) -> &'static Tz {
+    timezones::get_by_name(name).unwrap_or(KYIV)
+}
+
 pub fn format_kyiv(dt: time::PrimitiveDateTime) -> String {
-    let utc = dt.assume_utc();
-    let kyiv = utc.to_timezone(KYIV);
-    kyiv.format(
-        &time::format_description::parse("[day].[month] [hour]:[minute]").unwrap()
-    )
-    .unwrap_or_else(|_| "??".to_string())
+    format_in(dt, KYIV)
+}
+
+/// Time of day only, for windows whose date is already shown
+pub fn format_kyiv_time(dt: time::PrimitiveDateTime) -> String {
+    format_time_in(dt, KYIV)
+}
+
+/// Date and time of a naive UTC timestamp in `tz`
+pub fn format_in(dt: time::PrimitiveDateTime, tz: &Tz) -> String {
+    let local = dt.assume_utc().to_timezone(tz);
+    local
+        .format(&time::format_description::parse("[day].[month] [hour]:[minute]").unwrap())
+        .unwrap_or_else(|_| "??".to_string())
 }
 
-pub fn format_duration_minutes(minutes: i32) -> String {
-    if minutes < 60 {
-        format!("{} min", minutes)
-    } else {
-        let hours = minutes / 60;
-        let mins = minutes % 60;
-        if mins == 0 {
-            format!("{} h", hours)
-        } else {
-            format!("{} h {} min", hours, mins)
-        }
-    }
+/// Time of day only of a naive UTC timestamp in `tz`
+pub fn format_time_in(dt: time::PrimitiveDateTime, tz: &Tz) -> String {
+    let local = dt.assume_utc().to_timezone(tz);
+    local
+        .format(&time::format_description::parse("[hour]:[minute]").unwrap())
+        .unwrap_or_else(|_| "??".to_string())
 }
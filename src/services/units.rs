@@ -0,0 +1,122 @@
+use crate::templates::TemplateId;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+}
+
+impl TemperatureUnit {
+    pub fn convert(self, celsius: f32) -> f32 {
+        match self {
+            TemperatureUnit::Celsius => celsius,
+            TemperatureUnit::Fahrenheit => celsius * 9.0 / 5.0 + 32.0,
+        }
+    }
+
+    pub fn precision(self) -> usize {
+        1
+    }
+
+    pub fn template(self) -> TemplateId {
+        match self {
+            TemperatureUnit::Celsius => TemplateId::UnitCelsius,
+            TemperatureUnit::Fahrenheit => TemplateId::UnitFahrenheit,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            TemperatureUnit::Celsius => TemperatureUnit::Fahrenheit,
+            TemperatureUnit::Fahrenheit => TemperatureUnit::Celsius,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum PressureUnit {
+    #[default]
+    Hpa,
+    MmHg,
+    InHg,
+}
+
+impl PressureUnit {
+    /// Pressure is linear, so the same factor converts values and deltas
+    pub fn convert(self, hpa: f32) -> f32 {
+        match self {
+            PressureUnit::Hpa => hpa,
+            PressureUnit::MmHg => hpa * 0.750_062,
+            PressureUnit::InHg => hpa * 0.029_53,
+        }
+    }
+
+    pub fn precision(self) -> usize {
+        match self {
+            PressureUnit::Hpa | PressureUnit::MmHg => 1,
+            PressureUnit::InHg => 2,
+        }
+    }
+
+    pub fn template(self) -> TemplateId {
+        match self {
+            PressureUnit::Hpa => TemplateId::UnitHpa,
+            PressureUnit::MmHg => TemplateId::UnitMmHg,
+            PressureUnit::InHg => TemplateId::UnitInHg,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            PressureUnit::Hpa => PressureUnit::MmHg,
+            PressureUnit::MmHg => PressureUnit::InHg,
+            PressureUnit::InHg => PressureUnit::Hpa,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum VolumeUnit {
+    #[default]
+    Liters,
+    Gallons,
+}
+
+impl VolumeUnit {
+    pub fn convert(self, liters: f32) -> f32 {
+        match self {
+            VolumeUnit::Liters => liters,
+            VolumeUnit::Gallons => liters / 3.785_41,
+        }
+    }
+
+    pub fn precision(self) -> usize {
+        1
+    }
+
+    pub fn template(self) -> TemplateId {
+        match self {
+            VolumeUnit::Liters => TemplateId::UnitLiters,
+            VolumeUnit::Gallons => TemplateId::UnitGallons,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            VolumeUnit::Liters => VolumeUnit::Gallons,
+            VolumeUnit::Gallons => VolumeUnit::Liters,
+        }
+    }
+}
+
+/// Display units chosen by a user; readings are always stored as °C, hPa and liters
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Units {
+    pub temperature: TemperatureUnit,
+    pub pressure: PressureUnit,
+    pub volume: VolumeUnit,
+}
@@ -0,0 +1,90 @@
+use time::{Duration, OffsetDateTime, PrimitiveDateTime};
+
+use crate::config::weekly;
+use crate::db::{Db, PeriodStats};
+
+/// The last seven days and the seven before them
+pub struct WeeklyComparison {
+    pub from: PrimitiveDateTime,
+    pub to: PrimitiveDateTime,
+    pub this: PeriodStats,
+    pub last: PeriodStats,
+}
+
+/// A change from last week worth calling out
+pub enum NotableChange {
+    /// Percent faster or slower
+    SoilDriedFaster(i64),
+    SoilDriedSlower(i64),
+    /// Percent more or less
+    MoreWaterUsed(i64),
+    LessWaterUsed(i64),
+    Warmer,
+    Colder,
+    /// Minutes more or less
+    MoreOutages(i64),
+    FewerOutages(i64),
+}
+
+pub async fn weekly_comparison(db: &Db) -> sqlx::Result<WeeklyComparison> {
+    let now = OffsetDateTime::now_utc();
+    let to = PrimitiveDateTime::new(now.date(), now.time());
+    let from = to - Duration::days(7);
+    let last_from = from - Duration::days(7);
+
+    Ok(WeeklyComparison {
+        from,
+        to,
+        this: db.get_period_stats(from, to).await?,
+        last: db.get_period_stats(last_from, from).await?,
+    })
+}
+
+impl WeeklyComparison {
+    pub fn notable_changes(&self) -> Vec<NotableChange> {
+        let (this, last) = (&self.this, &self.last);
+        let mut changes = Vec::new();
+
+        if let Some(percent) = percent_change(this.soil_drying_per_day, last.soil_drying_per_day) {
+            if percent >= weekly::NOTABLE_PERCENT {
+                changes.push(NotableChange::SoilDriedFaster(percent.round() as i64));
+            } else if percent <= -weekly::NOTABLE_PERCENT {
+                changes.push(NotableChange::SoilDriedSlower(-percent.round() as i64));
+            }
+        }
+
+        if let Some(percent) = percent_change(this.water_used_percent, last.water_used_percent) {
+            if percent >= weekly::NOTABLE_PERCENT {
+                changes.push(NotableChange::MoreWaterUsed(percent.round() as i64));
+            } else if percent <= -weekly::NOTABLE_PERCENT {
+                changes.push(NotableChange::LessWaterUsed(-percent.round() as i64));
+            }
+        }
+
+        if let (Some(this_temp), Some(last_temp)) = (this.avg_temp, last.avg_temp) {
+            let delta = this_temp - last_temp;
+            if delta >= weekly::NOTABLE_TEMPERATURE {
+                changes.push(NotableChange::Warmer);
+            } else if delta <= -weekly::NOTABLE_TEMPERATURE {
+                changes.push(NotableChange::Colder);
+            }
+        }
+
+        let outage_delta = this.outage_minutes - last.outage_minutes;
+        if outage_delta >= weekly::NOTABLE_OUTAGE_MINS {
+            changes.push(NotableChange::MoreOutages(outage_delta));
+        } else if outage_delta <= -weekly::NOTABLE_OUTAGE_MINS {
+            changes.push(NotableChange::FewerOutages(-outage_delta));
+        }
+
+        changes
+    }
+}
+
+/// Change relative to last week; `None` when either week lacks data or last week was zero
+fn percent_change(this: Option<f32>, last: Option<f32>) -> Option<f32> {
+    match (this, last) {
+        (Some(this), Some(last)) if last > 0.0 => Some((this - last) / last * 100.0),
+        _ => None,
+    }
+}
@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use axum::{extract::State, http::HeaderMap, response::Html, routing::get, Router};
+use time::OffsetDateTime;
+use time_tz::{timezones::db::europe::KYIV, OffsetDateTimeExt};
+
+use crate::db::{Db, Locale, OutageCause, PowerOutage};
+use crate::services::{format_kyiv, format_kyiv_time};
+use crate::templates::{Localized, TemplateId, Templates, Value};
+
+#[derive(Clone)]
+struct PageState {
+    db: Db,
+    templates: Arc<Templates>,
+}
+
+/// Public read-only power status page for neighbors
+pub fn routes(db: Db, templates: Arc<Templates>) -> Router {
+    Router::new()
+        .route("/power", get(power_status))
+        .with_state(PageState { db, templates })
+}
+
+async fn power_status(State(state): State<PageState>, headers: HeaderMap) -> Html<String> {
+    let language = headers
+        .get("Accept-Language")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split([',', ';']).next());
+    let locale = Locale::from_language_code(language);
+    let t = state.templates.locale(locale).html();
+
+    let active = state.db.get_active_outage().await.ok().flatten();
+    let current = match &active {
+        Some(outage) => t.render(
+            TemplateId::PowerCurrentOutage,
+            &[("since", format_kyiv(outage.started_at).into())],
+        ),
+        None => t.text(TemplateId::PowerOk),
+    };
+
+    let now = OffsetDateTime::now_utc();
+    let today = now.to_timezone(KYIV).date();
+    let recent = state.db.get_recent_outages(50).await.unwrap_or_default();
+    let items: String = recent
+        .iter()
+        .filter(|o| o.cause != OutageCause::Network)
+        .filter(|o| {
+            let ended = o.ended_at.map(|e| e.assume_utc()).unwrap_or(now);
+            ended.to_timezone(KYIV).date() == today
+        })
+        .map(|o| format!("<li>{}</li>", outage_item(&t, o)))
+        .collect();
+    let today_html = if items.is_empty() {
+        format!("<p>{}</p>", t.text(TemplateId::StatusPageNoOutages))
+    } else {
+        format!("<ul>{}</ul>", items)
+    };
+
+    let title = t.text(TemplateId::StatusPageTitle);
+    let updated = t.render(
+        TemplateId::StatusPageUpdated,
+        &[("time", format_kyiv(to_primitive(now)).into())],
+    );
+
+    Html(format!(
+        r#"<!DOCTYPE html>
+<html lang="{lang}">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<meta http-equiv="refresh" content="60">
+<title>{title}</title>
+<style>
+body {{ font-family: system-ui, sans-serif; max-width: 32rem; margin: 2rem auto; padding: 0 1rem; }}
+.current {{ font-size: 1.5rem; }}
+.updated {{ color: #777; font-size: 0.875rem; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p class="current">{current}</p>
+<h2>{today_title}</h2>
+{today_html}
+<p class="updated">{updated}</p>
+</body>
+</html>
+"#,
+        lang = locale.code(),
+        today_title = t.text(TemplateId::StatusPageToday),
+    ))
+}
+
+fn outage_item(t: &Localized<'_>, outage: &PowerOutage) -> String {
+    match (outage.ended_at, outage.duration_minutes) {
+        (Some(ended), Some(minutes)) => t.render(
+            TemplateId::StatusPageItem,
+            &[
+                ("start", format_kyiv_time(outage.started_at).into()),
+                ("end", format_kyiv_time(ended).into()),
+                ("duration", Value::Fragment(t.duration(minutes))),
+            ],
+        ),
+        _ => t.render(
+            TemplateId::StatusPageItemOngoing,
+            &[("start", format_kyiv_time(outage.started_at).into())],
+        ),
+    }
+}
+
+fn to_primitive(dt: OffsetDateTime) -> time::PrimitiveDateTime {
+    time::PrimitiveDateTime::new(dt.date(), dt.time())
+}
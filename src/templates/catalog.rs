@@ -0,0 +1,2325 @@
+use super::VarKind::{self, Fragment, Integer, Number, Pressure, Temperature, Text, Volume};
+use crate::db::Locale;
+
+/// Every message and label the bot sends, with the variables it may reference.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TemplateId {
+    AlertSoilLow,
+    AlertTempHigh,
+    PowerOutageDetected,
+    PowerRestored,
+    NetworkRestored,
+    PowerOutageExpected,
+    PowerOutageDetectedScheduled,
+    PowerSourcesSilent,
+    PowerOutageOnBattery,
+    BatteryLow,
+    BatteryState,
+    BatteryStateRuntime,
+    NoSensorData,
+    Status,
+    Weather,
+    WeatherTrend,
+    WeatherNoTrend,
+    Garden,
+    StatsNoData,
+    Stats,
+    MorningSummary,
+    MorningOvernight,
+    MorningNoOvernight,
+    MorningWaterings,
+    MorningNoWaterings,
+    MorningOutages,
+    MorningNoOutages,
+    WeeklyReport,
+    WeeklyLine,
+    WeeklyLineNew,
+    WeeklyTemperature,
+    WeeklyHumidity,
+    WeeklyPressure,
+    WeeklySoil,
+    WeeklyWaterLevel,
+    WeeklySoilDrying,
+    WeeklyWaterings,
+    WeeklyWaterUsed,
+    WeeklyOutages,
+    WeeklyAlerts,
+    WeeklyPercent,
+    WeeklyPerDay,
+    WeeklyNotable,
+    WeeklySoilFaster,
+    WeeklySoilSlower,
+    WeeklyMoreWater,
+    WeeklyLessWater,
+    WeeklyWarmer,
+    WeeklyColder,
+    WeeklyMoreOutages,
+    WeeklyFewerOutages,
+    PowerHistory,
+    PowerCurrentOutage,
+    PowerOk,
+    PowerNoRecent,
+    PowerRecent,
+    PowerRecentItem,
+    PowerRecentItemNetwork,
+    PowerScheduleNext,
+    PowerScheduleCompare,
+    PowerScheduleCompareItem,
+    PowerScheduleActual,
+    PowerScheduleActualOngoing,
+    PowerScheduleNoOutage,
+    PowerStats,
+    PowerStatsHours,
+    PowerStatsHourItem,
+    PowerPeriodDay,
+    PowerPeriodWeek,
+    PowerPeriodMonth,
+    Outlook,
+    OutlookItem,
+    OutlookNoData,
+    OutlookHeadsUp,
+    ChartSelectMetric,
+    ChartSelectPeriod,
+    ChartNoData,
+    ChartTitle,
+    ChartWatering,
+    ChartOutage,
+    ChartTemperature,
+    ChartHumidity,
+    ChartPressure,
+    ChartSoilMoisture,
+    ChartWaterLevel,
+    StatusPageTitle,
+    StatusPageToday,
+    StatusPageNoOutages,
+    StatusPageItem,
+    StatusPageItemOngoing,
+    StatusPageUpdated,
+    WelcomeBack,
+    EnterSecret,
+    Authorized,
+    AuthFailed,
+    IncorrectSecret,
+    Subscribed,
+    Unsubscribed,
+    SubscriberHelp,
+    MainMenu,
+    UseMenu,
+    Help,
+    HelpAdmin,
+    NotPermitted,
+    UsersTitle,
+    UserItem,
+    UserUntil,
+    RoleViewer,
+    RoleOperator,
+    RoleAdmin,
+    UserNotFound,
+    UserRevoked,
+    UserRoleChanged,
+    CannotChangeOwnAccess,
+    AccessRevoked,
+    RevokeUsage,
+    PromoteUsage,
+    BroadcastUsage,
+    BroadcastMessage,
+    BroadcastSent,
+    SecretRotated,
+    InviteCreated,
+    GuestAccess,
+    InviteUsage,
+    InviteInvalid,
+    AuthLocked,
+    AuthSuspicious,
+    SettingsTitle,
+    WaterSelect,
+    WaterCustomPrompt,
+    WaterDurationInvalid,
+    WaterDurationOutOfRange,
+    WaterConfirm,
+    MorningHourSelect,
+    ButtonHour,
+    TimezoneSet,
+    TimezoneUsage,
+    TimezoneUnknown,
+    SpanHours,
+    SpanDays,
+    StatsPeriod,
+    StatsPeriodNoData,
+    OutagesPeriod,
+    OutagesPeriodNone,
+    WaterUsage,
+    StatsUsage,
+    HistoryUsage,
+    OutagesUsage,
+    ExportUsage,
+    ExportNoData,
+    LiveStatusUpdated,
+    LiveStatusOffline,
+    LiveStatusStopped,
+    MiniAppWaterPrompt,
+    GroupLinked,
+    GroupUnlinked,
+    GroupNotLinked,
+    GroupPrivateOnly,
+    InlineStatusTitle,
+    InlineStatusDescription,
+    QueueTitle,
+    QueueEmpty,
+    QueueItem,
+    QueueStateQueued,
+    QueueStateSent,
+    QueueStateCancelled,
+    CommandCancelled,
+    CommandCancelTooLate,
+    TokensTitle,
+    TokensNone,
+    TokenItem,
+    TokenLastUsed,
+    TokenCreated,
+    TokenUsage,
+    TokenScopeNotAllowed,
+    TokenNameTaken,
+    TokenRevoked,
+    TokenAlreadyRevoked,
+    InputCancelled,
+    ButtonStatus,
+    ButtonWeather,
+    ButtonGarden,
+    ButtonStats,
+    ButtonPower,
+    ButtonWater,
+    ButtonSettings,
+    ButtonOutlook,
+    ButtonCharts,
+    ButtonWaterSeconds,
+    ButtonWaterCustom,
+    ButtonWeeklyReport,
+    ButtonPinLiveStatus,
+    ButtonStopLiveStatus,
+    ButtonDashboard,
+    ButtonQueue,
+    ButtonCancelCommand,
+    ButtonRefresh,
+    ButtonRevokeToken,
+    ButtonConfirm,
+    ButtonCancel,
+    ButtonBack,
+    ButtonPeriodDay,
+    ButtonPeriodWeek,
+    ButtonPeriodMonth,
+    ButtonPowerHistory,
+    SettingSoil,
+    SettingTemperature,
+    SettingPower,
+    SettingQuietHours,
+    SettingOutlook,
+    SettingMorningSummary,
+    SettingMorningHour,
+    SettingWeeklyReport,
+    SettingLanguage,
+    NotAuthorized,
+    WaterQueued,
+    WaterQueueFailed,
+    SoilAlerts,
+    TemperatureAlerts,
+    PowerAlerts,
+    QuietHours,
+    OutlookAlerts,
+    MorningSummaries,
+    WeeklyReports,
+    SettingEnabled,
+    SettingDisabled,
+    UpdateFailed,
+    LanguageChanged,
+    SoilVeryDry,
+    SoilGettingDry,
+    SoilGood,
+    SoilVeryWet,
+    WaterLow,
+    WaterGettingLow,
+    WaterOk,
+    TrendFallingFast,
+    TrendFalling,
+    TrendStable,
+    TrendRising,
+    TrendRisingFast,
+    ForecastStorm,
+    ForecastRain,
+    ForecastClear,
+    ForecastNoChange,
+    DurationMinutes,
+    DurationHours,
+    DurationHoursMinutes,
+    UnitCelsius,
+    UnitFahrenheit,
+    UnitHpa,
+    UnitMmHg,
+    UnitInHg,
+    UnitLiters,
+    UnitGallons,
+    SettingTemperatureUnit,
+    SettingPressureUnit,
+    SettingVolumeUnit,
+}
+
+struct Spec {
+    name: &'static str,
+    vars: &'static [(&'static str, VarKind)],
+    en: &'static str,
+    uk: &'static str,
+}
+
+impl TemplateId {
+    pub const ALL: &'static [TemplateId] = &[
+        TemplateId::AlertSoilLow,
+        TemplateId::AlertTempHigh,
+        TemplateId::PowerOutageDetected,
+        TemplateId::PowerRestored,
+        TemplateId::NetworkRestored,
+        TemplateId::PowerOutageExpected,
+        TemplateId::PowerOutageDetectedScheduled,
+        TemplateId::PowerSourcesSilent,
+        TemplateId::PowerOutageOnBattery,
+        TemplateId::BatteryLow,
+        TemplateId::BatteryState,
+        TemplateId::BatteryStateRuntime,
+        TemplateId::NoSensorData,
+        TemplateId::Status,
+        TemplateId::Weather,
+        TemplateId::WeatherTrend,
+        TemplateId::WeatherNoTrend,
+        TemplateId::Garden,
+        TemplateId::StatsNoData,
+        TemplateId::Stats,
+        TemplateId::MorningSummary,
+        TemplateId::MorningOvernight,
+        TemplateId::MorningNoOvernight,
+        TemplateId::MorningWaterings,
+        TemplateId::MorningNoWaterings,
+        TemplateId::MorningOutages,
+        TemplateId::MorningNoOutages,
+        TemplateId::WeeklyReport,
+        TemplateId::WeeklyLine,
+        TemplateId::WeeklyLineNew,
+        TemplateId::WeeklyTemperature,
+        TemplateId::WeeklyHumidity,
+        TemplateId::WeeklyPressure,
+        TemplateId::WeeklySoil,
+        TemplateId::WeeklyWaterLevel,
+        TemplateId::WeeklySoilDrying,
+        TemplateId::WeeklyWaterings,
+        TemplateId::WeeklyWaterUsed,
+        TemplateId::WeeklyOutages,
+        TemplateId::WeeklyAlerts,
+        TemplateId::WeeklyPercent,
+        TemplateId::WeeklyPerDay,
+        TemplateId::WeeklyNotable,
+        TemplateId::WeeklySoilFaster,
+        TemplateId::WeeklySoilSlower,
+        TemplateId::WeeklyMoreWater,
+        TemplateId::WeeklyLessWater,
+        TemplateId::WeeklyWarmer,
+        TemplateId::WeeklyColder,
+        TemplateId::WeeklyMoreOutages,
+        TemplateId::WeeklyFewerOutages,
+        TemplateId::PowerHistory,
+        TemplateId::PowerCurrentOutage,
+        TemplateId::PowerOk,
+        TemplateId::PowerNoRecent,
+        TemplateId::PowerRecent,
+        TemplateId::PowerRecentItem,
+        TemplateId::PowerRecentItemNetwork,
+        TemplateId::PowerScheduleNext,
+        TemplateId::PowerScheduleCompare,
+        TemplateId::PowerScheduleCompareItem,
+        TemplateId::PowerScheduleActual,
+        TemplateId::PowerScheduleActualOngoing,
+        TemplateId::PowerScheduleNoOutage,
+        TemplateId::PowerStats,
+        TemplateId::PowerStatsHours,
+        TemplateId::PowerStatsHourItem,
+        TemplateId::PowerPeriodDay,
+        TemplateId::PowerPeriodWeek,
+        TemplateId::PowerPeriodMonth,
+        TemplateId::Outlook,
+        TemplateId::OutlookItem,
+        TemplateId::OutlookNoData,
+        TemplateId::OutlookHeadsUp,
+        TemplateId::ChartSelectMetric,
+        TemplateId::ChartSelectPeriod,
+        TemplateId::ChartNoData,
+        TemplateId::ChartTitle,
+        TemplateId::ChartWatering,
+        TemplateId::ChartOutage,
+        TemplateId::ChartTemperature,
+        TemplateId::ChartHumidity,
+        TemplateId::ChartPressure,
+        TemplateId::ChartSoilMoisture,
+        TemplateId::ChartWaterLevel,
+        TemplateId::StatusPageTitle,
+        TemplateId::StatusPageToday,
+        TemplateId::StatusPageNoOutages,
+        TemplateId::StatusPageItem,
+        TemplateId::StatusPageItemOngoing,
+        TemplateId::StatusPageUpdated,
+        TemplateId::WelcomeBack,
+        TemplateId::EnterSecret,
+        TemplateId::Authorized,
+        TemplateId::AuthFailed,
+        TemplateId::IncorrectSecret,
+        TemplateId::Subscribed,
+        TemplateId::Unsubscribed,
+        TemplateId::SubscriberHelp,
+        TemplateId::MainMenu,
+        TemplateId::UseMenu,
+        TemplateId::Help,
+        TemplateId::HelpAdmin,
+        TemplateId::NotPermitted,
+        TemplateId::UsersTitle,
+        TemplateId::UserItem,
+        TemplateId::UserUntil,
+        TemplateId::RoleViewer,
+        TemplateId::RoleOperator,
+        TemplateId::RoleAdmin,
+        TemplateId::UserNotFound,
+        TemplateId::UserRevoked,
+        TemplateId::UserRoleChanged,
+        TemplateId::CannotChangeOwnAccess,
+        TemplateId::AccessRevoked,
+        TemplateId::RevokeUsage,
+        TemplateId::PromoteUsage,
+        TemplateId::BroadcastUsage,
+        TemplateId::BroadcastMessage,
+        TemplateId::BroadcastSent,
+        TemplateId::SecretRotated,
+        TemplateId::InviteCreated,
+        TemplateId::GuestAccess,
+        TemplateId::InviteUsage,
+        TemplateId::InviteInvalid,
+        TemplateId::AuthLocked,
+        TemplateId::AuthSuspicious,
+        TemplateId::SettingsTitle,
+        TemplateId::WaterSelect,
+        TemplateId::WaterCustomPrompt,
+        TemplateId::WaterDurationInvalid,
+        TemplateId::WaterDurationOutOfRange,
+        TemplateId::WaterConfirm,
+        TemplateId::MorningHourSelect,
+        TemplateId::ButtonHour,
+        TemplateId::TimezoneSet,
+        TemplateId::TimezoneUsage,
+        TemplateId::TimezoneUnknown,
+        TemplateId::SpanHours,
+        TemplateId::SpanDays,
+        TemplateId::StatsPeriod,
+        TemplateId::StatsPeriodNoData,
+        TemplateId::OutagesPeriod,
+        TemplateId::OutagesPeriodNone,
+        TemplateId::WaterUsage,
+        TemplateId::StatsUsage,
+        TemplateId::HistoryUsage,
+        TemplateId::OutagesUsage,
+        TemplateId::ExportUsage,
+        TemplateId::ExportNoData,
+        TemplateId::LiveStatusUpdated,
+        TemplateId::LiveStatusOffline,
+        TemplateId::LiveStatusStopped,
+        TemplateId::MiniAppWaterPrompt,
+        TemplateId::GroupLinked,
+        TemplateId::GroupUnlinked,
+        TemplateId::GroupNotLinked,
+        TemplateId::GroupPrivateOnly,
+        TemplateId::InlineStatusTitle,
+        TemplateId::InlineStatusDescription,
+        TemplateId::QueueTitle,
+        TemplateId::QueueEmpty,
+        TemplateId::QueueItem,
+        TemplateId::QueueStateQueued,
+        TemplateId::QueueStateSent,
+        TemplateId::QueueStateCancelled,
+        TemplateId::CommandCancelled,
+        TemplateId::CommandCancelTooLate,
+        TemplateId::TokensTitle,
+        TemplateId::TokensNone,
+        TemplateId::TokenItem,
+        TemplateId::TokenLastUsed,
+        TemplateId::TokenCreated,
+        TemplateId::TokenUsage,
+        TemplateId::TokenScopeNotAllowed,
+        TemplateId::TokenNameTaken,
+        TemplateId::TokenRevoked,
+        TemplateId::TokenAlreadyRevoked,
+        TemplateId::InputCancelled,
+        TemplateId::ButtonStatus,
+        TemplateId::ButtonWeather,
+        TemplateId::ButtonGarden,
+        TemplateId::ButtonStats,
+        TemplateId::ButtonPower,
+        TemplateId::ButtonWater,
+        TemplateId::ButtonSettings,
+        TemplateId::ButtonOutlook,
+        TemplateId::ButtonCharts,
+        TemplateId::ButtonWaterSeconds,
+        TemplateId::ButtonWaterCustom,
+        TemplateId::ButtonWeeklyReport,
+        TemplateId::ButtonPinLiveStatus,
+        TemplateId::ButtonStopLiveStatus,
+        TemplateId::ButtonDashboard,
+        TemplateId::ButtonQueue,
+        TemplateId::ButtonCancelCommand,
+        TemplateId::ButtonRefresh,
+        TemplateId::ButtonRevokeToken,
+        TemplateId::ButtonConfirm,
+        TemplateId::ButtonCancel,
+        TemplateId::ButtonBack,
+        TemplateId::ButtonPeriodDay,
+        TemplateId::ButtonPeriodWeek,
+        TemplateId::ButtonPeriodMonth,
+        TemplateId::ButtonPowerHistory,
+        TemplateId::SettingSoil,
+        TemplateId::SettingTemperature,
+        TemplateId::SettingPower,
+        TemplateId::SettingQuietHours,
+        TemplateId::SettingOutlook,
+        TemplateId::SettingMorningSummary,
+        TemplateId::SettingMorningHour,
+        TemplateId::SettingWeeklyReport,
+        TemplateId::SettingLanguage,
+        TemplateId::NotAuthorized,
+        TemplateId::WaterQueued,
+        TemplateId::WaterQueueFailed,
+        TemplateId::SoilAlerts,
+        TemplateId::TemperatureAlerts,
+        TemplateId::PowerAlerts,
+        TemplateId::QuietHours,
+        TemplateId::OutlookAlerts,
+        TemplateId::MorningSummaries,
+        TemplateId::WeeklyReports,
+        TemplateId::SettingEnabled,
+        TemplateId::SettingDisabled,
+        TemplateId::UpdateFailed,
+        TemplateId::LanguageChanged,
+        TemplateId::SoilVeryDry,
+        TemplateId::SoilGettingDry,
+        TemplateId::SoilGood,
+        TemplateId::SoilVeryWet,
+        TemplateId::WaterLow,
+        TemplateId::WaterGettingLow,
+        TemplateId::WaterOk,
+        TemplateId::TrendFallingFast,
+        TemplateId::TrendFalling,
+        TemplateId::TrendStable,
+        TemplateId::TrendRising,
+        TemplateId::TrendRisingFast,
+        TemplateId::ForecastStorm,
+        TemplateId::ForecastRain,
+        TemplateId::ForecastClear,
+        TemplateId::ForecastNoChange,
+        TemplateId::DurationMinutes,
+        TemplateId::DurationHours,
+        TemplateId::DurationHoursMinutes,
+        TemplateId::UnitCelsius,
+        TemplateId::UnitFahrenheit,
+        TemplateId::UnitHpa,
+        TemplateId::UnitMmHg,
+        TemplateId::UnitInHg,
+        TemplateId::UnitLiters,
+        TemplateId::UnitGallons,
+        TemplateId::SettingTemperatureUnit,
+        TemplateId::SettingPressureUnit,
+        TemplateId::SettingVolumeUnit,
+    ];
+
+    pub fn name(self) -> &'static str {
+        self.spec().name
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|id| id.name() == name)
+    }
+
+    pub fn vars(self) -> &'static [(&'static str, VarKind)] {
+        self.spec().vars
+    }
+
+    pub fn default_body(self, locale: Locale) -> &'static str {
+        let spec = self.spec();
+        match locale {
+            Locale::En => spec.en,
+            Locale::Uk => spec.uk,
+        }
+    }
+
+    fn spec(self) -> Spec {
+        match self {
+            TemplateId::AlertSoilLow => Spec {
+                name: "alert_soil_low",
+                vars: &[("soil_moisture", Number)],
+                en: "⚠️ Low soil moisture: {soil_moisture}%",
+                uk: "⚠️ Низька вологість ґрунту: {soil_moisture}%",
+            },
+            TemplateId::AlertTempHigh => Spec {
+                name: "alert_temp_high",
+                vars: &[("temperature", Temperature)],
+                en: "🔥 High temperature: {temperature}",
+                uk: "🔥 Висока температура: {temperature}",
+            },
+            TemplateId::PowerOutageDetected => Spec {
+                name: "power_outage_detected",
+                vars: &[("last_data", Text), ("sources", Fragment), ("battery", Fragment)],
+                en: "⚡ Power outage detected!\nLast data: {last_data}{sources}{battery}",
+                uk: "⚡ Виявлено відключення світла!\nОстанні дані: {last_data}{sources}{battery}",
+            },
+            TemplateId::PowerRestored => Spec {
+                name: "power_restored",
+                vars: &[("time", Text), ("duration", Fragment)],
+                en: "✅ Power restored at {time}\nOutage duration: {duration}",
+                uk: "✅ Світло повернулося о {time}\nТривалість відключення: {duration}",
+            },
+            TemplateId::NetworkRestored => Spec {
+                name: "network_restored",
+                vars: &[("time", Text), ("duration", Fragment)],
+                en: "📶 Device back online at {time}\n\
+                     It stayed powered, so the {duration} gap was a connectivity outage",
+                uk: "📶 Пристрій знову на зв'язку о {time}\n\
+                     Живлення не зникало, тож перерва {duration} була через зв'язок",
+            },
+            TemplateId::PowerOutageExpected => Spec {
+                name: "power_outage_expected",
+                vars: &[("minutes", Integer), ("start", Text), ("end", Text)],
+                en: "⏰ Power outage expected in {minutes} min\nScheduled: {start} – {end}",
+                uk: "⏰ Відключення світла очікується через {minutes} хв\nЗа графіком: {start} – {end}",
+            },
+            TemplateId::PowerOutageDetectedScheduled => Spec {
+                name: "power_outage_detected_scheduled",
+                vars: &[
+                    ("last_data", Text),
+                    ("end", Text),
+                    ("sources", Fragment),
+                    ("battery", Fragment),
+                ],
+                en: "⚡ Power outage detected!\nLast data: {last_data}\n📅 Scheduled to end at {end}{sources}{battery}",
+                uk: "⚡ Виявлено відключення світла!\nОстанні дані: {last_data}\n📅 За графіком до {end}{sources}{battery}",
+            },
+            TemplateId::PowerSourcesSilent => Spec {
+                name: "power_sources_silent",
+                vars: &[("sources", Text)],
+                en: "📡 Silent: {sources}",
+                uk: "📡 Не на зв'язку: {sources}",
+            },
+            TemplateId::PowerOutageOnBattery => Spec {
+                name: "power_outage_on_battery",
+                vars: &[("since", Text), ("battery", Fragment)],
+                en: "⚡ Power outage detected!\nRunning on battery since {since}{battery}",
+                uk: "⚡ Виявлено відключення світла!\nПрацюємо від батареї з {since}{battery}",
+            },
+            TemplateId::BatteryLow => Spec {
+                name: "battery_low",
+                vars: &[("state", Fragment)],
+                en: "🪫 UPS battery is running low\n{state}",
+                uk: "🪫 Батарея ДБЖ сідає\n{state}",
+            },
+            TemplateId::BatteryState => Spec {
+                name: "battery_state",
+                vars: &[("battery", Number)],
+                en: "🔋 Battery: {battery:.0}%",
+                uk: "🔋 Батарея: {battery:.0}%",
+            },
+            TemplateId::BatteryStateRuntime => Spec {
+                name: "battery_state_runtime",
+                vars: &[("battery", Number), ("runtime", Fragment)],
+                en: "🔋 Battery: {battery:.0}%, about {runtime} left",
+                uk: "🔋 Батарея: {battery:.0}%, вистачить приблизно на {runtime}",
+            },
+            TemplateId::NoSensorData => Spec {
+                name: "no_sensor_data",
+                vars: &[],
+                en: "No sensor data available",
+                uk: "Немає даних із датчиків",
+            },
+            TemplateId::Status => Spec {
+                name: "status",
+                vars: &[
+                    ("temperature", Temperature),
+                    ("humidity", Number),
+                    ("pressure", Pressure),
+                    ("soil_moisture", Number),
+                    ("water_level", Number),
+                    ("battery", Fragment),
+                ],
+                en: "📊 Current Status\n\n\
+                     🌡 Temperature: {temperature}\n\
+                     💧 Humidity: {humidity}%\n\
+                     🌪 Pressure: {pressure}\n\
+                     🌱 Soil moisture: {soil_moisture}%\n\
+                     💦 Water level: {water_level}%{battery}",
+                uk: "📊 Поточний стан\n\n\
+                     🌡 Температура: {temperature}\n\
+                     💧 Вологість: {humidity}%\n\
+                     🌪 Тиск: {pressure}\n\
+                     🌱 Вологість ґрунту: {soil_moisture}%\n\
+                     💦 Рівень води: {water_level}%{battery}",
+            },
+            TemplateId::Weather => Spec {
+                name: "weather",
+                vars: &[
+                    ("temperature", Temperature),
+                    ("humidity", Number),
+                    ("pressure", Pressure),
+                    ("trend", Fragment),
+                ],
+                en: "🌤 Weather\n\n\
+                     🌡 Temperature: {temperature}\n\
+                     💧 Humidity: {humidity}%\n\
+                     🌪 Pressure: {pressure}\n\n\
+                     {trend}",
+                uk: "🌤 Погода\n\n\
+                     🌡 Температура: {temperature}\n\
+                     💧 Вологість: {humidity}%\n\
+                     🌪 Тиск: {pressure}\n\n\
+                     {trend}",
+            },
+            TemplateId::WeatherTrend => Spec {
+                name: "weather_trend",
+                vars: &[
+                    ("hours", Integer),
+                    ("symbol", Text),
+                    ("label", Fragment),
+                    ("delta", Pressure),
+                    ("forecast_emoji", Text),
+                    ("forecast", Fragment),
+                ],
+                en: "📉 {hours}h trend: {symbol} {label} ({delta:+})\n\n\
+                     {forecast_emoji} {forecast}",
+                uk: "📉 Тенденція за {hours} год: {symbol} {label} ({delta:+})\n\n\
+                     {forecast_emoji} {forecast}",
+            },
+            TemplateId::WeatherNoTrend => Spec {
+                name: "weather_no_trend",
+                vars: &[],
+                en: "📉 Trend: -- no history yet",
+                uk: "📉 Тенденція: -- ще немає історії",
+            },
+            TemplateId::Garden => Spec {
+                name: "garden",
+                vars: &[
+                    ("soil_moisture", Number),
+                    ("soil_emoji", Text),
+                    ("soil_message", Fragment),
+                    ("water_level", Number),
+                    ("water_volume", Volume),
+                    ("water_emoji", Text),
+                    ("water_message", Fragment),
+                ],
+                en: "🌱 Garden Status\n\n\
+                     🌱 Soil moisture: {soil_moisture}%\n\
+                     {soil_emoji} {soil_message}\n\n\
+                     💦 Water level: {water_level}% (~{water_volume})\n\
+                     {water_emoji} {water_message}",
+                uk: "🌱 Стан саду\n\n\
+                     🌱 Вологість ґрунту: {soil_moisture}%\n\
+                     {soil_emoji} {soil_message}\n\n\
+                     💦 Рівень води: {water_level}% (~{water_volume})\n\
+                     {water_emoji} {water_message}",
+            },
+            TemplateId::StatsNoData => Spec {
+                name: "stats_no_data",
+                vars: &[],
+                en: "No data for today",
+                uk: "Немає даних за сьогодні",
+            },
+            TemplateId::Stats => Spec {
+                name: "stats",
+                vars: &[
+                    ("min_temp", Temperature),
+                    ("max_temp", Temperature),
+                    ("avg_temp", Temperature),
+                    ("min_humidity", Number),
+                    ("max_humidity", Number),
+                ],
+                en: "📈 Today's Stats\n\n\
+                     🌡 Temperature:\n\
+                     Min: {min_temp}\n\
+                     Max: {max_temp}\n\
+                     Avg: {avg_temp}\n\n\
+                     💧 Humidity:\n\
+                     Min: {min_humidity}%\n\
+                     Max: {max_humidity}%",
+                uk: "📈 Статистика за сьогодні\n\n\
+                     🌡 Температура:\n\
+                     Мін: {min_temp}\n\
+                     Макс: {max_temp}\n\
+                     Сер: {avg_temp}\n\n\
+                     💧 Вологість:\n\
+                     Мін: {min_humidity}%\n\
+                     Макс: {max_humidity}%",
+            },
+            TemplateId::MorningSummary => Spec {
+                name: "morning_summary",
+                vars: &[
+                    ("overnight", Fragment),
+                    ("waterings", Fragment),
+                    ("status", Fragment),
+                    ("weather", Fragment),
+                    ("outages", Fragment),
+                ],
+                en: "🌅 Good morning!\n\n{overnight}\n{waterings}\n\n{status}\n\n{weather}\n\n{outages}",
+                uk: "🌅 Доброго ранку!\n\n{overnight}\n{waterings}\n\n{status}\n\n{weather}\n\n{outages}",
+            },
+            TemplateId::MorningOvernight => Spec {
+                name: "morning_overnight",
+                vars: &[("min_temp", Temperature), ("max_temp", Temperature)],
+                en: "🌙 Overnight: {min_temp} to {max_temp}",
+                uk: "🌙 Уночі: від {min_temp} до {max_temp}",
+            },
+            TemplateId::MorningNoOvernight => Spec {
+                name: "morning_no_overnight",
+                vars: &[],
+                en: "🌙 No overnight readings",
+                uk: "🌙 Нічних показників немає",
+            },
+            TemplateId::MorningWaterings => Spec {
+                name: "morning_waterings",
+                vars: &[("hours", Integer), ("times", Text)],
+                en: "💧 Watered in the last {hours} h at {times}",
+                uk: "💧 Полив за останні {hours} год: {times}",
+            },
+            TemplateId::MorningNoWaterings => Spec {
+                name: "morning_no_waterings",
+                vars: &[("hours", Integer)],
+                en: "💧 No watering in the last {hours} h",
+                uk: "💧 За останні {hours} год поливу не було",
+            },
+            TemplateId::MorningOutages => Spec {
+                name: "morning_outages",
+                vars: &[("current", Fragment), ("hours", Integer), ("items", Fragment)],
+                en: "{current}\nOutages in the last {hours} h:\n{items}",
+                uk: "{current}\nВідключення за останні {hours} год:\n{items}",
+            },
+            TemplateId::MorningNoOutages => Spec {
+                name: "morning_no_outages",
+                vars: &[("current", Fragment), ("hours", Integer)],
+                en: "{current}\nNo outages in the last {hours} h",
+                uk: "{current}\nЗа останні {hours} год відключень не було",
+            },
+            TemplateId::WeeklyReport => Spec {
+                name: "weekly_report",
+                vars: &[("from", Text), ("to", Text), ("lines", Fragment), ("notable", Fragment)],
+                en: "📅 Weekly report, {from} – {to}\n\n{lines}{notable}",
+                uk: "📅 Тижневий звіт, {from} – {to}\n\n{lines}{notable}",
+            },
+            TemplateId::WeeklyLine => Spec {
+                name: "weekly_line",
+                vars: &[("label", Fragment), ("this", Fragment), ("last", Fragment)],
+                en: "{label}: {this} (last week {last})\n",
+                uk: "{label}: {this} (минулого тижня {last})\n",
+            },
+            TemplateId::WeeklyLineNew => Spec {
+                name: "weekly_line_new",
+                vars: &[("label", Fragment), ("this", Fragment)],
+                en: "{label}: {this}\n",
+                uk: "{label}: {this}\n",
+            },
+            TemplateId::WeeklyTemperature => Spec {
+                name: "weekly_temperature",
+                vars: &[],
+                en: "🌡 Avg temperature",
+                uk: "🌡 Середня температура",
+            },
+            TemplateId::WeeklyHumidity => Spec {
+                name: "weekly_humidity",
+                vars: &[],
+                en: "💧 Avg humidity",
+                uk: "💧 Середня вологість",
+            },
+            TemplateId::WeeklyPressure => Spec {
+                name: "weekly_pressure",
+                vars: &[],
+                en: "🌀 Avg pressure",
+                uk: "🌀 Середній тиск",
+            },
+            TemplateId::WeeklySoil => Spec {
+                name: "weekly_soil",
+                vars: &[],
+                en: "🌱 Avg soil moisture",
+                uk: "🌱 Середня вологість ґрунту",
+            },
+            TemplateId::WeeklyWaterLevel => Spec {
+                name: "weekly_water_level",
+                vars: &[],
+                en: "💦 Avg tank level",
+                uk: "💦 Середній рівень у баку",
+            },
+            TemplateId::WeeklySoilDrying => Spec {
+                name: "weekly_soil_drying",
+                vars: &[],
+                en: "🍂 Soil drying",
+                uk: "🍂 Висихання ґрунту",
+            },
+            TemplateId::WeeklyWaterings => Spec {
+                name: "weekly_waterings",
+                vars: &[],
+                en: "🚿 Waterings",
+                uk: "🚿 Поливів",
+            },
+            TemplateId::WeeklyWaterUsed => Spec {
+                name: "weekly_water_used",
+                vars: &[],
+                en: "🪣 Water used",
+                uk: "🪣 Витрачено води",
+            },
+            TemplateId::WeeklyOutages => Spec {
+                name: "weekly_outages",
+                vars: &[],
+                en: "⚡ Without power",
+                uk: "⚡ Без світла",
+            },
+            TemplateId::WeeklyAlerts => Spec {
+                name: "weekly_alerts",
+                vars: &[],
+                en: "🔔 Alerts",
+                uk: "🔔 Сповіщень",
+            },
+            TemplateId::WeeklyPercent => Spec {
+                name: "weekly_percent",
+                vars: &[("value", Number)],
+                en: "{value}%",
+                uk: "{value}%",
+            },
+            TemplateId::WeeklyPerDay => Spec {
+                name: "weekly_per_day",
+                vars: &[("value", Number)],
+                en: "{value}%/day",
+                uk: "{value}%/добу",
+            },
+            TemplateId::WeeklyNotable => Spec {
+                name: "weekly_notable",
+                vars: &[("items", Fragment)],
+                en: "\n📌 Notable:\n{items}",
+                uk: "\n📌 Варто уваги:\n{items}",
+            },
+            TemplateId::WeeklySoilFaster => Spec {
+                name: "weekly_soil_faster",
+                vars: &[("percent", Integer)],
+                en: "• Soil dried {percent}% faster than last week\n",
+                uk: "• Ґрунт висихав на {percent}% швидше, ніж минулого тижня\n",
+            },
+            TemplateId::WeeklySoilSlower => Spec {
+                name: "weekly_soil_slower",
+                vars: &[("percent", Integer)],
+                en: "• Soil dried {percent}% slower than last week\n",
+                uk: "• Ґрунт висихав на {percent}% повільніше, ніж минулого тижня\n",
+            },
+            TemplateId::WeeklyMoreWater => Spec {
+                name: "weekly_more_water",
+                vars: &[("percent", Integer)],
+                en: "• {percent}% more water used than last week\n",
+                uk: "• Води витрачено на {percent}% більше, ніж минулого тижня\n",
+            },
+            TemplateId::WeeklyLessWater => Spec {
+                name: "weekly_less_water",
+                vars: &[("percent", Integer)],
+                en: "• {percent}% less water used than last week\n",
+                uk: "• Води витрачено на {percent}% менше, ніж минулого тижня\n",
+            },
+            TemplateId::WeeklyWarmer => Spec {
+                name: "weekly_warmer",
+                vars: &[],
+                en: "• Warmer than last week\n",
+                uk: "• Тепліше, ніж минулого тижня\n",
+            },
+            TemplateId::WeeklyColder => Spec {
+                name: "weekly_colder",
+                vars: &[],
+                en: "• Colder than last week\n",
+                uk: "• Холодніше, ніж минулого тижня\n",
+            },
+            TemplateId::WeeklyMoreOutages => Spec {
+                name: "weekly_more_outages",
+                vars: &[("duration", Fragment)],
+                en: "• {duration} more without power than last week\n",
+                uk: "• Без світла на {duration} довше, ніж минулого тижня\n",
+            },
+            TemplateId::WeeklyFewerOutages => Spec {
+                name: "weekly_fewer_outages",
+                vars: &[("duration", Fragment)],
+                en: "• {duration} less without power than last week\n",
+                uk: "• Без світла на {duration} менше, ніж минулого тижня\n",
+            },
+            TemplateId::PowerHistory => Spec {
+                name: "power_history",
+                vars: &[("current", Fragment), ("recent", Fragment), ("schedule", Fragment)],
+                en: "⚡ Power History\n\n{current}\n\n{recent}{schedule}",
+                uk: "⚡ Історія світла\n\n{current}\n\n{recent}{schedule}",
+            },
+            TemplateId::PowerCurrentOutage => Spec {
+                name: "power_current_outage",
+                vars: &[("since", Text)],
+                en: "🔴 Current outage since {since}",
+                uk: "🔴 Світла немає з {since}",
+            },
+            TemplateId::PowerOk => Spec {
+                name: "power_ok",
+                vars: &[],
+                en: "🟢 Power is OK",
+                uk: "🟢 Світло є",
+            },
+            TemplateId::PowerNoRecent => Spec {
+                name: "power_no_recent",
+                vars: &[],
+                en: "No recent outages",
+                uk: "Останнім часом відключень не було",
+            },
+            TemplateId::PowerRecent => Spec {
+                name: "power_recent",
+                vars: &[("items", Fragment)],
+                en: "Recent outages:\n{items}",
+                uk: "Останні відключення:\n{items}",
+            },
+            TemplateId::PowerRecentItem => Spec {
+                name: "power_recent_item",
+                vars: &[("started", Text), ("duration", Fragment)],
+                en: "• {started} ({duration})\n",
+                uk: "• {started} ({duration})\n",
+            },
+            TemplateId::PowerRecentItemNetwork => Spec {
+                name: "power_recent_item_network",
+                vars: &[("started", Text), ("duration", Fragment)],
+                en: "• {started} ({duration}, 📶 connectivity only)\n",
+                uk: "• {started} ({duration}, 📶 лише зв'язок)\n",
+            },
+            TemplateId::PowerScheduleNext => Spec {
+                name: "power_schedule_next",
+                vars: &[("start", Text), ("end", Text)],
+                en: "📅 Next scheduled outage: {start} – {end}",
+                uk: "📅 Наступне відключення за графіком: {start} – {end}",
+            },
+            TemplateId::PowerScheduleCompare => Spec {
+                name: "power_schedule_compare",
+                vars: &[("items", Fragment)],
+                en: "Scheduled vs actual:\n{items}",
+                uk: "За графіком і насправді:\n{items}",
+            },
+            TemplateId::PowerScheduleCompareItem => Spec {
+                name: "power_schedule_compare_item",
+                vars: &[("start", Text), ("end", Text), ("actual", Fragment)],
+                en: "• {start} – {end} → {actual}\n",
+                uk: "• {start} – {end} → {actual}\n",
+            },
+            TemplateId::PowerScheduleActual => Spec {
+                name: "power_schedule_actual",
+                vars: &[("start", Text), ("end", Text)],
+                en: "{start} – {end}",
+                uk: "{start} – {end}",
+            },
+            TemplateId::PowerScheduleActualOngoing => Spec {
+                name: "power_schedule_actual_ongoing",
+                vars: &[("start", Text)],
+                en: "since {start}",
+                uk: "з {start}",
+            },
+            TemplateId::PowerScheduleNoOutage => Spec {
+                name: "power_schedule_no_outage",
+                vars: &[],
+                en: "no outage",
+                uk: "світло було",
+            },
+            TemplateId::PowerStats => Spec {
+                name: "power_stats",
+                vars: &[
+                    ("period", Fragment),
+                    ("uptime", Number),
+                    ("uptime_change", Number),
+                    ("outages", Integer),
+                    ("outages_change", Integer),
+                    ("total", Fragment),
+                    ("longest", Fragment),
+                    ("hours", Fragment),
+                ],
+                en: "⚡ Power availability, {period}\n\n\
+                     🟢 Uptime: {uptime:.2}% ({uptime_change:+.2} vs previous)\n\
+                     🔴 Outages: {outages} ({outages_change:+} vs previous)\n\
+                     ⏱ Total without power: {total}\n\
+                     📏 Longest outage: {longest}{hours}",
+                uk: "⚡ Наявність світла {period}\n\n\
+                     🟢 Світло було: {uptime:.2}% ({uptime_change:+.2} до попереднього)\n\
+                     🔴 Відключень: {outages} ({outages_change:+} до попереднього)\n\
+                     ⏱ Загалом без світла: {total}\n\
+                     📏 Найдовше відключення: {longest}{hours}",
+            },
+            TemplateId::PowerStatsHours => Spec {
+                name: "power_stats_hours",
+                vars: &[("items", Fragment)],
+                en: "Outages by hour:\n{items}",
+                uk: "Відключення за годинами:\n{items}",
+            },
+            TemplateId::PowerStatsHourItem => Spec {
+                name: "power_stats_hour_item",
+                vars: &[("hour", Text), ("bar", Text), ("count", Integer)],
+                en: "{hour}:00 {bar} {count}\n",
+                uk: "{hour}:00 {bar} {count}\n",
+            },
+            TemplateId::PowerPeriodDay => Spec {
+                name: "power_period_day",
+                vars: &[],
+                en: "last 24 hours",
+                uk: "за останню добу",
+            },
+            TemplateId::PowerPeriodWeek => Spec {
+                name: "power_period_week",
+                vars: &[],
+                en: "last 7 days",
+                uk: "за останні 7 днів",
+            },
+            TemplateId::PowerPeriodMonth => Spec {
+                name: "power_period_month",
+                vars: &[],
+                en: "last 30 days",
+                uk: "за останні 30 днів",
+            },
+            TemplateId::Outlook => Spec {
+                name: "outlook",
+                vars: &[("hours", Integer), ("overall", Number), ("items", Fragment)],
+                en: "🔮 Outage outlook\n\n\
+                     Chance of an outage in the next {hours} hours: {overall:.0}%\n\n\
+                     {items}\n\
+                     Based on which hours lost power on recent days and on the same weekday before.",
+                uk: "🔮 Прогноз відключень\n\n\
+                     Ймовірність відключення в найближчі {hours} год: {overall:.0}%\n\n\
+                     {items}\n\
+                     За годинами без світла в останні дні та в той самий день тижня раніше.",
+            },
+            TemplateId::OutlookItem => Spec {
+                name: "outlook_item",
+                vars: &[("icon", Text), ("hour", Text), ("probability", Number)],
+                en: "{icon} {hour} {probability:.0}%\n",
+                uk: "{icon} {hour} {probability:.0}%\n",
+            },
+            TemplateId::OutlookNoData => Spec {
+                name: "outlook_no_data",
+                vars: &[],
+                en: "🔮 Not enough outage history for an outlook yet",
+                uk: "🔮 Поки замало історії відключень для прогнозу",
+            },
+            TemplateId::OutlookHeadsUp => Spec {
+                name: "outlook_heads_up",
+                vars: &[("hour", Text), ("probability", Number)],
+                en: "🔮 Heads-up: power often drops around {hour}\n\
+                     Chance of an outage in that hour: {probability:.0}%",
+                uk: "🔮 Увага: світло часто зникає близько {hour}\n\
+                     Ймовірність відключення в цю годину: {probability:.0}%",
+            },
+            TemplateId::ChartSelectMetric => Spec {
+                name: "chart_select_metric",
+                vars: &[],
+                en: "📉 Choose a chart:",
+                uk: "📉 Оберіть графік:",
+            },
+            TemplateId::ChartSelectPeriod => Spec {
+                name: "chart_select_period",
+                vars: &[("metric", Text)],
+                en: "📉 {metric}: choose a range",
+                uk: "📉 {metric}: оберіть період",
+            },
+            TemplateId::ChartNoData => Spec {
+                name: "chart_no_data",
+                vars: &[],
+                en: "📉 No readings for this range yet",
+                uk: "📉 Поки немає даних за цей період",
+            },
+            TemplateId::ChartTitle => Spec {
+                name: "chart_title",
+                vars: &[("metric", Text), ("unit", Text), ("period", Fragment)],
+                en: "{metric}, {unit} — {period}",
+                uk: "{metric}, {unit} — {period}",
+            },
+            TemplateId::ChartWatering => Spec {
+                name: "chart_watering",
+                vars: &[],
+                en: "Watering",
+                uk: "Полив",
+            },
+            TemplateId::ChartOutage => Spec {
+                name: "chart_outage",
+                vars: &[],
+                en: "Power outage",
+                uk: "Відключення світла",
+            },
+            TemplateId::ChartTemperature => Spec {
+                name: "chart_temperature",
+                vars: &[],
+                en: "Temperature",
+                uk: "Температура",
+            },
+            TemplateId::ChartHumidity => Spec {
+                name: "chart_humidity",
+                vars: &[],
+                en: "Humidity",
+                uk: "Вологість",
+            },
+            TemplateId::ChartPressure => Spec {
+                name: "chart_pressure",
+                vars: &[],
+                en: "Pressure",
+                uk: "Тиск",
+            },
+            TemplateId::ChartSoilMoisture => Spec {
+                name: "chart_soil_moisture",
+                vars: &[],
+                en: "Soil moisture",
+                uk: "Вологість ґрунту",
+            },
+            TemplateId::ChartWaterLevel => Spec {
+                name: "chart_water_level",
+                vars: &[],
+                en: "Water level",
+                uk: "Рівень води",
+            },
+            TemplateId::StatusPageTitle => Spec {
+                name: "status_page_title",
+                vars: &[],
+                en: "Power status",
+                uk: "Стан світла",
+            },
+            TemplateId::StatusPageToday => Spec {
+                name: "status_page_today",
+                vars: &[],
+                en: "Today's outages",
+                uk: "Відключення сьогодні",
+            },
+            TemplateId::StatusPageNoOutages => Spec {
+                name: "status_page_no_outages",
+                vars: &[],
+                en: "No outages today",
+                uk: "Сьогодні відключень не було",
+            },
+            TemplateId::StatusPageItem => Spec {
+                name: "status_page_item",
+                vars: &[("start", Text), ("end", Text), ("duration", Fragment)],
+                en: "{start} – {end} ({duration})",
+                uk: "{start} – {end} ({duration})",
+            },
+            TemplateId::StatusPageItemOngoing => Spec {
+                name: "status_page_item_ongoing",
+                vars: &[("start", Text)],
+                en: "since {start}, ongoing",
+                uk: "з {start}, триває",
+            },
+            TemplateId::StatusPageUpdated => Spec {
+                name: "status_page_updated",
+                vars: &[("time", Text)],
+                en: "Updated at {time}",
+                uk: "Оновлено о {time}",
+            },
+            TemplateId::WelcomeBack => Spec {
+                name: "welcome_back",
+                vars: &[],
+                en: "Welcome back!",
+                uk: "З поверненням!",
+            },
+            TemplateId::EnterSecret => Spec {
+                name: "enter_secret",
+                vars: &[],
+                en: "Please enter the secret word to continue.\n\n\
+                     Only need power alerts? Send /subscribe",
+                uk: "Введіть секретне слово, щоб продовжити.\n\n\
+                     Потрібні лише сповіщення про світло? Надішліть /subscribe",
+            },
+            TemplateId::Authorized => Spec {
+                name: "authorized",
+                vars: &[],
+                en: "You are now authorized!",
+                uk: "Вас авторизовано!",
+            },
+            TemplateId::AuthFailed => Spec {
+                name: "auth_failed",
+                vars: &[],
+                en: "Authorization failed. Please try again.",
+                uk: "Не вдалося авторизуватися. Спробуйте ще раз.",
+            },
+            TemplateId::IncorrectSecret => Spec {
+                name: "incorrect_secret",
+                vars: &[],
+                en: "Incorrect. Please enter the secret word.",
+                uk: "Невірно. Введіть секретне слово.",
+            },
+            TemplateId::Subscribed => Spec {
+                name: "subscribed",
+                vars: &[],
+                en: "🔔 You will get power outage and restoration alerts.\n\
+                     /power — current power state\n\
+                     /unsubscribe — stop alerts",
+                uk: "🔔 Ви отримуватимете сповіщення про відключення і появу світла.\n\
+                     /power — чи є світло зараз\n\
+                     /unsubscribe — відписатися",
+            },
+            TemplateId::Unsubscribed => Spec {
+                name: "unsubscribed",
+                vars: &[],
+                en: "🔕 Power alerts stopped. Send /subscribe to get them again.",
+                uk: "🔕 Сповіщення про світло вимкнено. Надішліть /subscribe, щоб знову їх отримувати.",
+            },
+            TemplateId::SubscriberHelp => Spec {
+                name: "subscriber_help",
+                vars: &[],
+                en: "You are subscribed to power alerts.\n\
+                     /power — current power state\n\
+                     /unsubscribe — stop alerts",
+                uk: "Ви підписані на сповіщення про світло.\n\
+                     /power — чи є світло зараз\n\
+                     /unsubscribe — відписатися",
+            },
+            TemplateId::MainMenu => Spec {
+                name: "main_menu",
+                vars: &[],
+                en: "Main menu:",
+                uk: "Головне меню:",
+            },
+            TemplateId::UseMenu => Spec {
+                name: "use_menu",
+                vars: &[],
+                en: "Use the menu below:",
+                uk: "Скористайтеся меню нижче:",
+            },
+            TemplateId::Help => Spec {
+                name: "help",
+                vars: &[],
+                en: "Available commands:\n\n\
+                     /start — Show main menu\n\
+                     /help — Show this help message\n\
+                     /settings — Notification settings\n\
+                     /status — Current readings\n\
+                     /water <duration> — Water the garden, e.g. /water 12\n\
+                     /stats [period] — Stats, e.g. /stats 7d\n\
+                     /history <metric> [period] — Chart, e.g. /history soil 24h\n\
+                     /outages [period] — Power outages, e.g. /outages 30d\n\
+                     /queue — Queued and recent device commands\n\
+                     /export csv <month> — Readings as CSV, e.g. /export csv 2025-12\n\
+                     /weekly — Weekly garden report\n\
+                     /timezone — Time zone for the morning summary and quiet hours\n\
+                     /tokens — API tokens for scripts and shortcuts\n\
+                     /newtoken <name> <scopes> [expiry] — Create an API token",
+                uk: "Доступні команди:\n\n\
+                     /start — Головне меню\n\
+                     /help — Ця довідка\n\
+                     /settings — Налаштування сповіщень\n\
+                     /status — Поточні показники\n\
+                     /water <duration> — Полити сад, напр. /water 12\n\
+                     /stats [period] — Статистика, напр. /stats 7d\n\
+                     /history <metric> [period] — Графік, напр. /history soil 24h\n\
+                     /outages [period] — Відключення світла, напр. /outages 30d\n\
+                     /queue — Черга та нещодавні команди пристрою\n\
+                     /export csv <month> — Показники у CSV, напр. /export csv 2025-12\n\
+                     /weekly — Тижневий звіт саду\n\
+                     /timezone — Часовий пояс ранкового зведення і тихих годин\n\
+                     /tokens — API-токени для скриптів і швидких команд\n\
+                     /newtoken <назва> <scopes> [термін] — Створити API-токен",
+            },
+            TemplateId::HelpAdmin => Spec {
+                name: "help_admin",
+                vars: &[],
+                en: "\n\nAdmin commands:\n\
+                     /users — List users\n\
+                     /revoke <user> — Remove access\n\
+                     /promote <user> <role> — Change role (admin, operator, viewer)\n\
+                     /broadcast <text> — Message all users\n\
+                     /invite <role> [uses] [hours] [access hours] — Create an invite link\n\
+                     /rotatesecret — Replace the bot secret\n\
+                     /linkgroup — Link a group chat, run in the group\n\
+                     /unlinkgroup — Unlink a group chat",
+                uk: "\n\nКоманди адміністратора:\n\
+                     /users — Список користувачів\n\
+                     /revoke <user> — Забрати доступ\n\
+                     /promote <user> <role> — Змінити роль (admin, operator, viewer)\n\
+                     /broadcast <text> — Повідомлення всім\n\
+                     /invite <role> [uses] [hours] [access hours] — Створити запрошення\n\
+                     /rotatesecret — Замінити секретне слово\n\
+                     /linkgroup — Підключити групу, виконати в групі\n\
+                     /unlinkgroup — Відключити групу",
+            },
+            TemplateId::NotPermitted => Spec {
+                name: "not_permitted",
+                vars: &[],
+                en: "⛔ Your role doesn't allow this",
+                uk: "⛔ Ваша роль цього не дозволяє",
+            },
+            TemplateId::UsersTitle => Spec {
+                name: "users_title",
+                vars: &[("items", Fragment)],
+                en: "👥 Users:{items}",
+                uk: "👥 Користувачі:{items}",
+            },
+            TemplateId::UserItem => Spec {
+                name: "user_item",
+                vars: &[("user", Text), ("role", Fragment), ("until", Fragment)],
+                en: "\n• {user} — {role}{until}",
+                uk: "\n• {user} — {role}{until}",
+            },
+            TemplateId::UserUntil => Spec {
+                name: "user_until",
+                vars: &[("time", Text)],
+                en: " (until {time})",
+                uk: " (до {time})",
+            },
+            TemplateId::RoleViewer => Spec {
+                name: "role_viewer",
+                vars: &[],
+                en: "viewer",
+                uk: "глядач",
+            },
+            TemplateId::RoleOperator => Spec {
+                name: "role_operator",
+                vars: &[],
+                en: "operator",
+                uk: "оператор",
+            },
+            TemplateId::RoleAdmin => Spec {
+                name: "role_admin",
+                vars: &[],
+                en: "admin",
+                uk: "адміністратор",
+            },
+            TemplateId::UserNotFound => Spec {
+                name: "user_not_found",
+                vars: &[("user", Text)],
+                en: "User {user} not found",
+                uk: "Користувача {user} не знайдено",
+            },
+            TemplateId::UserRevoked => Spec {
+                name: "user_revoked",
+                vars: &[("user", Text)],
+                en: "✅ Access revoked for {user}",
+                uk: "✅ {user} більше не має доступу",
+            },
+            TemplateId::UserRoleChanged => Spec {
+                name: "user_role_changed",
+                vars: &[("user", Text), ("role", Fragment)],
+                en: "✅ {user} is now {role}",
+                uk: "✅ {user} тепер {role}",
+            },
+            TemplateId::CannotChangeOwnAccess => Spec {
+                name: "cannot_change_own_access",
+                vars: &[],
+                en: "You can't change your own access",
+                uk: "Не можна змінити власний доступ",
+            },
+            TemplateId::AccessRevoked => Spec {
+                name: "access_revoked",
+                vars: &[],
+                en: "Your access to this bot was revoked",
+                uk: "Ваш доступ до бота скасовано",
+            },
+            TemplateId::RevokeUsage => Spec {
+                name: "revoke_usage",
+                vars: &[],
+                en: "Usage: /revoke <user id or @username>",
+                uk: "Використання: /revoke <id або @username>",
+            },
+            TemplateId::PromoteUsage => Spec {
+                name: "promote_usage",
+                vars: &[],
+                en: "Usage: /promote <user id or @username> <admin|operator|viewer>",
+                uk: "Використання: /promote <id або @username> <admin|operator|viewer>",
+            },
+            TemplateId::BroadcastUsage => Spec {
+                name: "broadcast_usage",
+                vars: &[],
+                en: "Usage: /broadcast <message>",
+                uk: "Використання: /broadcast <повідомлення>",
+            },
+            TemplateId::BroadcastMessage => Spec {
+                name: "broadcast_message",
+                vars: &[("text", Text)],
+                en: "📣 {text}",
+                uk: "📣 {text}",
+            },
+            TemplateId::BroadcastSent => Spec {
+                name: "broadcast_sent",
+                vars: &[("count", Integer)],
+                en: "📣 Sent to {count} users",
+                uk: "📣 Надіслано {count} користувачам",
+            },
+            TemplateId::SecretRotated => Spec {
+                name: "secret_rotated",
+                vars: &[("secret", Text)],
+                en: "🔑 New bot secret: {secret}\nThe old one no longer works",
+                uk: "🔑 Нове секретне слово: {secret}\nСтаре більше не діє",
+            },
+            TemplateId::InviteCreated => Spec {
+                name: "invite_created",
+                vars: &[("role", Fragment), ("uses", Integer), ("expires", Text), ("access", Fragment), ("link", Text)],
+                en: "🎟 Invite for {role}, {uses} use(s), valid until {expires}{access}:\n{link}",
+                uk: "🎟 Запрошення для ролі {role}, використань: {uses}, дійсне до {expires}{access}:\n{link}",
+            },
+            TemplateId::GuestAccess => Spec {
+                name: "guest_access",
+                vars: &[("duration", Fragment)],
+                en: ". Access ends {duration} after joining",
+                uk: ". Доступ закінчиться через {duration} після входу",
+            },
+            TemplateId::InviteUsage => Spec {
+                name: "invite_usage",
+                vars: &[],
+                en: "Usage: /invite <admin|operator|viewer> [uses] [valid hours] [access hours]",
+                uk: "Використання: /invite <admin|operator|viewer> [використань] [годин дії] [годин доступу]",
+            },
+            TemplateId::InviteInvalid => Spec {
+                name: "invite_invalid",
+                vars: &[],
+                en: "This invite link is invalid, expired or already used",
+                uk: "Це запрошення недійсне, прострочене або вже використане",
+            },
+            TemplateId::AuthLocked => Spec {
+                name: "auth_locked",
+                vars: &[("minutes", Integer)],
+                en: "🔒 Too many wrong attempts. Try again in {minutes} min",
+                uk: "🔒 Забагато невдалих спроб. Спробуйте через {minutes} хв",
+            },
+            TemplateId::AuthSuspicious => Spec {
+                name: "auth_suspicious",
+                vars: &[("user", Text), ("attempts", Integer), ("minutes", Integer)],
+                en: "⚠️ {user} made {attempts} wrong authorization attempts and is locked out for {minutes} min",
+                uk: "⚠️ {user}: {attempts} невдалих спроб авторизації, вхід заблоковано на {minutes} хв",
+            },
+            TemplateId::SettingsTitle => Spec {
+                name: "settings_title",
+                vars: &[],
+                en: "⚙️ Notification Settings",
+                uk: "⚙️ Налаштування сповіщень",
+            },
+            TemplateId::WaterSelect => Spec {
+                name: "water_select",
+                vars: &[],
+                en: "💧 Select watering duration:",
+                uk: "💧 Оберіть тривалість поливу:",
+            },
+            TemplateId::WaterCustomPrompt => Spec {
+                name: "water_custom_prompt",
+                vars: &[("min", Integer), ("max", Integer)],
+                en: "💧 Send the watering duration, e.g. 45s or 1m 20s ({min}–{max} seconds). A command or menu button cancels",
+                uk: "💧 Надішліть тривалість поливу, напр. 45с або 1хв 20с ({min}–{max} с). Команда чи кнопка меню скасовує введення",
+            },
+            TemplateId::WaterDurationInvalid => Spec {
+                name: "water_duration_invalid",
+                vars: &[],
+                en: "Couldn't read that duration. Try e.g. 45s or 1m 20s",
+                uk: "Не вдалося розпізнати тривалість. Спробуйте, напр., 45с або 1хв 20с",
+            },
+            TemplateId::WaterDurationOutOfRange => Spec {
+                name: "water_duration_out_of_range",
+                vars: &[("min", Integer), ("max", Integer)],
+                en: "The pump runs from {min} to {max} seconds. Send another duration",
+                uk: "Насос працює від {min} до {max} с. Надішліть іншу тривалість",
+            },
+            TemplateId::WaterConfirm => Spec {
+                name: "water_confirm",
+                vars: &[("seconds", Integer)],
+                en: "💧 Water for {seconds} seconds?",
+                uk: "💧 Поливати {seconds} с?",
+            },
+            TemplateId::MorningHourSelect => Spec {
+                name: "morning_hour_select",
+                vars: &[("timezone", Text)],
+                en: "🕗 When should the morning summary arrive? Time zone: {timezone}, change it with /timezone",
+                uk: "🕗 Коли надсилати ранкове зведення? Часовий пояс: {timezone}, змінити — /timezone",
+            },
+            TemplateId::ButtonHour => Spec {
+                name: "button_hour",
+                vars: &[("hour", Text)],
+                en: "{hour}:00",
+                uk: "{hour}:00",
+            },
+            TemplateId::TimezoneSet => Spec {
+                name: "timezone_set",
+                vars: &[("timezone", Text)],
+                en: "🌍 Time zone set to {timezone}",
+                uk: "🌍 Часовий пояс: {timezone}",
+            },
+            TemplateId::TimezoneUsage => Spec {
+                name: "timezone_usage",
+                vars: &[("timezone", Text)],
+                en: "🌍 Your time zone is {timezone}. Change it with /timezone <name>, e.g. /timezone Europe/Warsaw",
+                uk: "🌍 Ваш часовий пояс: {timezone}. Змінити: /timezone <назва>, напр. /timezone Europe/Warsaw",
+            },
+            TemplateId::TimezoneUnknown => Spec {
+                name: "timezone_unknown",
+                vars: &[("timezone", Text)],
+                en: "Unknown time zone {timezone}. Use a name like Europe/Kyiv",
+                uk: "Невідомий часовий пояс {timezone}. Вкажіть назву на кшталт Europe/Kyiv",
+            },
+            TemplateId::SpanHours => Spec {
+                name: "span_hours",
+                vars: &[("hours", Integer)],
+                en: "last {hours} h",
+                uk: "за останні {hours} год",
+            },
+            TemplateId::SpanDays => Spec {
+                name: "span_days",
+                vars: &[("days", Integer)],
+                en: "last {days} days",
+                uk: "за останні {days} дн.",
+            },
+            TemplateId::StatsPeriod => Spec {
+                name: "stats_period",
+                vars: &[
+                    ("period", Text),
+                    ("min_temp", Temperature),
+                    ("max_temp", Temperature),
+                    ("avg_temp", Temperature),
+                    ("min_humidity", Number),
+                    ("max_humidity", Number),
+                ],
+                en: "📈 Stats, {period}\n\n\
+                     🌡 Temperature:\n\
+                     Min: {min_temp}\n\
+                     Max: {max_temp}\n\
+                     Avg: {avg_temp}\n\n\
+                     💧 Humidity:\n\
+                     Min: {min_humidity}%\n\
+                     Max: {max_humidity}%",
+                uk: "📈 Статистика {period}\n\n\
+                     🌡 Температура:\n\
+                     Мін: {min_temp}\n\
+                     Макс: {max_temp}\n\
+                     Сер: {avg_temp}\n\n\
+                     💧 Вологість:\n\
+                     Мін: {min_humidity}%\n\
+                     Макс: {max_humidity}%",
+            },
+            TemplateId::StatsPeriodNoData => Spec {
+                name: "stats_period_no_data",
+                vars: &[("period", Text)],
+                en: "No data for the {period}",
+                uk: "Немає даних {period}",
+            },
+            TemplateId::OutagesPeriod => Spec {
+                name: "outages_period",
+                vars: &[
+                    ("period", Text),
+                    ("count", Integer),
+                    ("total", Fragment),
+                    ("items", Fragment),
+                ],
+                en: "🔌 Outages, {period}: {count}\n\
+                     ⏱ Total without power: {total}\n\n\
+                     {items}",
+                uk: "🔌 Відключення {period}: {count}\n\
+                     ⏱ Загалом без світла: {total}\n\n\
+                     {items}",
+            },
+            TemplateId::OutagesPeriodNone => Spec {
+                name: "outages_period_none",
+                vars: &[("period", Text)],
+                en: "✅ No outages in the {period}",
+                uk: "✅ Відключень {period} не було",
+            },
+            TemplateId::WaterUsage => Spec {
+                name: "water_usage",
+                vars: &[("min", Integer), ("max", Integer)],
+                en: "Usage: /water <duration>, e.g. /water 12 or /water 1m 30s, from {min} to {max} seconds",
+                uk: "Використання: /water <тривалість>, напр. /water 12 або /water 1хв 30с, від {min} до {max} с",
+            },
+            TemplateId::StatsUsage => Spec {
+                name: "stats_usage",
+                vars: &[],
+                en: "Usage: /stats [period], e.g. /stats 7d. Periods are hours, days or weeks (24h, 7d, 2w), up to a year",
+                uk: "Використання: /stats [період], напр. /stats 7d. Період у годинах, днях чи тижнях (24h, 7d, 2w), до року",
+            },
+            TemplateId::HistoryUsage => Spec {
+                name: "history_usage",
+                vars: &[],
+                en: "Usage: /history <metric> [period], e.g. /history soil 24h\n\
+                     Metrics: temperature, humidity, pressure, soil, water",
+                uk: "Використання: /history <показник> [період], напр. /history soil 24h\n\
+                     Показники: temperature, humidity, pressure, soil, water",
+            },
+            TemplateId::OutagesUsage => Spec {
+                name: "outages_usage",
+                vars: &[],
+                en: "Usage: /outages [period], e.g. /outages 30d",
+                uk: "Використання: /outages [період], напр. /outages 30d",
+            },
+            TemplateId::ExportUsage => Spec {
+                name: "export_usage",
+                vars: &[],
+                en: "Usage: /export csv <month>, e.g. /export csv 2025-12",
+                uk: "Використання: /export csv <місяць>, напр. /export csv 2025-12",
+            },
+            TemplateId::ExportNoData => Spec {
+                name: "export_no_data",
+                vars: &[("month", Text)],
+                en: "No readings for {month}",
+                uk: "Немає показників за {month}",
+            },
+            TemplateId::LiveStatusUpdated => Spec {
+                name: "live_status_updated",
+                vars: &[("time", Text)],
+                en: "\n\n🕒 Updated at {time}",
+                uk: "\n\n🕒 Оновлено о {time}",
+            },
+            TemplateId::LiveStatusOffline => Spec {
+                name: "live_status_offline",
+                vars: &[("time", Text)],
+                en: "\n\n🔴 Offline, no readings since {time}",
+                uk: "\n\n🔴 Офлайн, немає показників з {time}",
+            },
+            TemplateId::LiveStatusStopped => Spec {
+                name: "live_status_stopped",
+                vars: &[],
+                en: "Live status stopped",
+                uk: "Оновлення статусу зупинено",
+            },
+            TemplateId::MiniAppWaterPrompt => Spec {
+                name: "mini_app_water_prompt",
+                vars: &[("min", Integer), ("max", Integer)],
+                en: "Watering duration, {min}–{max} seconds",
+                uk: "Тривалість поливу, {min}–{max} с",
+            },
+            TemplateId::GroupLinked => Spec {
+                name: "group_linked",
+                vars: &[],
+                en: "✅ This group is linked. Members can check the garden with commands and get alerts here; only operators can water",
+                uk: "✅ Групу підключено. Учасники можуть переглядати стан саду командами й отримуватимуть тут сповіщення; поливати можуть лише оператори",
+            },
+            TemplateId::GroupUnlinked => Spec {
+                name: "group_unlinked",
+                vars: &[],
+                en: "This group is unlinked and won't get alerts",
+                uk: "Групу відключено, сповіщення сюди більше не надходитимуть",
+            },
+            TemplateId::GroupNotLinked => Spec {
+                name: "group_not_linked",
+                vars: &[],
+                en: "This group isn't linked to the bot. An admin can run /linkgroup here",
+                uk: "Цю групу не підключено до бота. Адміністратор може виконати тут /linkgroup",
+            },
+            TemplateId::GroupPrivateOnly => Spec {
+                name: "group_private_only",
+                vars: &[],
+                en: "This works in a private chat with the bot",
+                uk: "Це доступно в особистому чаті з ботом",
+            },
+            TemplateId::InlineStatusTitle => Spec {
+                name: "inline_status_title",
+                vars: &[],
+                en: "📊 Current readings",
+                uk: "📊 Поточні показники",
+            },
+            TemplateId::InlineStatusDescription => Spec {
+                name: "inline_status_description",
+                vars: &[
+                    ("temperature", Temperature),
+                    ("soil_moisture", Number),
+                    ("water_level", Number),
+                ],
+                en: "{temperature} · soil {soil_moisture:.0}% · tank {water_level:.0}%",
+                uk: "{temperature} · ґрунт {soil_moisture:.0}% · бак {water_level:.0}%",
+            },
+            TemplateId::QueueTitle => Spec {
+                name: "queue_title",
+                vars: &[("items", Fragment)],
+                en: "📋 Command queue\n\n{items}",
+                uk: "📋 Черга команд\n\n{items}",
+            },
+            TemplateId::QueueEmpty => Spec {
+                name: "queue_empty",
+                vars: &[],
+                en: "📋 The queue is empty and nothing was sent recently",
+                uk: "📋 Черга порожня, нещодавніх команд немає",
+            },
+            TemplateId::QueueItem => Spec {
+                name: "queue_item",
+                vars: &[
+                    ("id", Integer),
+                    ("seconds", Integer),
+                    ("who", Text),
+                    ("age", Fragment),
+                    ("state", Fragment),
+                ],
+                en: "• #{id} 💧 {seconds} s · {who} · {age} ago · {state}\n",
+                uk: "• #{id} 💧 {seconds} с · {who} · {age} тому · {state}\n",
+            },
+            TemplateId::QueueStateQueued => Spec {
+                name: "queue_state_queued",
+                vars: &[],
+                en: "⏳ queued",
+                uk: "⏳ у черзі",
+            },
+            TemplateId::QueueStateSent => Spec {
+                name: "queue_state_sent",
+                vars: &[],
+                en: "✅ sent",
+                uk: "✅ надіслано",
+            },
+            TemplateId::QueueStateCancelled => Spec {
+                name: "queue_state_cancelled",
+                vars: &[],
+                en: "🚫 cancelled",
+                uk: "🚫 скасовано",
+            },
+            TemplateId::CommandCancelled => Spec {
+                name: "command_cancelled",
+                vars: &[("id", Integer)],
+                en: "Command #{id} cancelled",
+                uk: "Команду #{id} скасовано",
+            },
+            TemplateId::CommandCancelTooLate => Spec {
+                name: "command_cancel_too_late",
+                vars: &[],
+                en: "Too late, the device already took it",
+                uk: "Запізно, пристрій уже її отримав",
+            },
+            TemplateId::TokensTitle => Spec {
+                name: "tokens_title",
+                vars: &[("items", Fragment)],
+                en: "🔑 Your API tokens:\n\n{items}\nCreate another with /newtoken <name> <scopes> [expiry]",
+                uk: "🔑 Ваші API-токени:\n\n{items}\nСтворити ще один: /newtoken <назва> <scopes> [термін]",
+            },
+            TemplateId::TokensNone => Spec {
+                name: "tokens_none",
+                vars: &[],
+                en: "🔑 No API tokens yet. Create one with /newtoken <name> <scopes> [expiry], e.g. /newtoken shortcut read,water 90d",
+                uk: "🔑 API-токенів ще немає. Створіть токен: /newtoken <назва> <scopes> [термін], напр. /newtoken shortcut read,water 90d",
+            },
+            TemplateId::TokenItem => Spec {
+                name: "token_item",
+                vars: &[
+                    ("name", Text),
+                    ("scopes", Text),
+                    ("expires", Text),
+                    ("used", Fragment),
+                ],
+                en: "• {name}: {scopes}, valid until {expires}{used}\n",
+                uk: "• {name}: {scopes}, дійсний до {expires}{used}\n",
+            },
+            TemplateId::TokenLastUsed => Spec {
+                name: "token_last_used",
+                vars: &[("time", Text)],
+                en: ", last used {time}",
+                uk: ", востаннє використано {time}",
+            },
+            TemplateId::TokenCreated => Spec {
+                name: "token_created",
+                vars: &[
+                    ("name", Text),
+                    ("scopes", Text),
+                    ("expires", Text),
+                    ("token", Text),
+                ],
+                en: "🔑 Token {name} ({scopes}), valid until {expires}:\n\n\
+                     {token}\n\n\
+                     Save it now, it won't be shown again. Send it in the header\n\
+                     Authorization: Bearer <token>",
+                uk: "🔑 Токен {name} ({scopes}), дійсний до {expires}:\n\n\
+                     {token}\n\n\
+                     Збережіть його зараз, більше його не буде показано. Надсилайте його в заголовку\n\
+                     Authorization: Bearer <token>",
+            },
+            TemplateId::TokenUsage => Spec {
+                name: "token_usage",
+                vars: &[("max_name", Integer), ("days", Integer)],
+                en: "Usage: /newtoken <name> <scopes> [expiry], e.g. /newtoken shortcut read,water 90d. The name is one word up to {max_name} characters. Scopes: read, water, admin. Expiry in days or weeks, up to a year, {days} days by default",
+                uk: "Використання: /newtoken <назва> <scopes> [термін], напр. /newtoken shortcut read,water 90d. Назва — одне слово до {max_name} символів. Scopes: read, water, admin. Термін у днях чи тижнях, до року, типово {days} днів",
+            },
+            TemplateId::TokenScopeNotAllowed => Spec {
+                name: "token_scope_not_allowed",
+                vars: &[("scope", Text)],
+                en: "⛔ Your role can't grant the {scope} scope",
+                uk: "⛔ Ваша роль не дозволяє scope {scope}",
+            },
+            TemplateId::TokenNameTaken => Spec {
+                name: "token_name_taken",
+                vars: &[("name", Text)],
+                en: "You already have a token named {name}. Revoke it in /tokens or pick another name",
+                uk: "Токен з назвою {name} уже є. Відкличте його в /tokens або оберіть іншу назву",
+            },
+            TemplateId::TokenRevoked => Spec {
+                name: "token_revoked",
+                vars: &[],
+                en: "Token revoked",
+                uk: "Токен відкликано",
+            },
+            TemplateId::TokenAlreadyRevoked => Spec {
+                name: "token_already_revoked",
+                vars: &[],
+                en: "Token was already revoked",
+                uk: "Токен уже відкликано",
+            },
+            TemplateId::InputCancelled => Spec {
+                name: "input_cancelled",
+                vars: &[],
+                en: "Input cancelled",
+                uk: "Введення скасовано",
+            },
+            TemplateId::ButtonStatus => Spec {
+                name: "button_status",
+                vars: &[],
+                en: "📊 Status",
+                uk: "📊 Стан",
+            },
+            TemplateId::ButtonWeather => Spec {
+                name: "button_weather",
+                vars: &[],
+                en: "🌤 Weather",
+                uk: "🌤 Погода",
+            },
+            TemplateId::ButtonGarden => Spec {
+                name: "button_garden",
+                vars: &[],
+                en: "🌱 Garden",
+                uk: "🌱 Сад",
+            },
+            TemplateId::ButtonStats => Spec {
+                name: "button_stats",
+                vars: &[],
+                en: "📈 Stats",
+                uk: "📈 Статистика",
+            },
+            TemplateId::ButtonPower => Spec {
+                name: "button_power",
+                vars: &[],
+                en: "⚡ Power",
+                uk: "⚡ Світло",
+            },
+            TemplateId::ButtonWater => Spec {
+                name: "button_water",
+                vars: &[],
+                en: "💧 Water",
+                uk: "💧 Полив",
+            },
+            TemplateId::ButtonSettings => Spec {
+                name: "button_settings",
+                vars: &[],
+                en: "⚙️ Settings",
+                uk: "⚙️ Налаштування",
+            },
+            TemplateId::ButtonOutlook => Spec {
+                name: "button_outlook",
+                vars: &[],
+                en: "🔮 Outlook",
+                uk: "🔮 Прогноз",
+            },
+            TemplateId::ButtonCharts => Spec {
+                name: "button_charts",
+                vars: &[],
+                en: "📉 Charts",
+                uk: "📉 Графіки",
+            },
+            TemplateId::ButtonWaterSeconds => Spec {
+                name: "button_water_seconds",
+                vars: &[("seconds", Integer)],
+                en: "{seconds} sec",
+                uk: "{seconds} с",
+            },
+            TemplateId::ButtonWaterCustom => Spec {
+                name: "button_water_custom",
+                vars: &[],
+                en: "✏️ Custom…",
+                uk: "✏️ Інша…",
+            },
+            TemplateId::ButtonWeeklyReport => Spec {
+                name: "button_weekly_report",
+                vars: &[],
+                en: "📅 Weekly report",
+                uk: "📅 Тижневий звіт",
+            },
+            TemplateId::ButtonPinLiveStatus => Spec {
+                name: "button_pin_live_status",
+                vars: &[],
+                en: "📌 Pin live status",
+                uk: "📌 Закріпити живий статус",
+            },
+            TemplateId::ButtonStopLiveStatus => Spec {
+                name: "button_stop_live_status",
+                vars: &[],
+                en: "⏹ Stop updating",
+                uk: "⏹ Зупинити оновлення",
+            },
+            TemplateId::ButtonDashboard => Spec {
+                name: "button_dashboard",
+                vars: &[],
+                en: "🌱 Dashboard",
+                uk: "🌱 Панель",
+            },
+            TemplateId::ButtonQueue => Spec {
+                name: "button_queue",
+                vars: &[],
+                en: "📋 Queue",
+                uk: "📋 Черга",
+            },
+            TemplateId::ButtonCancelCommand => Spec {
+                name: "button_cancel_command",
+                vars: &[("id", Integer), ("seconds", Integer)],
+                en: "❌ Cancel #{id} ({seconds} s)",
+                uk: "❌ Скасувати #{id} ({seconds} с)",
+            },
+            TemplateId::ButtonRefresh => Spec {
+                name: "button_refresh",
+                vars: &[],
+                en: "🔄 Refresh",
+                uk: "🔄 Оновити",
+            },
+            TemplateId::ButtonRevokeToken => Spec {
+                name: "button_revoke_token",
+                vars: &[("name", Text)],
+                en: "🗑 Revoke {name}",
+                uk: "🗑 Відкликати {name}",
+            },
+            TemplateId::ButtonConfirm => Spec {
+                name: "button_confirm",
+                vars: &[],
+                en: "✅ Confirm",
+                uk: "✅ Підтвердити",
+            },
+            TemplateId::ButtonCancel => Spec {
+                name: "button_cancel",
+                vars: &[],
+                en: "« Cancel",
+                uk: "« Скасувати",
+            },
+            TemplateId::ButtonBack => Spec {
+                name: "button_back",
+                vars: &[],
+                en: "« Back",
+                uk: "« Назад",
+            },
+            TemplateId::ButtonPeriodDay => Spec {
+                name: "button_period_day",
+                vars: &[],
+                en: "Day",
+                uk: "Доба",
+            },
+            TemplateId::ButtonPeriodWeek => Spec {
+                name: "button_period_week",
+                vars: &[],
+                en: "Week",
+                uk: "Тиждень",
+            },
+            TemplateId::ButtonPeriodMonth => Spec {
+                name: "button_period_month",
+                vars: &[],
+                en: "Month",
+                uk: "Місяць",
+            },
+            TemplateId::ButtonPowerHistory => Spec {
+                name: "button_power_history",
+                vars: &[],
+                en: "📜 History",
+                uk: "📜 Історія",
+            },
+            TemplateId::SettingSoil => Spec {
+                name: "setting_soil",
+                vars: &[("icon", Text)],
+                en: "{icon} Soil moisture",
+                uk: "{icon} Вологість ґрунту",
+            },
+            TemplateId::SettingTemperature => Spec {
+                name: "setting_temperature",
+                vars: &[("icon", Text)],
+                en: "{icon} Temperature",
+                uk: "{icon} Температура",
+            },
+            TemplateId::SettingPower => Spec {
+                name: "setting_power",
+                vars: &[("icon", Text)],
+                en: "{icon} Power outage",
+                uk: "{icon} Відключення світла",
+            },
+            TemplateId::SettingQuietHours => Spec {
+                name: "setting_quiet_hours",
+                vars: &[("icon", Text), ("start", Text), ("end", Text)],
+                en: "{icon} Quiet hours ({start}-{end})",
+                uk: "{icon} Тихі години ({start}-{end})",
+            },
+            TemplateId::SettingOutlook => Spec {
+                name: "setting_outlook",
+                vars: &[("icon", Text)],
+                en: "{icon} Outage heads-up",
+                uk: "{icon} Попередження про можливе відключення",
+            },
+            TemplateId::SettingMorningSummary => Spec {
+                name: "setting_morning_summary",
+                vars: &[("icon", Text)],
+                en: "{icon} Morning summary",
+                uk: "{icon} Ранкове зведення",
+            },
+            TemplateId::SettingMorningHour => Spec {
+                name: "setting_morning_hour",
+                vars: &[("hour", Text)],
+                en: "🕗 {hour}:00",
+                uk: "🕗 {hour}:00",
+            },
+            TemplateId::SettingWeeklyReport => Spec {
+                name: "setting_weekly_report",
+                vars: &[("icon", Text)],
+                en: "{icon} Weekly report",
+                uk: "{icon} Тижневий звіт",
+            },
+            TemplateId::SettingLanguage => Spec {
+                name: "setting_language",
+                vars: &[],
+                en: "🌐 Language: English",
+                uk: "🌐 Мова: українська",
+            },
+            TemplateId::NotAuthorized => Spec {
+                name: "not_authorized",
+                vars: &[],
+                en: "Not authorized",
+                uk: "Немає доступу",
+            },
+            TemplateId::WaterQueued => Spec {
+                name: "water_queued",
+                vars: &[("seconds", Integer)],
+                en: "💧 Watering for {seconds} seconds queued!",
+                uk: "💧 Полив на {seconds} с додано в чергу!",
+            },
+            TemplateId::WaterQueueFailed => Spec {
+                name: "water_queue_failed",
+                vars: &[],
+                en: "Failed to queue command",
+                uk: "Не вдалося додати команду",
+            },
+            TemplateId::SoilAlerts => Spec {
+                name: "soil_alerts",
+                vars: &[],
+                en: "Soil alerts",
+                uk: "Сповіщення про ґрунт",
+            },
+            TemplateId::TemperatureAlerts => Spec {
+                name: "temperature_alerts",
+                vars: &[],
+                en: "Temperature alerts",
+                uk: "Сповіщення про температуру",
+            },
+            TemplateId::PowerAlerts => Spec {
+                name: "power_alerts",
+                vars: &[],
+                en: "Power alerts",
+                uk: "Сповіщення про світло",
+            },
+            TemplateId::OutlookAlerts => Spec {
+                name: "outlook_alerts",
+                vars: &[],
+                en: "Outage heads-ups",
+                uk: "Попередження про відключення",
+            },
+            TemplateId::MorningSummaries => Spec {
+                name: "morning_summaries",
+                vars: &[],
+                en: "Morning summary",
+                uk: "Ранкове зведення",
+            },
+            TemplateId::WeeklyReports => Spec {
+                name: "weekly_reports",
+                vars: &[],
+                en: "Weekly report",
+                uk: "Тижневий звіт",
+            },
+            TemplateId::QuietHours => Spec {
+                name: "quiet_hours",
+                vars: &[],
+                en: "Quiet hours",
+                uk: "Тихі години",
+            },
+            TemplateId::SettingEnabled => Spec {
+                name: "setting_enabled",
+                vars: &[("name", Text)],
+                en: "{name} enabled",
+                uk: "{name}: увімкнено",
+            },
+            TemplateId::SettingDisabled => Spec {
+                name: "setting_disabled",
+                vars: &[("name", Text)],
+                en: "{name} disabled",
+                uk: "{name}: вимкнено",
+            },
+            TemplateId::UpdateFailed => Spec {
+                name: "update_failed",
+                vars: &[],
+                en: "Failed to update",
+                uk: "Не вдалося оновити",
+            },
+            TemplateId::LanguageChanged => Spec {
+                name: "language_changed",
+                vars: &[],
+                en: "🇬🇧 Language set to English",
+                uk: "🇺🇦 Мову змінено на українську",
+            },
+            TemplateId::SoilVeryDry => Spec {
+                name: "soil_very_dry",
+                vars: &[],
+                en: "Very dry - water now!",
+                uk: "Дуже сухо — полийте зараз!",
+            },
+            TemplateId::SoilGettingDry => Spec {
+                name: "soil_getting_dry",
+                vars: &[],
+                en: "Getting dry",
+                uk: "Підсихає",
+            },
+            TemplateId::SoilGood => Spec {
+                name: "soil_good",
+                vars: &[],
+                en: "Good",
+                uk: "Добре",
+            },
+            TemplateId::SoilVeryWet => Spec {
+                name: "soil_very_wet",
+                vars: &[],
+                en: "Very wet",
+                uk: "Дуже волого",
+            },
+            TemplateId::WaterLow => Spec {
+                name: "water_low",
+                vars: &[],
+                en: "Low - refill needed",
+                uk: "Мало — треба долити",
+            },
+            TemplateId::WaterGettingLow => Spec {
+                name: "water_getting_low",
+                vars: &[],
+                en: "Getting low",
+                uk: "Закінчується",
+            },
+            TemplateId::WaterOk => Spec {
+                name: "water_ok",
+                vars: &[],
+                en: "OK",
+                uk: "Достатньо",
+            },
+            TemplateId::TrendFallingFast => Spec {
+                name: "trend_falling_fast",
+                vars: &[],
+                en: "falling fast",
+                uk: "швидко падає",
+            },
+            TemplateId::TrendFalling => Spec {
+                name: "trend_falling",
+                vars: &[],
+                en: "falling",
+                uk: "падає",
+            },
+            TemplateId::TrendStable => Spec {
+                name: "trend_stable",
+                vars: &[],
+                en: "stable",
+                uk: "стабільний",
+            },
+            TemplateId::TrendRising => Spec {
+                name: "trend_rising",
+                vars: &[],
+                en: "rising",
+                uk: "зростає",
+            },
+            TemplateId::TrendRisingFast => Spec {
+                name: "trend_rising_fast",
+                vars: &[],
+                en: "rising fast",
+                uk: "швидко зростає",
+            },
+            TemplateId::ForecastStorm => Spec {
+                name: "forecast_storm",
+                vars: &[],
+                en: "Storm likely",
+                uk: "Ймовірна гроза",
+            },
+            TemplateId::ForecastRain => Spec {
+                name: "forecast_rain",
+                vars: &[],
+                en: "Rain possible",
+                uk: "Можливий дощ",
+            },
+            TemplateId::ForecastClear => Spec {
+                name: "forecast_clear",
+                vars: &[],
+                en: "Clear weather",
+                uk: "Ясна погода",
+            },
+            TemplateId::ForecastNoChange => Spec {
+                name: "forecast_no_change",
+                vars: &[],
+                en: "No significant change",
+                uk: "Без суттєвих змін",
+            },
+            TemplateId::DurationMinutes => Spec {
+                name: "duration_minutes",
+                vars: &[("minutes", Integer)],
+                en: "{minutes} min",
+                uk: "{minutes} хв",
+            },
+            TemplateId::DurationHours => Spec {
+                name: "duration_hours",
+                vars: &[("hours", Integer)],
+                en: "{hours} h",
+                uk: "{hours} год",
+            },
+            TemplateId::DurationHoursMinutes => Spec {
+                name: "duration_hours_minutes",
+                vars: &[("hours", Integer), ("minutes", Integer)],
+                en: "{hours} h {minutes} min",
+                uk: "{hours} год {minutes} хв",
+            },
+            TemplateId::UnitCelsius => Spec {
+                name: "unit_celsius",
+                vars: &[("value", Text)],
+                en: "{value}°C",
+                uk: "{value}°C",
+            },
+            TemplateId::UnitFahrenheit => Spec {
+                name: "unit_fahrenheit",
+                vars: &[("value", Text)],
+                en: "{value}°F",
+                uk: "{value}°F",
+            },
+            TemplateId::UnitHpa => Spec {
+                name: "unit_hpa",
+                vars: &[("value", Text)],
+                en: "{value} hPa",
+                uk: "{value} гПа",
+            },
+            TemplateId::UnitMmHg => Spec {
+                name: "unit_mm_hg",
+                vars: &[("value", Text)],
+                en: "{value} mmHg",
+                uk: "{value} мм рт. ст.",
+            },
+            TemplateId::UnitInHg => Spec {
+                name: "unit_in_hg",
+                vars: &[("value", Text)],
+                en: "{value} inHg",
+                uk: "{value} дюйм рт. ст.",
+            },
+            TemplateId::UnitLiters => Spec {
+                name: "unit_liters",
+                vars: &[("value", Text)],
+                en: "{value} L",
+                uk: "{value} л",
+            },
+            TemplateId::UnitGallons => Spec {
+                name: "unit_gallons",
+                vars: &[("value", Text)],
+                en: "{value} gal",
+                uk: "{value} гал",
+            },
+            TemplateId::SettingTemperatureUnit => Spec {
+                name: "setting_temperature_unit",
+                vars: &[("unit", Text)],
+                en: "🌡 Temperature in {unit}",
+                uk: "🌡 Температура в {unit}",
+            },
+            TemplateId::SettingPressureUnit => Spec {
+                name: "setting_pressure_unit",
+                vars: &[("unit", Text)],
+                en: "🌪 Pressure in {unit}",
+                uk: "🌪 Тиск в {unit}",
+            },
+            TemplateId::SettingVolumeUnit => Spec {
+                name: "setting_volume_unit",
+                vars: &[("unit", Text)],
+                en: "💦 Volume in {unit}",
+                uk: "💦 Об'єм в {unit}",
+            },
+        }
+    }
+}
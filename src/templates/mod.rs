@@ -0,0 +1,595 @@
+mod catalog;
+
+use std::collections::HashMap;
+
+use teloxide::{
+    payloads::{EditMessageText, SendMessage},
+    prelude::*,
+    requests::JsonRequest,
+    types::{InputMessageContent, InputMessageContentText, MessageId, ParseMode},
+};
+
+use crate::db::{Db, Locale, NotificationSettings};
+use crate::services::{BatteryStatus, Units};
+
+pub use catalog::TemplateId;
+
+/// Type of a template variable, checked when templates are loaded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VarKind {
+    Number,
+    Integer,
+    Text,
+    /// Output of another template, inserted without escaping
+    Fragment,
+    /// Measurements rendered in the recipient's units, with the unit symbol
+    Temperature,
+    Pressure,
+    Volume,
+}
+
+impl VarKind {
+    fn is_numeric(self) -> bool {
+        !matches!(self, VarKind::Text | VarKind::Fragment)
+    }
+}
+
+pub enum Value {
+    Number(f32),
+    Integer(i64),
+    Text(String),
+    Fragment(String),
+    /// °C
+    Temperature(f32),
+    /// hPa, also used for pressure deltas
+    Pressure(f32),
+    /// Liters
+    Volume(f32),
+}
+
+impl Value {
+    fn kind(&self) -> VarKind {
+        match self {
+            Value::Number(_) => VarKind::Number,
+            Value::Integer(_) => VarKind::Integer,
+            Value::Text(_) => VarKind::Text,
+            Value::Fragment(_) => VarKind::Fragment,
+            Value::Temperature(_) => VarKind::Temperature,
+            Value::Pressure(_) => VarKind::Pressure,
+            Value::Volume(_) => VarKind::Volume,
+        }
+    }
+}
+
+impl From<f32> for Value {
+    fn from(v: f32) -> Self {
+        Value::Number(v)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(v: i32) -> Self {
+        Value::Integer(v as i64)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(v: &str) -> Self {
+        Value::Text(v.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(v: String) -> Self {
+        Value::Text(v)
+    }
+}
+
+/// Telegram formatting used for rendered messages
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MessageFormat {
+    #[default]
+    Plain,
+    MarkdownV2,
+    Html,
+}
+
+impl MessageFormat {
+    pub fn from_env() -> anyhow::Result<Self> {
+        match std::env::var("MESSAGE_FORMAT").ok().as_deref() {
+            None | Some("") | Some("plain") => Ok(MessageFormat::Plain),
+            Some("markdown_v2") | Some("markdownv2") => Ok(MessageFormat::MarkdownV2),
+            Some("html") => Ok(MessageFormat::Html),
+            Some(other) => anyhow::bail!("Unknown MESSAGE_FORMAT: {}", other),
+        }
+    }
+
+    pub fn parse_mode(self) -> Option<ParseMode> {
+        match self {
+            MessageFormat::Plain => None,
+            MessageFormat::MarkdownV2 => Some(ParseMode::MarkdownV2),
+            MessageFormat::Html => Some(ParseMode::Html),
+        }
+    }
+
+    pub fn escape(self, text: &str) -> String {
+        match self {
+            MessageFormat::Plain => text.to_string(),
+            MessageFormat::MarkdownV2 => {
+                let mut out = String::with_capacity(text.len());
+                for c in text.chars() {
+                    if "_*[]()~`>#+-=|{}.!\\".contains(c) {
+                        out.push('\\');
+                    }
+                    out.push(c);
+                }
+                out
+            }
+            MessageFormat::Html => text
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Segment {
+    Literal(String),
+    Var {
+        name: String,
+        sign: bool,
+        precision: Option<usize>,
+    },
+}
+
+#[derive(Clone, Debug)]
+struct Template {
+    segments: Vec<Segment>,
+    /// Built-in bodies are plain text and get escaped for the configured format;
+    /// overrides are written in that format already.
+    builtin: bool,
+}
+
+pub struct Templates {
+    format: MessageFormat,
+    templates: HashMap<(Locale, TemplateId), Template>,
+}
+
+impl Templates {
+    /// Built-in templates overridden by `TEMPLATES_FILE` (a JSON object of
+    /// name -> body, names prefixed with `uk.` for Ukrainian) and then by
+    /// rows in `message_templates`.
+    pub async fn load(db: &Db) -> anyhow::Result<Self> {
+        let format = MessageFormat::from_env()?;
+        let mut templates = Self::builtin(format);
+        let mut errors = Vec::new();
+
+        if let Ok(path) = std::env::var("TEMPLATES_FILE") {
+            let raw = std::fs::read_to_string(&path)
+                .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path, e))?;
+            let overrides: HashMap<String, String> = serde_json::from_str(&raw)
+                .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", path, e))?;
+            for (key, body) in overrides {
+                let (locale, name) = match key.split_once('.') {
+                    Some((code, name)) => (code, name),
+                    None => (Locale::En.code(), key.as_str()),
+                };
+                if let Err(e) = templates.set_override(locale, name, &body) {
+                    errors.push(format!("{} ({}): {}", key, path, e));
+                }
+            }
+        }
+
+        for row in db.get_message_templates().await? {
+            if let Err(e) = templates.set_override(&row.locale, &row.name, &row.body) {
+                errors.push(format!(
+                    "{}.{} (message_templates): {}",
+                    row.locale, row.name, e
+                ));
+            }
+        }
+
+        if !errors.is_empty() {
+            anyhow::bail!("Invalid message templates:\n{}", errors.join("\n"));
+        }
+
+        Ok(templates)
+    }
+
+    pub fn builtin(format: MessageFormat) -> Self {
+        let mut templates = HashMap::new();
+
+        for &locale in Locale::ALL {
+            for &id in TemplateId::ALL {
+                let segments = parse(id, id.default_body(locale)).unwrap_or_else(|e| {
+                    panic!("Built-in template {}.{}: {}", locale.code(), id.name(), e)
+                });
+                templates.insert(
+                    (locale, id),
+                    Template {
+                        segments,
+                        builtin: true,
+                    },
+                );
+            }
+        }
+
+        Self { format, templates }
+    }
+
+    fn set_override(&mut self, locale: &str, name: &str, body: &str) -> Result<(), String> {
+        let locale = Locale::from_code(locale).ok_or("unknown locale")?;
+        let id = TemplateId::from_name(name).ok_or("unknown template")?;
+        let segments = parse(id, body)?;
+        self.templates.insert(
+            (locale, id),
+            Template {
+                segments,
+                builtin: false,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn locale(&self, locale: Locale) -> Localized<'_> {
+        Localized {
+            templates: self,
+            format: self.format,
+            locale,
+            units: Units::default(),
+        }
+    }
+
+    /// Locale and units from a user's settings
+    pub fn for_user(&self, settings: &NotificationSettings) -> Localized<'_> {
+        Localized {
+            templates: self,
+            format: self.format,
+            locale: settings.locale,
+            units: settings.units(),
+        }
+    }
+}
+
+/// Templates of one locale and set of units, as seen by a single recipient
+#[derive(Clone, Copy)]
+pub struct Localized<'a> {
+    templates: &'a Templates,
+    format: MessageFormat,
+    locale: Locale,
+    units: Units,
+}
+
+impl Localized<'_> {
+    /// Renders as HTML regardless of the bot's message format, for web pages
+    pub fn html(self) -> Self {
+        Self {
+            format: MessageFormat::Html,
+            ..self
+        }
+    }
+
+    pub fn text(&self, id: TemplateId) -> String {
+        self.render(id, &[])
+    }
+
+    pub fn render(&self, id: TemplateId, vars: &[(&str, Value)]) -> String {
+        self.render_as(self.format, id, vars)
+    }
+
+    /// Renders without markup, for keyboard labels and callback answers
+    pub fn plain(&self, id: TemplateId, vars: &[(&str, Value)]) -> String {
+        self.render_as(MessageFormat::Plain, id, vars)
+    }
+
+    fn render_as(&self, format: MessageFormat, id: TemplateId, vars: &[(&str, Value)]) -> String {
+        let template = &self.templates.templates[&(self.locale, id)];
+        let mut out = String::new();
+
+        for segment in &template.segments {
+            match segment {
+                Segment::Literal(text) if template.builtin => {
+                    out.push_str(&format.escape(text));
+                }
+                Segment::Literal(text) => out.push_str(text),
+                Segment::Var {
+                    name,
+                    sign,
+                    precision,
+                } => {
+                    let Some((_, value)) = vars.iter().find(|(n, _)| n == name) else {
+                        eprintln!("Template {} rendered without {}", id.name(), name);
+                        continue;
+                    };
+                    debug_assert!(
+                        id.vars()
+                            .iter()
+                            .any(|(n, kind)| n == name && *kind == value.kind()),
+                        "Template {} got wrong type for {}",
+                        id.name(),
+                        name
+                    );
+
+                    self.push_value(&mut out, format, value, *sign, *precision);
+                }
+            }
+        }
+
+        out
+    }
+
+    /// A single value formatted like a template variable, in the recipient's units
+    pub fn value(&self, value: Value) -> String {
+        let mut out = String::new();
+        self.push_value(&mut out, self.format, &value, false, None);
+        out
+    }
+
+    fn push_value(
+        &self,
+        out: &mut String,
+        format: MessageFormat,
+        value: &Value,
+        sign: bool,
+        precision: Option<usize>,
+    ) {
+        let units = self.units;
+        let (number, unit_precision, unit) = match value {
+            Value::Integer(v) if sign => {
+                out.push_str(&format.escape(&format!("{:+}", v)));
+                return;
+            }
+            Value::Integer(v) => {
+                out.push_str(&format.escape(&v.to_string()));
+                return;
+            }
+            Value::Text(v) => {
+                out.push_str(&format.escape(v));
+                return;
+            }
+            Value::Fragment(v) => {
+                out.push_str(v);
+                return;
+            }
+            Value::Number(v) => (*v, 1, None),
+            Value::Temperature(v) => (
+                units.temperature.convert(*v),
+                units.temperature.precision(),
+                Some(units.temperature.template()),
+            ),
+            Value::Pressure(v) => (
+                units.pressure.convert(*v),
+                units.pressure.precision(),
+                Some(units.pressure.template()),
+            ),
+            Value::Volume(v) => (
+                units.volume.convert(*v),
+                units.volume.precision(),
+                Some(units.volume.template()),
+            ),
+        };
+
+        let precision = precision.unwrap_or(unit_precision);
+        let number = if sign {
+            format!("{:+.*}", precision, number)
+        } else {
+            format!("{:.*}", precision, number)
+        };
+
+        match unit {
+            Some(unit) => out.push_str(&self.render_as(format, unit, &[("value", number.into())])),
+            None => out.push_str(&format.escape(&number)),
+        }
+    }
+
+    pub fn label(&self, id: TemplateId) -> String {
+        self.plain(id, &[])
+    }
+
+    /// Unit template rendered without a value, e.g. "°F" or "mmHg"
+    pub fn unit_symbol(&self, unit: TemplateId) -> String {
+        self.plain(unit, &[("value", "".into())]).trim().to_string()
+    }
+
+    pub fn units(&self) -> Units {
+        self.units
+    }
+
+    pub fn duration(&self, minutes: i32) -> String {
+        if minutes < 60 {
+            self.render(TemplateId::DurationMinutes, &[("minutes", minutes.into())])
+        } else {
+            let hours = minutes / 60;
+            let mins = minutes % 60;
+            if mins == 0 {
+                self.render(TemplateId::DurationHours, &[("hours", hours.into())])
+            } else {
+                self.render(
+                    TemplateId::DurationHoursMinutes,
+                    &[("hours", hours.into()), ("minutes", mins.into())],
+                )
+            }
+        }
+    }
+
+    /// Battery line prefixed with a newline, or nothing without battery telemetry
+    pub fn battery(&self, status: Option<&BatteryStatus>) -> String {
+        let Some(status) = status else {
+            return String::new();
+        };
+        let line = match status.runtime_minutes {
+            Some(minutes) => self.render(
+                TemplateId::BatteryStateRuntime,
+                &[
+                    ("battery", status.percent.into()),
+                    ("runtime", Value::Fragment(self.duration(minutes))),
+                ],
+            ),
+            None => self.render(
+                TemplateId::BatteryState,
+                &[("battery", status.percent.into())],
+            ),
+        };
+        format!("\n{}", line)
+    }
+
+    /// `send_message` with the parse mode rendered templates are written in
+    pub fn send_message(
+        &self,
+        bot: &Bot,
+        chat_id: ChatId,
+        text: String,
+    ) -> JsonRequest<SendMessage> {
+        let request = bot.send_message(chat_id, text);
+        match self.format.parse_mode() {
+            Some(mode) => request.parse_mode(mode),
+            None => request,
+        }
+    }
+
+    pub fn edit_message_text(
+        &self,
+        bot: &Bot,
+        chat_id: ChatId,
+        message_id: MessageId,
+        text: String,
+    ) -> JsonRequest<EditMessageText> {
+        let request = bot.edit_message_text(chat_id, message_id, text);
+        match self.format.parse_mode() {
+            Some(mode) => request.parse_mode(mode),
+            None => request,
+        }
+    }
+
+    /// Message content for inline query results, with the same parse mode
+    pub fn message_content(&self, text: String) -> InputMessageContent {
+        InputMessageContent::Text(InputMessageContentText {
+            parse_mode: self.format.parse_mode(),
+            ..InputMessageContentText::new(text)
+        })
+    }
+}
+
+/// Parses `{name}` / `{name:+.2}` placeholders, `{{` and `}}` are literal braces.
+fn parse(id: TemplateId, body: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = body.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '}' => return Err("unmatched '}'".to_string()),
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => return Err("unclosed '{'".to_string()),
+                    }
+                }
+
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                segments.push(parse_placeholder(id, &placeholder)?);
+            }
+            c => literal.push(c),
+        }
+    }
+
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+
+    Ok(segments)
+}
+
+fn parse_placeholder(id: TemplateId, placeholder: &str) -> Result<Segment, String> {
+    let (name, spec) = match placeholder.split_once(':') {
+        Some((name, spec)) => (name.trim(), Some(spec.trim())),
+        None => (placeholder.trim(), None),
+    };
+
+    let Some(&(_, kind)) = id.vars().iter().find(|(n, _)| *n == name) else {
+        return Err(format!("unknown variable '{}'", name));
+    };
+
+    let mut sign = false;
+    let mut precision = None;
+
+    if let Some(spec) = spec {
+        if !kind.is_numeric() {
+            return Err(format!("'{}' is not numeric and takes no format", name));
+        }
+        let rest = match spec.strip_prefix('+') {
+            Some(rest) => {
+                sign = true;
+                rest
+            }
+            None => spec,
+        };
+        if let Some(digits) = rest.strip_prefix('.') {
+            if kind == VarKind::Integer {
+                return Err(format!("'{}' is an integer and takes no precision", name));
+            }
+            precision = Some(
+                digits
+                    .parse::<usize>()
+                    .map_err(|_| format!("bad precision in '{}'", placeholder))?,
+            );
+        } else if !rest.is_empty() {
+            return Err(format!("bad format in '{}'", placeholder));
+        }
+    }
+
+    Ok(Segment::Var {
+        name: name.to_string(),
+        sign,
+        precision,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_templates_parse() {
+        Templates::builtin(MessageFormat::Plain);
+    }
+
+    #[test]
+    fn placeholders() {
+        let segments = parse(TemplateId::BatteryState, "{{{battery:+.1}}}%").unwrap();
+        assert!(matches!(&segments[0], Segment::Literal(s) if s == "{"));
+        assert!(matches!(
+            &segments[1],
+            Segment::Var { name, sign: true, precision: Some(1) } if name == "battery"
+        ));
+        assert!(matches!(&segments[2], Segment::Literal(s) if s == "}%"));
+    }
+
+    #[test]
+    fn rejects_bad_placeholders() {
+        let id = TemplateId::PowerOutageExpected;
+        assert!(parse(id, "{minutes}").is_ok());
+        assert!(parse(id, "{minutes:+}").is_ok());
+        assert!(parse(id, "{hours}").is_err());
+        assert!(parse(id, "{minutes:.2}").is_err());
+        assert!(parse(id, "{start:.2}").is_err());
+        assert!(parse(id, "{minutes:x}").is_err());
+        assert!(parse(TemplateId::BatteryState, "{battery:.x}").is_err());
+        assert!(parse(id, "{minutes").is_err());
+        assert!(parse(id, "minutes}").is_err());
+    }
+}
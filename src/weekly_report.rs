@@ -0,0 +1,44 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use teloxide::{prelude::*, types::ChatId};
+use tokio::time::interval;
+
+use crate::bot::build_weekly_report;
+use crate::config::weekly;
+use crate::db::Db;
+use crate::templates::Templates;
+
+/// Sends the weekly report on Sunday evening in each user's time zone, unless they opted out
+pub fn spawn_weekly_report(bot: Arc<Bot>, db: Db, templates: Arc<Templates>) {
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(weekly::CHECK_INTERVAL_SECS));
+
+        loop {
+            interval.tick().await;
+
+            let user_ids = match db
+                .take_due_weekly_reports(weekly::WEEKDAY, weekly::HOUR)
+                .await
+            {
+                Ok(user_ids) => user_ids,
+                Err(e) => {
+                    eprintln!("Failed to find due weekly reports: {}", e);
+                    continue;
+                }
+            };
+
+            for user_id in user_ids {
+                let settings = db
+                    .get_notification_settings(user_id)
+                    .await
+                    .unwrap_or_default();
+                let t = templates.for_user(&settings);
+                let report = build_weekly_report(&db, &t).await;
+                if let Err(e) = t.send_message(&bot, ChatId(user_id), report).await {
+                    eprintln!("Failed to send weekly report to {}: {}", user_id, e);
+                }
+            }
+        }
+    });
+}